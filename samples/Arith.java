@@ -0,0 +1,74 @@
+public class Arith {
+  public static void main(String[] args) {
+    int total = 0;
+    for (int i = 1; i <= 10; i++) {
+      total += fib(i);
+    }
+    if (total != 143) {
+      throw new IllegalStateException();
+    }
+  }
+
+  static int fib(int n) {
+    return n < 2 ? n : fib(n - 1) + fib(n - 2);
+  }
+
+  static long factorial(int n) {
+    long result = 1;
+    while (n > 1) {
+      result *= n--;
+    }
+    return result;
+  }
+
+  static double norm2(double a, double b) {
+    return a * a + b * b;
+  }
+
+  static float average(float a, float b, float c) {
+    return (a + b + c) / 3;
+  }
+
+  static int gcd(int a, int b) {
+    while (b != 0) {
+      int t = a % b;
+      a = b;
+      b = t;
+    }
+    return a;
+  }
+
+  static long mix(int i, long l, float f, double d) {
+    long bits = (l << 3) ^ (l >>> 7) ^ (i >> 2);
+    return bits + (long) f + (long) d + (short) i + (byte) i + (char) i;
+  }
+
+  static int compare(double a, double b) {
+    if (a < b) {
+      return -1;
+    } else if (a > b) {
+      return 1;
+    } else if (a == b) {
+      return 0;
+    }
+    return 2;
+  }
+
+  static int season(int month) {
+    switch (month) {
+      case 12: case 1: case 2: return 0;
+      case 3: case 4: case 5: return 1;
+      case 6: case 7: case 8: return 2;
+      case 9: case 10: case 11: return 3;
+      default: return -1;
+    }
+  }
+
+  static int divide(int a, int b) {
+    return a / b;
+  }
+
+  static int overflow() {
+    return Integer.MAX_VALUE + 1;
+  }
+}
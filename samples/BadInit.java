@@ -0,0 +1,13 @@
+public class BadInit {
+  static int[] table = build(3);
+
+  static int[] build(int size) {
+    int[] table = new int[size];
+    table[size] = 1;
+    return table;
+  }
+
+  public static void main(String[] args) {
+    System.out.println(table.length);
+  }
+}
@@ -0,0 +1,39 @@
+public class Builtins {
+  public static void main(String[] args) {
+    System.out.println("Hello from rusvm");
+    System.out.println(42);
+    System.out.println(-7L);
+    System.out.println(1.5f);
+    System.out.println(1e10);
+    System.out.println(0.001);
+    System.out.println(100.0 / 3);
+    System.out.println(true);
+    System.out.println('x');
+    System.out.print("no newline");
+    System.out.println();
+
+    StringBuilder sb = new StringBuilder("sum=");
+    sb.append(Integer.sum(2, 3)).append(' ').append(Math.max(1.5, 2.5)).append(false);
+    System.out.println(sb.toString());
+    System.out.println(sb.length());
+    System.out.println(new StringBuilder("stressed").reverse());
+
+    String s = "hello";
+    System.out.println(s.length());
+    System.out.println(s.charAt(1));
+    System.out.println(s.concat(" world"));
+    System.out.println(s.equals("hel".concat("lo")));
+    System.out.println(s.hashCode());
+
+    System.out.println(Integer.parseInt("-123") + 1);
+    System.out.println(Integer.toHexString(255));
+    System.out.println(Integer.valueOf(7));
+    Integer small = 127, sameSmall = 127, large = 128, sameLarge = 128;
+    System.out.println(small == sameSmall);
+    System.out.println(large == sameLarge);
+    System.out.println(Math.abs(-3) + Math.floorMod(-7, 3));
+    System.out.println(Math.sqrt(2.0));
+    System.out.println(String.valueOf(3.0f));
+    System.err.println("to stderr");
+  }
+}
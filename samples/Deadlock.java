@@ -0,0 +1,35 @@
+/** Two threads taking two locks in opposite order, which never finishes on a JVM */
+public class Deadlock {
+  static void pause() {
+    try {
+      Thread.sleep(10);
+    } catch (InterruptedException e) {
+      return;
+    }
+  }
+
+  public static void main(String[] args) throws InterruptedException {
+    Object a = new Object();
+    Object b = new Object();
+    Thread first = new Thread(() -> {
+      synchronized (a) {
+        pause();
+        synchronized (b) {
+          System.out.println("first");
+        }
+      }
+    });
+    Thread second = new Thread(() -> {
+      synchronized (b) {
+        pause();
+        synchronized (a) {
+          System.out.println("second");
+        }
+      }
+    });
+    first.start();
+    second.start();
+    first.join();
+    second.join();
+  }
+}
@@ -0,0 +1,145 @@
+public class Dispatch {
+  abstract static class Shape {
+    abstract int area();
+
+    String name() {
+      return "shape";
+    }
+
+    String describe() {
+      return name() + " " + area();
+    }
+  }
+
+  static class Square extends Shape {
+    int side;
+
+    Square(int side) {
+      this.side = side;
+    }
+
+    int area() {
+      return side * side;
+    }
+
+    String name() {
+      return "square<" + super.name() + ">";
+    }
+  }
+
+  static class Cube extends Square {
+    Cube(int side) {
+      super(side);
+    }
+
+    int area() {
+      return 6 * super.area();
+    }
+
+    String name() {
+      return "cube:" + super.name();
+    }
+  }
+
+  interface Named {
+    String name();
+
+    default String greet() {
+      return "hello " + name();
+    }
+  }
+
+  interface Polite extends Named {
+    default String greet() {
+      return "dear " + Named.super.greet();
+    }
+  }
+
+  interface Left extends Named {
+    default String side() {
+      return "left";
+    }
+  }
+
+  interface Right extends Named {
+    default String side() {
+      return "right";
+    }
+  }
+
+  static class Person implements Named {
+    public String name() {
+      return "ada";
+    }
+  }
+
+  static class Diamond implements Left, Right, Polite {
+    public String name() {
+      return "diamond";
+    }
+
+    public String side() {
+      return Left.super.side() + "+" + Right.super.side();
+    }
+  }
+
+  interface Counter {
+    int count();
+
+    private int twice() {
+      return 2 * count();
+    }
+
+    default int doubled() {
+      return twice();
+    }
+  }
+
+  static class Base implements Counter {
+    private String id() {
+      return "base";
+    }
+
+    String show() {
+      return id();
+    }
+
+    public int count() {
+      return 21;
+    }
+  }
+
+  static class Derived extends Base {
+    String id() {
+      return "derived";
+    }
+  }
+
+  public static void main(String[] args) {
+    Shape[] shapes = {new Square(3), new Cube(2)};
+    for (Shape shape : shapes) {
+      System.out.println(shape.describe());
+    }
+
+    Named[] named = {new Person(), new Diamond()};
+    for (Named n : named) {
+      System.out.println(n.greet());
+    }
+    Left left = new Diamond();
+    System.out.println(left.side());
+    System.out.println(left.equals(left));
+
+    Derived derived = new Derived();
+    System.out.println(derived.show() + " " + derived.id());
+    Counter counter = derived;
+    System.out.println(counter.doubled());
+
+    Plugin plugin = new Legacy();
+    System.out.println(plugin.name());
+    try {
+      System.out.println(plugin.version());
+    } catch (AbstractMethodError e) {
+      System.out.println(e.getMessage());
+    }
+  }
+}
@@ -0,0 +1,113 @@
+public class Exceptions {
+  static class Point {
+    int x;
+  }
+
+  static class InsufficientFunds extends RuntimeException {
+    int missing;
+
+    InsufficientFunds(int missing) {
+      super("missing " + missing);
+      this.missing = missing;
+    }
+  }
+
+  static int withdraw(int balance, int amount) {
+    if (amount > balance) {
+      throw new InsufficientFunds(amount - balance);
+    }
+    return balance - amount;
+  }
+
+  static int divide(int a, int b) {
+    try {
+      return a / b;
+    } finally {
+      System.out.println("finally " + a);
+    }
+  }
+
+  static String classify(Object value) {
+    try {
+      Integer number = (Integer) value;
+      return "integer " + number;
+    } catch (ClassCastException e) {
+      return "not an integer";
+    }
+  }
+
+  static void rethrow() throws Exception {
+    try {
+      withdraw(1, 5);
+    } catch (InsufficientFunds e) {
+      throw new Exception("wrapped", e);
+    }
+  }
+
+  static int depth;
+
+  static void recurse() {
+    depth++;
+    recurse();
+  }
+
+  public static void main(String[] args) {
+    System.out.println(withdraw(10, 3));
+    try {
+      withdraw(10, 30);
+    } catch (RuntimeException e) {
+      System.out.println(e.getMessage() + " " + ((InsufficientFunds) e).missing);
+    }
+
+    System.out.println(divide(7, 2));
+    try {
+      divide(1, 0);
+    } catch (ArithmeticException e) {
+      System.out.println(e);
+    }
+
+    int[] numbers = new int[2];
+    try {
+      numbers[2] = 1;
+    } catch (IndexOutOfBoundsException e) {
+      System.out.println(e.getMessage());
+    }
+
+    Point point = null;
+    try {
+      point.x = 1;
+    } catch (NullPointerException e) {
+      System.out.println("npe caught");
+    }
+
+    System.out.println(classify(Integer.valueOf(4)));
+    System.out.println(classify("four"));
+    Object o = numbers;
+    System.out.println((o instanceof int[]) + " " + (o instanceof Object[]) + " " + (o instanceof Cloneable));
+
+    try {
+      rethrow();
+    } catch (Exception e) {
+      System.out.println(e.getMessage() + " <- " + e.getCause().getMessage());
+    }
+
+    Object[] objects = new String[1];
+    try {
+      objects[0] = Integer.valueOf(1);
+    } catch (ArrayStoreException e) {
+      System.out.println(e);
+    }
+
+    try {
+      throw new Error();
+    } catch (Throwable t) {
+      System.out.println(t + " " + t.getMessage());
+    }
+
+    try {
+      recurse();
+    } catch (StackOverflowError e) {
+      System.out.println(e + " " + (depth > 1000));
+    }
+  }
+}
@@ -0,0 +1,80 @@
+import java.util.function.IntBinaryOperator;
+
+public class Features implements Comparable<Features> {
+  static final long BIG = 1L << 40;
+  static final double HALF = 0.5;
+  static final String GREETING = "héllo \u0000 😀";
+
+  private final int value;
+  protected volatile transient float weight;
+
+  public Features(int value) {
+    this.value = value;
+  }
+
+  @Override
+  public int compareTo(Features other) {
+    return Integer.compare(value, other.value);
+  }
+
+  static String name(int day) {
+    switch (day) {
+      case 1: return "mon";
+      case 2: return "tue";
+      case 3: return "wed";
+      default: return "other";
+    }
+  }
+
+  static int sparse(int key) {
+    switch (key) {
+      case -100: return 1;
+      case 7: return 2;
+      case 100000: return 3;
+      default: return 0;
+    }
+  }
+
+  static int safeDiv(int a, int b) {
+    try {
+      return a / b;
+    } catch (ArithmeticException e) {
+      return 0;
+    } finally {
+      System.out.println("done");
+    }
+  }
+
+  static int wide() {
+    long a = 1, b = 2, c = 3, d = 4, e = 5, f = 6, g = 7, h = 8;
+    double i = 9, j = 10, k = 11, l = 12, m = 13, n = 14, o = 15, p = 16;
+    double q = 17, r = 18, s = 19, t = 20, u = 21, v = 22, w = 23, x = 24;
+    double y = 25, z = 26, aa = 27, bb = 28, cc = 29, dd = 30, ee = 31, ff = 32;
+    double gg = 33, hh = 34, ii = 35, jj = 36, kk = 37, ll = 38, mm = 39, nn = 40;
+    double oo = 41, pp = 42, qq = 43, rr = 44, ss = 45, tt = 46, uu = 47, vv = 48;
+    double ww = 49, xx = 50, yy = 51, zz = 52, ab = 53, ac = 54, ad = 55, ae = 56;
+    double af = 57, ag = 58, ah = 59, ai = 60, aj = 61, ak = 62, al = 63, am = 64;
+    double an = 65, ao = 66, ap = 67, aq = 68, ar = 69, as = 70, at = 71, au = 72;
+    double av = 73, aw = 74, ax = 75, ay = 76, az = 77, ba = 78, bc = 79, bd = 80;
+    int count = 0;
+    count += 1000;
+    return count + (int) (a + h + bd);
+  }
+
+  static int apply(int a, int b) {
+    IntBinaryOperator add = (x, y) -> x + y;
+    return add.applyAsInt(a, b);
+  }
+
+  static String concat(String s, int n) {
+    return s + n + GREETING;
+  }
+
+  static int[][] grid(int n) {
+    int[][] cells = new int[n][n];
+    for (int i = 0; i < n; i++) {
+      cells[i][i] = i;
+    }
+    return cells;
+  }
+}
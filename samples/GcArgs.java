@@ -0,0 +1,23 @@
+/**
+ * A static initializer allocating enough to collect the heap before main, or a
+ * static method, gets the arguments passed to it
+ */
+public class GcArgs {
+  static Object last;
+
+  static {
+    for (int i = 0; i < 5000; i++) {
+      last = new Object();
+    }
+  }
+
+  static String echo(String value) {
+    return value;
+  }
+
+  public static void main(String[] args) {
+    for (String arg : args) {
+      System.out.println(arg);
+    }
+  }
+}
@@ -0,0 +1,98 @@
+public class Init {
+  static StringBuilder log = new StringBuilder();
+
+  static void log(String event) {
+    log.append(event).append(' ');
+  }
+
+  static class Parent {
+    static int parentValue = compute("parent", 1);
+  }
+
+  static class Child extends Parent {
+    static final int LIMIT = 100;
+    static final String NAME = "child";
+    static final long BIG = 1L << 40;
+    static int childValue = compute("child", parentValue + 1);
+
+    static int read() {
+      return childValue;
+    }
+  }
+
+  static class Lazy {
+    static int value = compute("lazy", 7);
+  }
+
+  interface Greeting {
+    int ID = compute("greeting", 3);
+
+    default String greet() {
+      return "hi";
+    }
+  }
+
+  interface Marker {
+    int ID = compute("marker", 4);
+  }
+
+  static class Greeter implements Greeting, Marker {
+    static int value = compute("greeter", 5);
+  }
+
+  static class Broken {
+    static int value = 10 / zero();
+
+    static int get() {
+      return value;
+    }
+  }
+
+  static class Fatal {
+    static {
+      if (zero() == 0) {
+        throw new StackOverflowError("too deep");
+      }
+    }
+
+    static void touch() {}
+  }
+
+  static int zero() {
+    return 0;
+  }
+
+  static int compute(String name, int value) {
+    log(name);
+    return value;
+  }
+
+  public static void main(String[] args) {
+    log("main");
+    System.out.println(Child.read());
+    System.out.println(log);
+
+    Lazy[] none = new Lazy[2];
+    System.out.println(none.length + " " + log);
+    System.out.println(Lazy.value + " " + log);
+
+    new Greeter();
+    System.out.println(log);
+
+    try {
+      System.out.println(Broken.get());
+    } catch (ExceptionInInitializerError e) {
+      System.out.println(e.getMessage() + " caused by " + e.getCause());
+    }
+    try {
+      System.out.println(Broken.get());
+    } catch (NoClassDefFoundError e) {
+      System.out.println(e);
+    }
+    try {
+      Fatal.touch();
+    } catch (StackOverflowError e) {
+      System.out.println(e);
+    }
+  }
+}
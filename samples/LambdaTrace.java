@@ -0,0 +1,8 @@
+import java.util.function.Function;
+
+public class LambdaTrace {
+  public static void main(String[] args) {
+    Function<String, Integer> parse = s -> Integer.parseInt(s) / 0;
+    System.out.println(parse.apply("4"));
+  }
+}
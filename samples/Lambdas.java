@@ -0,0 +1,118 @@
+import java.util.Comparator;
+import java.util.function.BiFunction;
+import java.util.function.Function;
+import java.util.function.IntBinaryOperator;
+import java.util.function.Predicate;
+import java.util.function.Supplier;
+import java.util.function.ToIntFunction;
+import java.util.function.UnaryOperator;
+
+public class Lambdas {
+  interface Shape {
+    double area();
+  }
+
+  static class Box {
+    private final String label;
+
+    Box(String label) {
+      this.label = label;
+    }
+
+    String label() {
+      return label;
+    }
+
+    @Override
+    public String toString() {
+      return "Box(" + label + ")";
+    }
+  }
+
+  private int base = 100;
+
+  static int twice(int x) {
+    return 2 * x;
+  }
+
+  int addBase(int x) {
+    return base + x;
+  }
+
+  public static void main(String[] args) {
+    Runnable hello = () -> System.out.println("hello from a lambda");
+    hello.run();
+
+    IntBinaryOperator add = (a, b) -> a + b;
+    System.out.println("add: " + add.applyAsInt(3, 4));
+
+    int offset = 10;
+    Function<Integer, Integer> shift = x -> x + offset;
+    System.out.println("shift: " + shift.apply(5));
+
+    IntBinaryOperator max = Math::max;
+    System.out.println("max: " + max.applyAsInt(7, 9));
+
+    Function<Integer, Integer> twice = Lambdas::twice;
+    System.out.println("twice: " + twice.apply(21));
+
+    Lambdas self = new Lambdas();
+    Function<Integer, Integer> bound = self::addBase;
+    System.out.println("bound: " + bound.apply(1));
+
+    ToIntFunction<String> length = String::length;
+    System.out.println("length: " + length.applyAsInt("lambda"));
+
+    Function<String, Integer> boxedLength = String::length;
+    System.out.println("boxed length: " + boxedLength.apply("four"));
+
+    Function<String, Box> boxer = Box::new;
+    Box box = boxer.apply("b");
+    System.out.println("box: " + box + " " + box.label());
+
+    Supplier<String> supplier = () -> "supplied " + offset;
+    System.out.println(supplier.get());
+
+    Predicate<String> empty = String::isEmpty;
+    System.out.println("empty: " + empty.test("") + " " + empty.test("x"));
+
+    UnaryOperator<String> upper = s -> s + "!";
+    System.out.println(upper.apply("wow"));
+
+    BiFunction<String, Integer, String> repeat = (s, n) -> {
+      StringBuilder sb = new StringBuilder();
+      for (int i = 0; i < n; i++) {
+        sb.append(s);
+      }
+      return sb.toString();
+    };
+    System.out.println(repeat.apply("ab", 3));
+
+    Comparator<Integer> descending = (a, b) -> b - a;
+    System.out.println("compare: " + descending.compare(1, 2));
+
+    Shape circle = () -> 3.0 * 2 * 2;
+    System.out.println("area: " + circle.area());
+
+    long big = 1L << 40;
+    char c = 'x';
+    boolean flag = true;
+    double d = 1.5;
+    Object nothing = null;
+    System.out.println("concat: " + big + " " + c + " " + flag + " " + d + " " + nothing + " " + box);
+
+    for (int i = 0; i < 3; i++) {
+      Supplier<String> counter = () -> "i=" + offset;
+      System.out.print(counter.get() + (i < 2 ? ", " : "\n"));
+    }
+
+    Runnable failing = () -> {
+      throw new IllegalStateException("from lambda");
+    };
+    try {
+      failing.run();
+    } catch (IllegalStateException e) {
+      System.out.println("caught: " + e.getMessage());
+    }
+  }
+}
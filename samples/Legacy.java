@@ -0,0 +1,7 @@
+// Compiled against a Plugin that didn't declare version() yet, so calling version()
+// on a Legacy throws AbstractMethodError. javac rejects it against the current Plugin.
+public class Legacy implements Plugin {
+  public String name() {
+    return "legacy";
+  }
+}
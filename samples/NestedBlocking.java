@@ -0,0 +1,35 @@
+/** A static initializer waiting for a lock another thread holds, which a JVM runs once the lock is released */
+public class NestedBlocking {
+  static final Object lock = new Object();
+
+  static class Late {
+    static {
+      synchronized (lock) {
+        System.out.println("initialized");
+      }
+    }
+
+    static void touch() {}
+  }
+
+  static void pause(long millis) {
+    try {
+      Thread.sleep(millis);
+    } catch (InterruptedException e) {
+      return;
+    }
+  }
+
+  public static void main(String[] args) throws InterruptedException {
+    Thread holder = new Thread(() -> {
+      synchronized (lock) {
+        pause(20);
+        System.out.println("released");
+      }
+    });
+    holder.start();
+    pause(5);
+    Late.touch();
+    holder.join();
+  }
+}
@@ -0,0 +1,114 @@
+public class Objects {
+  static int[] squares;
+  static Node kept;
+
+  static class Point {
+    int x;
+    int y;
+    String label = "point";
+
+    Point(int x, int y) {
+      this.x = x;
+      this.y = y;
+    }
+
+    int sum() {
+      return x + y;
+    }
+  }
+
+  static class Point3D extends Point {
+    int z;
+    // shadows Point.label
+    String label = "point3d";
+
+    Point3D(int x, int y, int z) {
+      super(x, y);
+      this.z = z;
+    }
+
+    int sum() {
+      return super.sum() + z;
+    }
+  }
+
+  static class Node {
+    long value;
+    Node next;
+
+    Node(long value, Node next) {
+      this.value = value;
+      this.next = next;
+    }
+  }
+
+  static int at(int[] array, int index) {
+    return array[index];
+  }
+
+  static int x(Point point) {
+    return point.x;
+  }
+
+  public static void main(String[] args) {
+    System.out.println(args.length);
+
+    Point3D p = new Point3D(1, 2, 3);
+    p.x += 10;
+    Point q = p;
+    System.out.println(p.sum());
+    System.out.println(p.label + " " + q.label + " " + ((Point) p).y);
+
+    squares = new int[5];
+    for (int i = 0; i < squares.length; i++) {
+      squares[i] = i * i;
+    }
+    int total = 0;
+    for (int square : squares) {
+      total += square;
+    }
+    System.out.println(total);
+
+    byte[] bytes = new byte[2];
+    bytes[0] = (byte) 200;
+    char[] chars = {'r', 'u', 's', 't'};
+    short[] shorts = {(short) 70000};
+    boolean[] flags = new boolean[3];
+    flags[1] = true;
+    long[] longs = {1L << 40};
+    float[] floats = {0.5f};
+    double[] doubles = new double[1];
+    doubles[0] = floats[0] * 3;
+    System.out.println(bytes[0] + " " + bytes[1] + " " + chars[3] + " " + shorts[0]);
+    System.out.println(flags[0] + " " + flags[1] + " " + longs[0] + " " + doubles[0]);
+
+    int[][] grid = new int[3][4];
+    grid[2][3] = 7;
+    String[][] names = new String[2][];
+    names[1] = new String[] {"a", "b"};
+    System.out.println(grid.length + " " + grid[2].length + " " + grid[2][3]);
+    System.out.println(names[0] + " " + names[1][1]);
+
+    char[] copy = new char[6];
+    System.arraycopy(chars, 0, copy, 1, 4);
+    System.arraycopy(copy, 1, copy, 2, 3);
+    for (int i = 1; i < 5; i++) {
+      System.out.print(copy[i]);
+    }
+    System.out.println();
+
+    // garbage: only the last list stays reachable
+    for (int round = 0; round < 50; round++) {
+      Node head = null;
+      for (int i = 0; i < 1000; i++) {
+        head = new Node(i, head);
+      }
+      kept = head;
+    }
+    long sum = 0;
+    for (Node node = kept; node != null; node = node.next) {
+      sum += node.value;
+    }
+    System.out.println(sum);
+  }
+}
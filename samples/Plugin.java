@@ -0,0 +1,5 @@
+public interface Plugin {
+  String name();
+
+  int version();
+}
@@ -0,0 +1,62 @@
+import java.util.function.IntConsumer;
+
+/**
+ * A producer and a consumer logging every item they pass through a one item
+ * slot. Which of them prints first depends on how the threads are scheduled.
+ */
+public class ProducerConsumer {
+  private int item;
+  private boolean full;
+
+  synchronized void put(int value) throws InterruptedException {
+    while (full) {
+      wait();
+    }
+    item = value;
+    full = true;
+    System.out.println(Thread.currentThread().getName() + " put " + value);
+    notify();
+  }
+
+  synchronized int take() throws InterruptedException {
+    while (!full) {
+      wait();
+    }
+    full = false;
+    System.out.println(Thread.currentThread().getName() + " took " + item);
+    notify();
+    return item;
+  }
+
+  static Thread loop(String name, int count, IntConsumer body) {
+    return new Thread(() -> {
+      for (int i = 1; i <= count; i++) {
+        body.accept(i);
+      }
+    }, name);
+  }
+
+  public static void main(String[] args) throws InterruptedException {
+    ProducerConsumer slot = new ProducerConsumer();
+    int[] sum = new int[1];
+    Thread producer = loop("producer", 5, i -> {
+      try {
+        slot.put(i * i);
+      } catch (InterruptedException e) {
+        throw new IllegalStateException(e);
+      }
+    });
+    Thread consumer = loop("consumer", 5, i -> {
+      try {
+        sum[0] += slot.take();
+      } catch (InterruptedException e) {
+        throw new IllegalStateException(e);
+      }
+    });
+    producer.start();
+    consumer.start();
+    producer.join();
+    consumer.join();
+    System.out.println("sum " + sum[0]);
+  }
+}
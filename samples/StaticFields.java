@@ -0,0 +1,21 @@
+/**
+ * A static field is looked up in the class, then in its superinterfaces, then in
+ * its superclasses: B.X is the X of Sup, not the X of the I that Sup2 implements
+ */
+public class StaticFields {
+  interface I {
+    int X = 1;
+  }
+
+  static class Sup2 implements I {}
+
+  static class Sup extends Sup2 {
+    static int X = 2;
+  }
+
+  static class B extends Sup {}
+
+  public static void main(String[] args) {
+    System.out.println(B.X);
+  }
+}
@@ -0,0 +1,57 @@
+public class Strings {
+  static final String GREETING = "hello";
+
+  static class Other {
+    static String greeting() {
+      return "hello";
+    }
+  }
+
+  /** A lone surrogate, which modified UTF-8 encodes like any other char */
+  static String lone() {
+    return "\uD800x";
+  }
+
+  /** Lone surrogates in the recipe of a concatenation and in one of its constants */
+  static String loneConcat(int n) {
+    return "\uDC00" + n + "\u0001\uD800";
+  }
+
+  static String kind(String command) {
+    switch (command) {
+      case "start":
+        return "go";
+      case "stop":
+        return "halt";
+      default:
+        return "unknown";
+    }
+  }
+
+  public static void main(String[] args) {
+    String lo = "lo";
+    String built = "hel" + lo;
+    System.out.println((GREETING == Other.greeting()) + " " + (built == GREETING));
+    System.out.println(built.equals(GREETING) + " " + (built.intern() == GREETING));
+    String copy = new String(GREETING);
+    System.out.println((copy == GREETING) + " " + (copy.intern() == GREETING));
+    System.out.println(GREETING.hashCode() + " " + built.hashCode() + " " + "".hashCode());
+
+    String unicode = "héllo wörld ✓ 😀";
+    System.out.println(unicode + " " + unicode.length());
+    System.out.println((int) unicode.charAt(1) + " " + (int) unicode.charAt(14) + " " + (int) unicode.charAt(15));
+    String nul = "a\u0000b";
+    System.out.println(nul.length() + " " + (int) nul.charAt(1));
+
+    System.out.println(GREETING.concat(", ").concat("world") + " " + (GREETING.concat("") == GREETING));
+    char[] letters = {'j', 'v', 'm'};
+    System.out.println(new String(letters) + new String() + " " + 'x' + 1 + 2.5 + true + null);
+
+    StringBuilder sb = new StringBuilder();
+    for (int i = 0; i < 3; i++) {
+      sb.append(i).append(',');
+    }
+    System.out.println(sb + " " + sb.length());
+    System.out.println(kind("start") + " " + kind("stop") + " " + kind("pause"));
+  }
+}
@@ -0,0 +1,179 @@
+public class Threads {
+  /** A bounded queue of ints */
+  static class Buffer {
+    private final int[] items;
+    private int head;
+    private int tail;
+    private int size;
+
+    Buffer(int capacity) {
+      items = new int[capacity];
+    }
+
+    synchronized void put(int item) throws InterruptedException {
+      while (size == items.length) {
+        wait();
+      }
+      items[tail] = item;
+      tail = (tail + 1) % items.length;
+      size++;
+      notifyAll();
+    }
+
+    synchronized int take() throws InterruptedException {
+      while (size == 0) {
+        wait();
+      }
+      int item = items[head];
+      head = (head + 1) % items.length;
+      size--;
+      notifyAll();
+      return item;
+    }
+  }
+
+  static class Counter {
+    private int count;
+
+    void increment() {
+      synchronized (this) {
+        count++;
+      }
+    }
+
+    synchronized int get() {
+      return count;
+    }
+  }
+
+  static class Sleeper extends Thread {
+    private final int millis;
+    private final StringBuilder log;
+
+    Sleeper(String name, int millis, StringBuilder log) {
+      super(name);
+      this.millis = millis;
+      this.log = log;
+    }
+
+    @Override
+    public void run() {
+      try {
+        Thread.sleep(millis);
+      } catch (InterruptedException e) {
+        return;
+      }
+      synchronized (log) {
+        log.append(' ').append(getName());
+      }
+    }
+  }
+
+  static class Failing extends Thread {
+    Failing() {
+      super("failing");
+    }
+
+    @Override
+    public void run() {
+      throw new IllegalStateException("boom");
+    }
+  }
+
+  static int total;
+
+  static synchronized void add(int n) {
+    total += n;
+  }
+
+  public static void main(String[] args) throws InterruptedException {
+    Buffer buffer = new Buffer(2);
+    int[] sum = new int[1];
+    Thread producer = new Thread(() -> {
+      try {
+        for (int i = 1; i <= 20; i++) {
+          buffer.put(i);
+        }
+        buffer.put(-1);
+      } catch (InterruptedException e) {
+        return;
+      }
+    }, "producer");
+    Thread consumer = new Thread(() -> {
+      try {
+        int item;
+        while ((item = buffer.take()) != -1) {
+          sum[0] += item;
+        }
+      } catch (InterruptedException e) {
+        return;
+      }
+    }, "consumer");
+    consumer.start();
+    producer.start();
+    producer.join();
+    consumer.join();
+    System.out.println("consumed " + sum[0]);
+    System.out.println("alive " + producer.isAlive() + " " + consumer.isAlive());
+
+    Counter counter = new Counter();
+    Thread[] incrementers = new Thread[4];
+    for (int i = 0; i < incrementers.length; i++) {
+      incrementers[i] = new Thread(() -> {
+        for (int j = 0; j < 1000; j++) {
+          counter.increment();
+          add(1);
+        }
+      });
+      incrementers[i].start();
+    }
+    for (Thread incrementer : incrementers) {
+      incrementer.join();
+    }
+    System.out.println("count " + counter.get() + " " + total);
+
+    StringBuilder log = new StringBuilder();
+    Thread[] sleepers = {
+      new Sleeper("slow", 60, log), new Sleeper("fast", 10, log), new Sleeper("medium", 30, log)
+    };
+    for (Thread sleeper : sleepers) {
+      sleeper.start();
+    }
+    for (Thread sleeper : sleepers) {
+      sleeper.join();
+    }
+    System.out.println("woke" + log);
+
+    Thread unnamed = new Thread(() -> {});
+    System.out.println(Thread.currentThread().getName() + " " + unnamed.getName() + " " + unnamed.isAlive());
+    unnamed.start();
+    unnamed.join();
+    System.out.println(unnamed + " " + unnamed.isAlive());
+
+    Object lock = new Object();
+    try {
+      lock.notify();
+    } catch (IllegalMonitorStateException e) {
+      System.out.println("notify: " + e.getMessage());
+    }
+    try {
+      lock.wait();
+    } catch (IllegalMonitorStateException e) {
+      System.out.println("wait: " + e.getMessage());
+    }
+    synchronized (lock) {
+      lock.wait(20);
+      System.out.println("wait timed out");
+    }
+    try {
+      producer.start();
+    } catch (IllegalThreadStateException e) {
+      System.out.println("started twice");
+    }
+
+    Thread failing = new Failing();
+    failing.start();
+    failing.join();
+    System.out.println("done");
+  }
+}
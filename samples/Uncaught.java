@@ -0,0 +1,21 @@
+public class Uncaught {
+  static int depth(int n) {
+    if (n == 0) {
+      return 10 / n;
+    }
+    return depth(n - 1);
+  }
+
+  static void load() {
+    try {
+      depth(2);
+    } catch (ArithmeticException e) {
+      throw new IllegalStateException("could not load", e);
+    }
+  }
+
+  public static void main(String[] args) {
+    System.out.println("loading");
+    load();
+  }
+}
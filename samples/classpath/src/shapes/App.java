@@ -0,0 +1,19 @@
+package shapes;
+
+import util.Strings;
+
+public class App extends Base {
+    public static int sides() {
+        return 4;
+    }
+
+    public static void main(String[] args) {
+        new App();
+        System.out.println(Strings.shout(describe(sides())));
+        System.out.println(Strings.shout(describe(Base.sides())));
+    }
+
+    static void neverCalled() {
+        Unused.run();
+    }
+}
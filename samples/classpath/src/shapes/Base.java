@@ -0,0 +1,11 @@
+package shapes;
+
+public class Base {
+    public static int sides() {
+        return 0;
+    }
+
+    public static String describe(int sides) {
+        return "a shape with " + sides + " sides";
+    }
+}
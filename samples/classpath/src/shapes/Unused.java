@@ -0,0 +1,7 @@
+package shapes;
+
+public class Unused {
+    public static void run() {
+        System.out.println("unused");
+    }
+}
@@ -0,0 +1,7 @@
+package util;
+
+public class Strings {
+    public static String shout(String text) {
+        return text + "!";
+    }
+}
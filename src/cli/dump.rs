@@ -0,0 +1,33 @@
+use rusvm::{parsers::bytecode::disassemble, Attr, JavaClassFile};
+
+pub fn dump_class_file(class: &JavaClassFile) {
+    println!("Magic: 0x{:x}", class.magic);
+    println!("Minor Version: 0x{:x}", class.minor);
+    println!(
+        "Major Version: 0x{:x} ({}{})",
+        class.major,
+        class.java_release().unwrap_or("unknown release"),
+        if class.is_preview() { ", preview" } else { "" }
+    );
+    println!("Access Flags: {:?}", class.access_flags);
+    println!(
+        "This Class : {:?}\nSuper Class: {:?}",
+        class.this_class, class.super_class
+    );
+    println!("Interfaces: {:?}", class.interfaces);
+    println!("{:#?}", class.constant_pool);
+    println!("{:#?}", class.fields);
+    println!("{:#?}", class.methods);
+    println!("{:#?}", class.attrs);
+
+    for method in &class.methods {
+        for attr in &method.attrs {
+            if let Attr::Code { code, .. } = attr {
+                println!("\n{}{}", method.name, method.descriptor);
+                for line in disassemble(code, &class.constant_pool) {
+                    println!("{line}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    io::{self, Write},
+    process::exit,
+};
+
+use rusvm::{JavaClassFile, Query, QueryResult, QueryType};
+
+use crate::cli::dump::dump_class_file;
+
+pub fn prompt(class_file: &JavaClassFile, query: Query) {
+    println!(
+        r"
+        =====================================
+
+             Welcome to RusVM v.0.1 alpha
+
+          [1] => Search a method
+          [2] => List all methods
+          [3] => dump entire class file
+          [4] => Search a field
+          [5] => List all fields
+          [6] => List implemented interfaces
+          [7] => Disassemble a method
+
+"
+    );
+
+    let mut user_input = String::new();
+    print!("Option > ");
+    io::stdout()
+        .flush()
+        .expect("[ERROR]: Failed to flush stdout");
+    io::stdin()
+        .read_line(&mut user_input)
+        .expect("[ERROR]: Provide an input");
+
+    match user_input.trim() {
+        "1" => {
+            let mut user_input = String::new();
+            print!("Method Name > ");
+            io::stdout()
+                .flush()
+                .expect("[ERROR]: Failed to flush stdout");
+            io::stdin()
+                .read_line(&mut user_input)
+                .expect("[ERROR]: Provide an input");
+
+            user_input = user_input.trim().to_string();
+
+            let method = query.query(QueryType::QMethod(user_input.clone()));
+            if method.is_none() {
+                eprintln!("[ERROR]: method {user_input} not found");
+                exit(1)
+            }
+            println!("\nMethod\n");
+            println!("{:#?}\n", method.unwrap());
+        }
+        "2" => {
+            println!("\nList of avalible methods\n");
+            println!("{:#?}\n", query.query(QueryType::QMethodList).unwrap());
+        }
+        "3" => dump_class_file(class_file),
+        "4" => {
+            let mut user_input = String::new();
+            print!("Field Name > ");
+            io::stdout()
+                .flush()
+                .expect("[ERROR]: Failed to flush stdout");
+            io::stdin()
+                .read_line(&mut user_input)
+                .expect("[ERROR]: Provide an input");
+
+            user_input = user_input.trim().to_string();
+
+            let field = query.query(QueryType::QField(user_input.clone()));
+            if field.is_none() {
+                eprintln!("[ERROR]: field {user_input} not found");
+                exit(1)
+            }
+            println!("\nField\n");
+            println!("{:#?}\n", field.unwrap());
+        }
+        "5" => {
+            println!("\nList of avalible fields\n");
+            println!("{:#?}\n", query.query(QueryType::QFieldList).unwrap());
+        }
+        "6" => {
+            println!("\nList of implemented interfaces\n");
+            println!("{:#?}\n", query.query(QueryType::QInterfaceList).unwrap());
+        }
+        "7" => {
+            let mut user_input = String::new();
+            print!("Method Name > ");
+            io::stdout()
+                .flush()
+                .expect("[ERROR]: Failed to flush stdout");
+            io::stdin()
+                .read_line(&mut user_input)
+                .expect("[ERROR]: Provide an input");
+
+            user_input = user_input.trim().to_string();
+
+            match query.query(QueryType::QDisassemble(user_input.clone())) {
+                Some(QueryResult::QDisassembly(lines)) => {
+                    println!("\n{user_input}\n");
+                    for line in lines {
+                        println!("{line}");
+                    }
+                }
+                _ => {
+                    eprintln!("[ERROR]: method {user_input} not found or has no code");
+                    exit(1)
+                }
+            }
+        }
+        _ => {
+            eprintln!("[ERROR]: Invalid Option: {user_input}");
+            exit(1);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! `rusvm` is a Java class file parser and a virtual machine that runs the parsed classes.
+//!
+//! [`JavaClassFileParser`] turns the bytes of a `.class` file into a [`JavaClassFile`],
+//! whose constant pool, fields, methods and attributes can then be inspected directly
+//! or through [`Query`]. [`ClassWriter`] serialises a `JavaClassFile` back to bytes.
+//!
+//! [`Vm`] interprets the bytecode of the classes added to it, and loads the classes they
+//! use through a [`ClassLoader`] reading a classpath of directories and jars. Arguments
+//! and results cross between Rust and Java as [`Value`]s.
+//!
+//! ```
+//! use rusvm::{Attr, JavaClassFileParser, Query, QueryResult, QueryType, Value, Vm};
+//!
+//! let bytes = std::fs::read("samples/Main.class").unwrap();
+//! let class = JavaClassFileParser::new(&bytes).parse().unwrap();
+//! assert_eq!(class.this_class, "Main");
+//!
+//! let main = class.methods.iter().find(|m| m.name == "main").unwrap();
+//! assert!(main.attrs.iter().any(|attr| matches!(attr, Attr::Code { .. })));
+//!
+//! let query = Query::new(&class);
+//! match query.query(QueryType::QMethod("sum".to_string())) {
+//!     Some(QueryResult::QMethod(method)) => assert_eq!(method.descriptor, "(II)I"),
+//!     _ => unreachable!(),
+//! }
+//!
+//! let mut vm = Vm::new();
+//! vm.add_class(class).unwrap();
+//! let sum = vm.invoke_static("Main", "sum", "(II)I", vec![Value::Int(2), Value::Int(3)]);
+//! assert_eq!(sum.unwrap(), Some(Value::Int(5)));
+//! ```
+
+pub mod loader;
+pub mod parsers;
+pub mod query;
+pub mod utils;
+pub mod vm;
+pub mod writer;
+
+pub use loader::classloader::ClassLoader;
+pub use loader::classpath::ClassPathEntry;
+pub use loader::error::LoadError;
+pub use parsers::attributes::{Attr, BootstrapMethod, ExceptionTableEntry, LineNumberTableEntry};
+pub use parsers::bytecode::Instruction;
+pub use parsers::class::{JavaClassFile, JavaClassFileParser};
+pub use parsers::constantpool::{Constant, ConstantPool, ConstantPoolError, Utf8};
+pub use parsers::error::ParseError;
+pub use parsers::field::Field;
+pub use parsers::method::Method;
+pub use parsers::version::VersionPolicy;
+pub use query::query::{Query, QueryResult, QueryType};
+pub use vm::error::VmError;
+pub use vm::value::Value;
+pub use vm::vm::Vm;
+pub use writer::classwriter::{ClassWriter, WriteError};
@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::env;
+use std::rc::Rc;
+
+use super::{classpath::ClassPathEntry, error::LoadError};
+use crate::parsers::class::{JavaClassFile, JavaClassFileParser};
+
+/// Finds classes by binary name, e.g. `com/acme/Foo`, in a classpath of directories
+/// and jar files. Each class is parsed once, later loads return the cached class.
+#[derive(Debug, Default)]
+pub struct ClassLoader {
+    classpath: Vec<ClassPathEntry>,
+    classes: HashMap<String, Rc<JavaClassFile>>,
+    warnings: Vec<String>,
+}
+
+impl ClassLoader {
+    pub fn new(classpath: Vec<ClassPathEntry>) -> Self {
+        Self {
+            classpath,
+            ..Default::default()
+        }
+    }
+
+    /// Opens every entry of a classpath string like `classes:lib/dep.jar`, which uses
+    /// the platform's path separator
+    pub fn from_classpath(classpath: &str) -> Result<Self, LoadError> {
+        let classpath = env::split_paths(classpath)
+            .filter(|path| !path.as_os_str().is_empty())
+            .map(ClassPathEntry::open)
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(classpath))
+    }
+
+    pub fn push(&mut self, entry: ClassPathEntry) {
+        self.classpath.push(entry);
+    }
+
+    /// Warnings of the parser for the classes loaded so far
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    pub fn is_loaded(&self, name: &str) -> bool {
+        self.classes.contains_key(name)
+    }
+
+    /// Loads class `name` from the first classpath entry that has it. Only that class
+    /// is loaded, its superclass and the classes it references are loaded when asked
+    /// for.
+    pub fn load(&mut self, name: &str) -> Result<Rc<JavaClassFile>, LoadError> {
+        if let Some(class) = self.classes.get(name) {
+            return Ok(Rc::clone(class));
+        }
+        let bytes = self
+            .find(name)?
+            .ok_or_else(|| LoadError::ClassNotFound(name.to_string()))?;
+        let mut parser = JavaClassFileParser::new(&bytes);
+        let class = parser.parse().map_err(|error| LoadError::Parse {
+            class: name.to_string(),
+            error,
+        })?;
+        for warning in parser.warnings() {
+            self.warnings.push(format!("{name}: {warning}"));
+        }
+        if class.this_class != name {
+            return Err(LoadError::WrongName {
+                expected: name.to_string(),
+                found: class.this_class,
+            });
+        }
+        let class = Rc::new(class);
+        self.classes.insert(name.to_string(), Rc::clone(&class));
+        Ok(class)
+    }
+
+    fn find(&self, name: &str) -> Result<Option<Vec<u8>>, LoadError> {
+        for entry in &self.classpath {
+            if let Some(bytes) = entry.read_class(name)? {
+                return Ok(Some(bytes));
+            }
+        }
+        Ok(None)
+    }
+}
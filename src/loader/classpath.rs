@@ -0,0 +1,67 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use super::{error::LoadError, zip::ZipArchive};
+
+/// A place classes are looked up in, classes are stored as `com/acme/Foo.class`
+/// relative to its root
+#[derive(Debug)]
+pub enum ClassPathEntry {
+    Directory(PathBuf),
+    Archive { path: PathBuf, archive: ZipArchive },
+}
+
+impl ClassPathEntry {
+    /// A directory, or a .jar or .zip archive which is read right away
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            return Ok(ClassPathEntry::Directory(path.to_path_buf()));
+        }
+        let bytes = fs::read(path).map_err(|err| LoadError::Io {
+            path: path.display().to_string(),
+            message: err.to_string(),
+        })?;
+        let archive = ZipArchive::new(bytes).map_err(|message| LoadError::Zip {
+            path: path.display().to_string(),
+            message,
+        })?;
+        Ok(ClassPathEntry::Archive {
+            path: path.to_path_buf(),
+            archive,
+        })
+    }
+
+    /// The bytes of class `name`, e.g. `java/lang/Object`, None if this entry doesn't
+    /// have it
+    pub fn read_class(&self, name: &str) -> Result<Option<Vec<u8>>, LoadError> {
+        // the name becomes a path inside the entry, it may not lead out of it
+        if name
+            .split('/')
+            .any(|segment| matches!(segment, "" | "." | ".."))
+        {
+            return Err(LoadError::InvalidName(name.to_string()));
+        }
+        let file = format!("{name}.class");
+        match self {
+            ClassPathEntry::Directory(root) => {
+                let path = root.join(file);
+                match fs::read(&path) {
+                    Ok(bytes) => Ok(Some(bytes)),
+                    Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+                    Err(err) => Err(LoadError::Io {
+                        path: path.display().to_string(),
+                        message: err.to_string(),
+                    }),
+                }
+            }
+            ClassPathEntry::Archive { path, archive } => {
+                archive.read(&file).map_err(|message| LoadError::Zip {
+                    path: path.display().to_string(),
+                    message,
+                })
+            }
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::fmt;
+
+use crate::parsers::error::ParseError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadError {
+    /// No classpath entry has the class
+    ClassNotFound(String),
+    /// A class name that isn't a path relative to the classpath entry, e.g. with a
+    /// `..` segment
+    InvalidName(String),
+    Io {
+        path: String,
+        message: String,
+    },
+    Zip {
+        path: String,
+        message: String,
+    },
+    Parse {
+        class: String,
+        error: ParseError,
+    },
+    /// The file found for a class declares another one, e.g. a `Foo.class` copied
+    /// into the wrong package directory
+    WrongName {
+        expected: String,
+        found: String,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::ClassNotFound(name) => write!(f, "Class {name} not found"),
+            LoadError::InvalidName(name) => write!(f, "Invalid class name {name}"),
+            LoadError::Io { path, message } => write!(f, "Couldn't read {path}: {message}"),
+            LoadError::Zip { path, message } => {
+                write!(f, "Invalid jar or zip file {path}: {message}")
+            }
+            LoadError::Parse { class, error } => write!(f, "Couldn't parse {class}: {error}"),
+            LoadError::WrongName { expected, found } => {
+                write!(f, "Expected class {expected} but the file declares {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
@@ -0,0 +1,4 @@
+pub mod classloader;
+pub mod classpath;
+pub mod error;
+pub mod zip;
@@ -0,0 +1,128 @@
+//! Just enough of the zip format to read classes out of jar files: stored and
+//! deflated entries, without zip64 or encryption.
+
+use std::collections::HashMap;
+
+use miniz_oxide::inflate::decompress_to_vec;
+
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+const CENTRAL_DIRECTORY_HEADER: u32 = 0x02014b50;
+const LOCAL_FILE_HEADER: u32 = 0x04034b50;
+const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+
+const STORED: u16 = 0;
+const DEFLATED: u16 = 8;
+
+#[derive(Debug)]
+struct ZipEntry {
+    method: u16,
+    compressed_size: usize,
+    uncompressed_size: usize,
+    local_header_offset: usize,
+}
+
+/// A zip archive read into memory, entries are decompressed on demand
+#[derive(Debug)]
+pub struct ZipArchive {
+    bytes: Vec<u8>,
+    entries: HashMap<String, ZipEntry>,
+}
+
+impl ZipArchive {
+    pub fn new(bytes: Vec<u8>) -> Result<Self, String> {
+        let end = find_end_of_central_directory(&bytes)?;
+        let count = u16_at(&bytes, end + 10)? as usize;
+        let mut offset = u32_at(&bytes, end + 16)? as usize;
+
+        let mut entries = HashMap::with_capacity(count);
+        for _ in 0..count {
+            if u32_at(&bytes, offset)? != CENTRAL_DIRECTORY_HEADER {
+                return Err(format!("Invalid central directory header at {offset}"));
+            }
+            let name_length = u16_at(&bytes, offset + 28)? as usize;
+            let extra_length = u16_at(&bytes, offset + 30)? as usize;
+            let comment_length = u16_at(&bytes, offset + 32)? as usize;
+            let name = slice(&bytes, offset + 46, name_length)?;
+            let entry = ZipEntry {
+                method: u16_at(&bytes, offset + 10)?,
+                compressed_size: u32_at(&bytes, offset + 20)? as usize,
+                uncompressed_size: u32_at(&bytes, offset + 24)? as usize,
+                local_header_offset: u32_at(&bytes, offset + 42)? as usize,
+            };
+            entries.insert(String::from_utf8_lossy(name).into_owned(), entry);
+            offset += 46 + name_length + extra_length + comment_length;
+        }
+        Ok(Self { bytes, entries })
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// The decompressed content of entry `name`, None if there is no such entry
+    pub fn read(&self, name: &str) -> Result<Option<Vec<u8>>, String> {
+        let Some(entry) = self.entries.get(name) else {
+            return Ok(None);
+        };
+        let offset = entry.local_header_offset;
+        if u32_at(&self.bytes, offset)? != LOCAL_FILE_HEADER {
+            return Err(format!("Invalid local file header for {name}"));
+        }
+        // the local header has its own name and extra field lengths
+        let name_length = u16_at(&self.bytes, offset + 26)? as usize;
+        let extra_length = u16_at(&self.bytes, offset + 28)? as usize;
+        let data = slice(
+            &self.bytes,
+            offset + 30 + name_length + extra_length,
+            entry.compressed_size,
+        )?;
+        let content = match entry.method {
+            STORED => data.to_vec(),
+            DEFLATED => decompress_to_vec(data)
+                .map_err(|err| format!("Couldn't inflate {name}: {:?}", err.status))?,
+            method => {
+                return Err(format!(
+                    "Unsupported compression method {method} for {name}"
+                ))
+            }
+        };
+        if content.len() != entry.uncompressed_size {
+            return Err(format!("Size mismatch for {name}"));
+        }
+        Ok(Some(content))
+    }
+}
+
+/// The end of central directory record is the last thing in the file, followed only by
+/// a comment of up to 64KiB
+fn find_end_of_central_directory(bytes: &[u8]) -> Result<usize, String> {
+    let last = bytes
+        .len()
+        .checked_sub(END_OF_CENTRAL_DIRECTORY_SIZE)
+        .ok_or("Not a zip file")?;
+    let first = last.saturating_sub(u16::MAX as usize);
+    (first..=last)
+        .rev()
+        .find(|&offset| u32_at(bytes, offset) == Ok(END_OF_CENTRAL_DIRECTORY))
+        .ok_or_else(|| "Not a zip file".to_string())
+}
+
+fn slice(bytes: &[u8], offset: usize, length: usize) -> Result<&[u8], String> {
+    bytes
+        .get(offset..offset + length)
+        .ok_or_else(|| format!("Truncated at offset {offset}"))
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Result<u16, String> {
+    let bytes = slice(bytes, offset, 2)?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Result<u32, String> {
+    let bytes = slice(bytes, offset, 4)?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
@@ -1,43 +1,141 @@
+mod cli;
+
 use std::env;
+use std::fs;
+use std::path::Path;
 use std::process::exit;
 
-mod parsers;
-mod query;
-mod utils;
-
-use parsers::attributes::Attr;
-use parsers::class::JavaClassFileParser;
-use parsers::constantpool::ConstantPool;
-use parsers::method::Method;
-use query::query::Query;
-use utils::bytestream::ByteStream;
-
-use utils::prompt::prompt;
-use utils::read_file_to_bytes::read_file_to_bytes;
-
-#[derive(Default, Clone)]
-pub struct JavaClassFile {
-    pub magic: u32,
-    pub minor: u16,
-    pub major: u16,
-    pub constant_pool: ConstantPool,
-    pub access_flags: Vec<String>,
-    pub this_class: String,
-    pub super_class: String,
-    pub methods: Vec<Method>,
-    pub attrs: Vec<Attr>,
-}
+use cli::prompt::prompt;
+use rusvm::{ClassLoader, ClassPathEntry, JavaClassFile, JavaClassFileParser, Query, Vm, VmError};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        eprintln!("Usage: main <path_to_class_file>");
-        exit(1);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args[1..] {
+        [file_path] => inspect(parse_class_file(file_path)),
+        ["-cp", classpath, class_name] => {
+            let mut loader = open_classpath(classpath);
+            inspect(load_class(&mut loader, class_name))
+        }
+        ["run", "-cp", classpath, class_name, ref args @ ..] => {
+            run(open_classpath(classpath), &binary_name(class_name), args)
+        }
+        ["run", file_path, ref args @ ..] => run_file(file_path, args),
+        _ => {
+            eprintln!("Usage: main <path_to_class_file>");
+            eprintln!("       main -cp <classpath> <class_name>");
+            eprintln!("       main run <path_to_class_file> [args...]");
+            eprintln!("       main run -cp <classpath> <class_name> [args...]");
+            exit(1);
+        }
     }
+}
 
-    let file_path = &args[1];
-    let content = read_file_to_bytes(file_path);
-    let class_file = JavaClassFileParser::new(content).parse();
+fn inspect(class_file: JavaClassFile) {
     let query = Query::new(&class_file);
     prompt(&class_file, query);
 }
+
+/// Runs a class file, the classes it uses are loaded from the directory its package
+/// starts in
+fn run_file(file_path: &str, args: &[&str]) {
+    let class_file = parse_class_file(file_path);
+    let class_name = class_file.this_class.clone();
+    let mut root = Path::new(file_path)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .to_path_buf();
+    for _ in class_name.matches('/') {
+        root.pop();
+    }
+    let root = if root.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        &root
+    };
+    let mut loader = ClassLoader::default();
+    if let Ok(entry) = ClassPathEntry::open(root) {
+        loader.push(entry);
+    }
+    let mut vm = Vm::new();
+    vm.set_class_loader(loader);
+    if let Err(err) = vm.add_class(class_file) {
+        exit_with(err);
+    }
+    run_vm(vm, &class_name, args)
+}
+
+fn run(loader: ClassLoader, class_name: &str, args: &[&str]) {
+    let mut vm = Vm::new();
+    vm.set_class_loader(loader);
+    run_vm(vm, class_name, args)
+}
+
+fn run_vm(mut vm: Vm, class_name: &str, args: &[&str]) {
+    let result = vm.run_main_with_args(class_name, args);
+    for warning in vm.class_loader().warnings() {
+        eprintln!("[WARNING]: {warning}");
+    }
+    match result {
+        Ok(()) => {}
+        // the virtual machine already printed its stack trace
+        Err(VmError::Exception { .. }) => exit(1),
+        Err(err) => exit_with(err),
+    }
+}
+
+fn exit_with(err: VmError) -> ! {
+    eprintln!("[ERROR]: {err}");
+    exit(1);
+}
+
+fn open_classpath(classpath: &str) -> ClassLoader {
+    match ClassLoader::from_classpath(classpath) {
+        Ok(loader) => loader,
+        Err(err) => {
+            eprintln!("[ERROR]: {err}");
+            exit(1);
+        }
+    }
+}
+
+fn load_class(loader: &mut ClassLoader, class_name: &str) -> JavaClassFile {
+    let class_file = match loader.load(&binary_name(class_name)) {
+        Ok(class_file) => class_file,
+        Err(err) => {
+            eprintln!("[ERROR]: {err}");
+            exit(1);
+        }
+    };
+    for warning in loader.warnings() {
+        eprintln!("[WARNING]: {warning}");
+    }
+    (*class_file).clone()
+}
+
+/// `com.acme.Foo` as `com/acme/Foo`
+fn binary_name(class_name: &str) -> String {
+    class_name.replace('.', "/")
+}
+
+fn parse_class_file(file_path: &str) -> JavaClassFile {
+    let content = match fs::read(file_path) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("[ERROR]: Couldn't read {file_path}: {err}");
+            exit(1);
+        }
+    };
+    let mut parser = JavaClassFileParser::new(&content);
+    let class_file = match parser.parse() {
+        Ok(class_file) => class_file,
+        Err(err) => {
+            eprintln!("[ERROR]: Couldn't parse {file_path}: {err}");
+            exit(1);
+        }
+    };
+    for warning in parser.warnings() {
+        eprintln!("[WARNING]: {warning}");
+    }
+    class_file
+}
@@ -1,6 +1,14 @@
 use std::ops::BitAnd;
 
-const CLASS_ACCESS_FLAGS: [(&str, u16); 8] = [
+pub const ACC_PUBLIC: u16 = 0x0001;
+pub const ACC_PRIVATE: u16 = 0x0002;
+pub const ACC_STATIC: u16 = 0x0008;
+pub const ACC_SYNCHRONIZED: u16 = 0x0020;
+pub const ACC_NATIVE: u16 = 0x0100;
+pub const ACC_INTERFACE: u16 = 0x0200;
+pub const ACC_ABSTRACT: u16 = 0x0400;
+
+const CLASS_ACCESS_FLAGS: [(&str, u16); 9] = [
     ("ACC_PUBLIC", 0x0001),
     ("ACC_FINAL", 0x0010),
     ("ACC_SUPER", 0x0020),
@@ -9,6 +17,7 @@ const CLASS_ACCESS_FLAGS: [(&str, u16); 8] = [
     ("ACC_SYNTHETIC", 0x1000),
     ("ACC_ANNOTATION", 0x2000),
     ("ACC_ENUM", 0x4000),
+    ("ACC_MODULE", 0x8000),
 ];
 
 const METHOD_ACCESS_FLAGS: [(&str, u16); 12] = [
@@ -26,6 +35,18 @@ const METHOD_ACCESS_FLAGS: [(&str, u16); 12] = [
     ("ACC_SYNTHETIC", 0x1000),
 ];
 
+const FIELD_ACCESS_FLAGS: [(&str, u16); 9] = [
+    ("ACC_PUBLIC", 0x0001),
+    ("ACC_PRIVATE", 0x0002),
+    ("ACC_PROTECTED", 0x0004),
+    ("ACC_STATIC", 0x0008),
+    ("ACC_FINAL", 0x0010),
+    ("ACC_VOLATILE", 0x0040),
+    ("ACC_TRANSIENT", 0x0080),
+    ("ACC_SYNTHETIC", 0x1000),
+    ("ACC_ENUM", 0x4000),
+];
+
 pub fn parse_class_access_flags(mask: u16) -> Vec<String> {
     parse_access_flags(mask, &CLASS_ACCESS_FLAGS)
 }
@@ -34,6 +55,50 @@ pub fn parse_method_access_flags(mask: u16) -> Vec<String> {
     parse_access_flags(mask, &METHOD_ACCESS_FLAGS)
 }
 
+pub fn parse_field_access_flags(mask: u16) -> Vec<String> {
+    parse_access_flags(mask, &FIELD_ACCESS_FLAGS)
+}
+
+pub fn class_access_flags_mask(flags: &[String]) -> u16 {
+    access_flags_mask(flags, &CLASS_ACCESS_FLAGS)
+}
+
+pub fn method_access_flags_mask(flags: &[String]) -> u16 {
+    access_flags_mask(flags, &METHOD_ACCESS_FLAGS)
+}
+
+pub fn field_access_flags_mask(flags: &[String]) -> u16 {
+    access_flags_mask(flags, &FIELD_ACCESS_FLAGS)
+}
+
+/// The bits of `mask` that `parse_class_access_flags` has no name for
+pub fn unnamed_class_access_flags(mask: u16) -> u16 {
+    unnamed_access_flags(mask, &CLASS_ACCESS_FLAGS)
+}
+
+pub fn unnamed_method_access_flags(mask: u16) -> u16 {
+    unnamed_access_flags(mask, &METHOD_ACCESS_FLAGS)
+}
+
+pub fn unnamed_field_access_flags(mask: u16) -> u16 {
+    unnamed_access_flags(mask, &FIELD_ACCESS_FLAGS)
+}
+
+/// Reserved bits are dropped by `parse_access_flags` but kept by the class file
+fn unnamed_access_flags(mask: u16, access_flags: &[(&str, u16)]) -> u16 {
+    access_flags
+        .iter()
+        .fold(mask, |mask, (_, value)| mask & !value)
+}
+
+/// Inverse of `parse_access_flags`, names that are not in the table are ignored
+fn access_flags_mask(flags: &[String], access_flags: &[(&str, u16)]) -> u16 {
+    access_flags
+        .iter()
+        .filter(|(name, _)| flags.iter().any(|flag| flag == name))
+        .fold(0, |mask, (_, value)| mask | value)
+}
+
 fn parse_access_flags(mask: u16, access_flags: &[(&str, u16)]) -> Vec<String> {
     let mut flags: Vec<String> = vec![];
     for (name, value) in access_flags {
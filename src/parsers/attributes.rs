@@ -1,98 +1,374 @@
-use std::process::exit;
-
-use super::{bytecode::parse_byte_code, constantpool::ConstantPool};
+use super::{
+    bytecode::{parse_byte_code, Instruction},
+    constantpool::ConstantPool,
+    error::{ParseError, ParseErrorKind, Section},
+};
 use crate::utils::bytestream::ByteStream;
 
 #[derive(Debug, Clone)]
-#[allow(unused)]
 pub struct LineNumberTableEntry {
-    start_pc: u16,
-    line_number: u16,
+    pub start_pc: u16,
+    pub line_number: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExceptionTableEntry {
+    pub start_pc: u16,
+    pub end_pc: u16,
+    pub handler_pc: u16,
+    pub catch_type: String,
+    /// The Class entry `catch_type` was read from, 0 for `any`
+    pub catch_type_index: u16,
+}
+
+/// An entry of the BootstrapMethods attribute, the bootstrap method of the
+/// `invokedynamic` instructions whose InvokeDynamic entries point at it
+#[derive(Debug, Clone)]
+pub struct BootstrapMethod {
+    /// Index of a MethodHandle entry
+    pub method_ref: u16,
+    /// Indices of the loadable entries passed to the bootstrap method after the
+    /// lookup, name and type of the call site
+    pub arguments: Vec<u16>,
 }
 
+/// An attribute of a class, field, method or Code attribute. `name_index` is the
+/// Utf8 entry its name was read from, 0 for attributes that weren't parsed.
 #[derive(Debug, Clone)]
-#[allow(unused)]
 pub enum Attr {
     Code {
+        name_index: u16,
         max_stack: u16,
         max_locals: u16,
         code_length: u32,
-        code: Vec<String>,
+        code: Vec<(u32, Instruction)>,
+        exception_table: Vec<ExceptionTableEntry>,
         attrs: Vec<Attr>,
     },
     LineNumberTable {
+        name_index: u16,
         table: Vec<LineNumberTableEntry>,
     },
     SourceFile {
+        name_index: u16,
         file: String,
+        file_index: u16,
     },
+    ConstantValue {
+        name_index: u16,
+        value_index: u16,
+    },
+    BootstrapMethods {
+        name_index: u16,
+        methods: Vec<BootstrapMethod>,
+    },
+    /// Any attribute this parser does not understand. The JVM spec requires readers to
+    /// skip those, the raw payload is kept so it can still be inspected or written back.
+    Unknown {
+        name_index: u16,
+        name: String,
+        bytes: Vec<u8>,
+    },
+}
+
+impl Attr {
+    pub fn name(&self) -> &str {
+        match self {
+            Attr::Code { .. } => "Code",
+            Attr::LineNumberTable { .. } => "LineNumberTable",
+            Attr::SourceFile { .. } => "SourceFile",
+            Attr::ConstantValue { .. } => "ConstantValue",
+            Attr::BootstrapMethods { .. } => "BootstrapMethods",
+            Attr::Unknown { name, .. } => name,
+        }
+    }
+
+    pub fn name_index(&self) -> u16 {
+        match self {
+            Attr::Code { name_index, .. }
+            | Attr::LineNumberTable { name_index, .. }
+            | Attr::SourceFile { name_index, .. }
+            | Attr::ConstantValue { name_index, .. }
+            | Attr::BootstrapMethods { name_index, .. }
+            | Attr::Unknown { name_index, .. } => *name_index,
+        }
+    }
 }
 
-pub fn parse_attrs(bytes: &mut ByteStream, constantpool: &ConstantPool) -> Vec<Attr> {
+pub fn parse_attrs(
+    bytes: &mut ByteStream,
+    constantpool: &ConstantPool,
+) -> Result<Vec<Attr>, ParseError> {
     __parse_attrs(bytes, constantpool)
 }
 
-fn __parse_attrs(bytes: &mut ByteStream, cp: &ConstantPool) -> Vec<Attr> {
+fn __parse_attrs(bytes: &mut ByteStream, cp: &ConstantPool) -> Result<Vec<Attr>, ParseError> {
     let mut attrs: Vec<Attr> = vec![];
 
-    for _ in 0..bytes.parse_u2() {
-        let name = cp.query(bytes.parse_u2() as usize);
-        let length = bytes.parse_u4();
+    for _ in 0..bytes.parse_u2()? {
+        let name_index = bytes.parse_u2()?;
+        let name = bytes.resolve(cp.utf8(name_index as usize))?.to_string();
+        let length = bytes.parse_u4()?;
+        let mut attr_bytes = bytes.sub_stream(length as usize)?;
 
-        match name.as_ref() {
+        let attr = match name.as_ref() {
             "Code" => {
-                let mut code_attr_bytes = ByteStream {
-                    xs: bytes.parse_n(length as usize),
-                };
-
-                let max_stack = code_attr_bytes.parse_u2();
-                let max_locals = code_attr_bytes.parse_u2();
-                let code_length = code_attr_bytes.parse_u4();
-                let code = parse_byte_code(ByteStream {
-                    xs: code_attr_bytes.parse_n(code_length as usize),
-                }, cp);
-
-                for _ in 0..code_attr_bytes.parse_u2() {
-                    assert!(
-                        false,
-                        "[ERROR]: exception_table parser not implemented yet!\n"
-                    );
+                attr_bytes.section = Section::Code;
+                let max_stack = attr_bytes.parse_u2()?;
+                let max_locals = attr_bytes.parse_u2()?;
+                let code_length = attr_bytes.parse_u4()?;
+                let code = parse_byte_code(attr_bytes.sub_stream(code_length as usize)?)?;
+
+                let mut exception_table: Vec<ExceptionTableEntry> = vec![];
+                for _ in 0..attr_bytes.parse_u2()? {
+                    let start_pc = attr_bytes.parse_u2()?;
+                    let end_pc = attr_bytes.parse_u2()?;
+                    let handler_pc = attr_bytes.parse_u2()?;
+                    // catch_type 0 means the handler is called for any exception (finally)
+                    let catch_type_index = attr_bytes.parse_u2()?;
+                    let catch_type = match catch_type_index {
+                        0 => "any".to_string(),
+                        index => attr_bytes
+                            .resolve(cp.class_name(index as usize))?
+                            .to_string(),
+                    };
+                    exception_table.push(ExceptionTableEntry {
+                        start_pc,
+                        end_pc,
+                        handler_pc,
+                        catch_type,
+                        catch_type_index,
+                    });
                 }
 
-                let nested_attrs = __parse_attrs(&mut code_attr_bytes, cp);
+                let nested_attrs = __parse_attrs(&mut attr_bytes, cp)?;
 
-                attrs.push(Attr::Code {
+                Attr::Code {
+                    name_index,
                     max_stack,
                     max_locals,
                     code_length,
                     code,
+                    exception_table,
                     attrs: nested_attrs,
-                });
+                }
             }
             "LineNumberTable" => {
                 let mut table: Vec<LineNumberTableEntry> = vec![];
-                let mut lnt_attr_bytes = ByteStream {
-                    xs: bytes.parse_n(length as usize),
-                };
-                for _ in 0..lnt_attr_bytes.parse_u2() {
+                for _ in 0..attr_bytes.parse_u2()? {
                     table.push(LineNumberTableEntry {
-                        start_pc: lnt_attr_bytes.parse_u2(),
-                        line_number: lnt_attr_bytes.parse_u2(),
+                        start_pc: attr_bytes.parse_u2()?,
+                        line_number: attr_bytes.parse_u2()?,
                     })
                 }
-                attrs.push(Attr::LineNumberTable { table });
+                Attr::LineNumberTable { name_index, table }
             }
             "SourceFile" => {
-                attrs.push(Attr::SourceFile {
-                    file: cp.query(bytes.parse_u2() as usize),
-                });
+                let file_index = attr_bytes.parse_u2()?;
+                Attr::SourceFile {
+                    name_index,
+                    file: attr_bytes
+                        .resolve(cp.utf8(file_index as usize))?
+                        .to_string(),
+                    file_index,
+                }
             }
-            _ => {
-                eprintln!("[ERROR]:{}:{}: Unknown Attr: {name}", file!(), line!());
-                exit(1);
+            "ConstantValue" => Attr::ConstantValue {
+                name_index,
+                value_index: attr_bytes.parse_u2()?,
+            },
+            "BootstrapMethods" => {
+                let mut methods: Vec<BootstrapMethod> = vec![];
+                for _ in 0..attr_bytes.parse_u2()? {
+                    let method_ref = attr_bytes.parse_u2()?;
+                    let mut arguments = vec![];
+                    for _ in 0..attr_bytes.parse_u2()? {
+                        arguments.push(attr_bytes.parse_u2()?);
+                    }
+                    methods.push(BootstrapMethod {
+                        method_ref,
+                        arguments,
+                    });
+                }
+                Attr::BootstrapMethods {
+                    name_index,
+                    methods,
+                }
             }
+            _ => Attr::Unknown {
+                name_index,
+                name,
+                bytes: attr_bytes.parse_n(length as usize)?.to_vec(),
+            },
+        };
+
+        if !attr_bytes.is_empty() {
+            return Err(attr_bytes.error(ParseErrorKind::AttributeLength {
+                name: attr.name().to_string(),
+                length,
+                remaining: attr_bytes.remaining(),
+            }));
         }
+        attrs.push(attr);
     }
 
-    attrs
+    Ok(attrs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::constantpool::{Constant, ConstantPoolError, Utf8};
+
+    fn pool() -> ConstantPool {
+        let mut constants: Vec<Constant> = ["Code", "java/lang/Exception", "Custom"]
+            .map(|value| Constant::Utf8(Utf8::new(value)))
+            .to_vec();
+        constants.push(Constant::Class { name_index: 2 });
+        ConstantPool { constants }
+    }
+
+    /// An attribute named by the Utf8 entry `name_index` with the payload `info`
+    fn attr(name_index: u16, info: &[u8]) -> Vec<u8> {
+        let mut bytes = name_index.to_be_bytes().to_vec();
+        bytes.extend((info.len() as u32).to_be_bytes());
+        bytes.extend(info);
+        bytes
+    }
+
+    fn parse(attrs: &[Vec<u8>]) -> Result<Vec<Attr>, ParseError> {
+        let bytes = [&(attrs.len() as u16).to_be_bytes()[..], &attrs.concat()].concat();
+        parse_attrs(&mut ByteStream::new(&bytes), &pool())
+    }
+
+    /// A Code attribute of a single `return` with the exception table `handlers`
+    fn code(handlers: &[[u16; 4]]) -> Vec<u8> {
+        let mut info = vec![0, 1, 0, 1, 0, 0, 0, 1, 0xB1];
+        info.extend((handlers.len() as u16).to_be_bytes());
+        for handler in handlers {
+            for value in handler {
+                info.extend(value.to_be_bytes());
+            }
+        }
+        info.extend([0, 0]);
+        attr(1, &info)
+    }
+
+    #[test]
+    fn parses_exception_tables() {
+        let attrs = parse(&[code(&[[0, 1, 1, 4], [0, 1, 1, 0]])]).unwrap();
+        let [Attr::Code {
+            exception_table, ..
+        }] = &attrs[..]
+        else {
+            panic!("expected a Code attribute, got {attrs:?}");
+        };
+        let handlers: Vec<_> = exception_table
+            .iter()
+            .map(|entry| {
+                let pcs = (entry.start_pc, entry.end_pc, entry.handler_pc);
+                (pcs, entry.catch_type.as_str(), entry.catch_type_index)
+            })
+            .collect();
+        assert_eq!(
+            handlers,
+            [
+                ((0, 1, 1), "java/lang/Exception", 4),
+                // catch_type 0 catches everything, it's how finally is compiled
+                ((0, 1, 1), "any", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_an_empty_exception_table() {
+        let attrs = parse(&[code(&[])]).unwrap();
+        assert!(
+            matches!(&attrs[..], [Attr::Code { exception_table, .. }] if exception_table.is_empty())
+        );
+    }
+
+    #[test]
+    fn rejects_catch_types_that_are_not_classes() {
+        let err = parse(&[code(&[[0, 1, 1, 2]])]).unwrap_err();
+        assert_eq!(err.section, Section::Code);
+        // after the count of attributes, the 6 bytes naming the attribute, the 11
+        // bytes before the handler and the 8 bytes of the handler
+        assert_eq!(err.offset, 2 + 6 + 11 + 8);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::ConstantPool(ConstantPoolError::UnexpectedTag {
+                index: 2,
+                expected: "Class",
+                found: "Utf8",
+            })
+        );
+    }
+
+    #[test]
+    fn reports_truncated_exception_tables() {
+        // a handler count of 1 without the handler, the attribute ends right after it
+        let info = [0, 1, 0, 1, 0, 0, 0, 1, 0xB1, 0, 1];
+        let err = parse(&[attr(1, &info)]).unwrap_err();
+        assert_eq!(err.section, Section::Code);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::UnexpectedEof {
+                needed: 2,
+                remaining: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_unknown_attributes_as_raw_bytes() {
+        let attrs = parse(&[attr(3, &[1, 2, 3]), attr(3, &[]), code(&[])]).unwrap();
+        let unknown: Vec<_> = attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                Attr::Unknown {
+                    name_index,
+                    name,
+                    bytes,
+                } => Some((*name_index, name.as_str(), bytes.as_slice())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            unknown,
+            [(3, "Custom", &[1, 2, 3][..]), (3, "Custom", &[][..])]
+        );
+        // the attribute after them is read from the right offset
+        assert!(matches!(attrs[2], Attr::Code { max_stack: 1, .. }));
+        assert_eq!(attrs[0].name(), "Custom");
+    }
+
+    #[test]
+    fn rejects_unknown_attributes_longer_than_the_input() {
+        let mut bytes = attr(3, &[1, 2, 3]);
+        bytes.truncate(bytes.len() - 1);
+        let err = parse(&[bytes]).unwrap_err();
+        assert_eq!(err.offset, 2 + 6);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::UnexpectedEof {
+                needed: 3,
+                remaining: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_attribute_names_that_are_not_utf8() {
+        let err = parse(&[attr(4, &[])]).unwrap_err();
+        assert_eq!(err.offset, 2 + 2);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::ConstantPool(ConstantPoolError::UnexpectedTag {
+                index: 4,
+                expected: "Utf8",
+                found: "Class",
+            })
+        );
+    }
 }
@@ -1,75 +1,1616 @@
+use std::fmt;
+
 use super::constantpool::ConstantPool;
+use super::error::{ParseError, ParseErrorKind};
 use crate::utils::bytestream::ByteStream;
-use std::collections::HashMap;
-
-const INVOKESPECIAL: u8 = 0xb7;
-const GETSTATIC: u8 = 0xb2;
-const LDC: u8 = 0x12;
-const INVOKEVIRTUAL: u8 = 0xb6;
-
-#[allow(unused)]
-const BYTECODETABLE: [(u8, &str); 12] = [
-    (
-        0x1a,
-        "iload_0         #load an int value from local variable 0",
-    ),
-    (
-        0x1b,
-        "iload_1         #load an int value from local variable 1",
-    ),
-    (0x60, "iadd            #add two ints"),
-    (0x3d, "istore_2        #store int value into variable 2"),
-    (
-        0x1c,
-        "iload_2         #load an int value from local variable 2",
-    ),
-    (0xac, "ireturn         #return an integer from a method"),
-    (
-        0x2a,
-        "aload_0         #load a reference onto the stack from local variable 0",
-    ),
-    (
-        0xb7,
-        "invokespecial   #invoke instance method on object objectref",
-    ),
-    (0xb1, "return          #return void from method"),
-    (0xb2, "getstatic       #get static field from class"),
-    (
-        0x12,
-        "ldc             #push item from run-time constant pool",
-    ),
-    (0xb6, "invokevirtual   #invoke instance method"),
+
+const BYTECODETABLE: [(u8, &str); 202] = [
+    (0x00, "nop"),
+    (0x01, "aconst_null"),
+    (0x02, "iconst_m1"),
+    (0x03, "iconst_0"),
+    (0x04, "iconst_1"),
+    (0x05, "iconst_2"),
+    (0x06, "iconst_3"),
+    (0x07, "iconst_4"),
+    (0x08, "iconst_5"),
+    (0x09, "lconst_0"),
+    (0x0a, "lconst_1"),
+    (0x0b, "fconst_0"),
+    (0x0c, "fconst_1"),
+    (0x0d, "fconst_2"),
+    (0x0e, "dconst_0"),
+    (0x0f, "dconst_1"),
+    (0x10, "bipush"),
+    (0x11, "sipush"),
+    (0x12, "ldc"),
+    (0x13, "ldc_w"),
+    (0x14, "ldc2_w"),
+    (0x15, "iload"),
+    (0x16, "lload"),
+    (0x17, "fload"),
+    (0x18, "dload"),
+    (0x19, "aload"),
+    (0x1a, "iload_0"),
+    (0x1b, "iload_1"),
+    (0x1c, "iload_2"),
+    (0x1d, "iload_3"),
+    (0x1e, "lload_0"),
+    (0x1f, "lload_1"),
+    (0x20, "lload_2"),
+    (0x21, "lload_3"),
+    (0x22, "fload_0"),
+    (0x23, "fload_1"),
+    (0x24, "fload_2"),
+    (0x25, "fload_3"),
+    (0x26, "dload_0"),
+    (0x27, "dload_1"),
+    (0x28, "dload_2"),
+    (0x29, "dload_3"),
+    (0x2a, "aload_0"),
+    (0x2b, "aload_1"),
+    (0x2c, "aload_2"),
+    (0x2d, "aload_3"),
+    (0x2e, "iaload"),
+    (0x2f, "laload"),
+    (0x30, "faload"),
+    (0x31, "daload"),
+    (0x32, "aaload"),
+    (0x33, "baload"),
+    (0x34, "caload"),
+    (0x35, "saload"),
+    (0x36, "istore"),
+    (0x37, "lstore"),
+    (0x38, "fstore"),
+    (0x39, "dstore"),
+    (0x3a, "astore"),
+    (0x3b, "istore_0"),
+    (0x3c, "istore_1"),
+    (0x3d, "istore_2"),
+    (0x3e, "istore_3"),
+    (0x3f, "lstore_0"),
+    (0x40, "lstore_1"),
+    (0x41, "lstore_2"),
+    (0x42, "lstore_3"),
+    (0x43, "fstore_0"),
+    (0x44, "fstore_1"),
+    (0x45, "fstore_2"),
+    (0x46, "fstore_3"),
+    (0x47, "dstore_0"),
+    (0x48, "dstore_1"),
+    (0x49, "dstore_2"),
+    (0x4a, "dstore_3"),
+    (0x4b, "astore_0"),
+    (0x4c, "astore_1"),
+    (0x4d, "astore_2"),
+    (0x4e, "astore_3"),
+    (0x4f, "iastore"),
+    (0x50, "lastore"),
+    (0x51, "fastore"),
+    (0x52, "dastore"),
+    (0x53, "aastore"),
+    (0x54, "bastore"),
+    (0x55, "castore"),
+    (0x56, "sastore"),
+    (0x57, "pop"),
+    (0x58, "pop2"),
+    (0x59, "dup"),
+    (0x5a, "dup_x1"),
+    (0x5b, "dup_x2"),
+    (0x5c, "dup2"),
+    (0x5d, "dup2_x1"),
+    (0x5e, "dup2_x2"),
+    (0x5f, "swap"),
+    (0x60, "iadd"),
+    (0x61, "ladd"),
+    (0x62, "fadd"),
+    (0x63, "dadd"),
+    (0x64, "isub"),
+    (0x65, "lsub"),
+    (0x66, "fsub"),
+    (0x67, "dsub"),
+    (0x68, "imul"),
+    (0x69, "lmul"),
+    (0x6a, "fmul"),
+    (0x6b, "dmul"),
+    (0x6c, "idiv"),
+    (0x6d, "ldiv"),
+    (0x6e, "fdiv"),
+    (0x6f, "ddiv"),
+    (0x70, "irem"),
+    (0x71, "lrem"),
+    (0x72, "frem"),
+    (0x73, "drem"),
+    (0x74, "ineg"),
+    (0x75, "lneg"),
+    (0x76, "fneg"),
+    (0x77, "dneg"),
+    (0x78, "ishl"),
+    (0x79, "lshl"),
+    (0x7a, "ishr"),
+    (0x7b, "lshr"),
+    (0x7c, "iushr"),
+    (0x7d, "lushr"),
+    (0x7e, "iand"),
+    (0x7f, "land"),
+    (0x80, "ior"),
+    (0x81, "lor"),
+    (0x82, "ixor"),
+    (0x83, "lxor"),
+    (0x84, "iinc"),
+    (0x85, "i2l"),
+    (0x86, "i2f"),
+    (0x87, "i2d"),
+    (0x88, "l2i"),
+    (0x89, "l2f"),
+    (0x8a, "l2d"),
+    (0x8b, "f2i"),
+    (0x8c, "f2l"),
+    (0x8d, "f2d"),
+    (0x8e, "d2i"),
+    (0x8f, "d2l"),
+    (0x90, "d2f"),
+    (0x91, "i2b"),
+    (0x92, "i2c"),
+    (0x93, "i2s"),
+    (0x94, "lcmp"),
+    (0x95, "fcmpl"),
+    (0x96, "fcmpg"),
+    (0x97, "dcmpl"),
+    (0x98, "dcmpg"),
+    (0x99, "ifeq"),
+    (0x9a, "ifne"),
+    (0x9b, "iflt"),
+    (0x9c, "ifge"),
+    (0x9d, "ifgt"),
+    (0x9e, "ifle"),
+    (0x9f, "if_icmpeq"),
+    (0xa0, "if_icmpne"),
+    (0xa1, "if_icmplt"),
+    (0xa2, "if_icmpge"),
+    (0xa3, "if_icmpgt"),
+    (0xa4, "if_icmple"),
+    (0xa5, "if_acmpeq"),
+    (0xa6, "if_acmpne"),
+    (0xa7, "goto"),
+    (0xa8, "jsr"),
+    (0xa9, "ret"),
+    (0xaa, "tableswitch"),
+    (0xab, "lookupswitch"),
+    (0xac, "ireturn"),
+    (0xad, "lreturn"),
+    (0xae, "freturn"),
+    (0xaf, "dreturn"),
+    (0xb0, "areturn"),
+    (0xb1, "return"),
+    (0xb2, "getstatic"),
+    (0xb3, "putstatic"),
+    (0xb4, "getfield"),
+    (0xb5, "putfield"),
+    (0xb6, "invokevirtual"),
+    (0xb7, "invokespecial"),
+    (0xb8, "invokestatic"),
+    (0xb9, "invokeinterface"),
+    (0xba, "invokedynamic"),
+    (0xbb, "new"),
+    (0xbc, "newarray"),
+    (0xbd, "anewarray"),
+    (0xbe, "arraylength"),
+    (0xbf, "athrow"),
+    (0xc0, "checkcast"),
+    (0xc1, "instanceof"),
+    (0xc2, "monitorenter"),
+    (0xc3, "monitorexit"),
+    (0xc4, "wide"),
+    (0xc5, "multianewarray"),
+    (0xc6, "ifnull"),
+    (0xc7, "ifnonnull"),
+    (0xc8, "goto_w"),
+    (0xc9, "jsr_w"),
 ];
 
-pub fn parse_byte_code(mut bytes: ByteStream, _: &ConstantPool) -> Vec<String> {
-    let bytecodes: HashMap<u8, &str> = HashMap::from(BYTECODETABLE);
+/// A decoded JVM instruction. Local variable and constant pool operands are kept as
+/// indices, branch operands are resolved to the absolute pc of their target.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+    Nop,
+    AconstNull,
+    IconstM1,
+    Iconst0,
+    Iconst1,
+    Iconst2,
+    Iconst3,
+    Iconst4,
+    Iconst5,
+    Lconst0,
+    Lconst1,
+    Fconst0,
+    Fconst1,
+    Fconst2,
+    Dconst0,
+    Dconst1,
+    Bipush(i8),
+    Sipush(i16),
+    Ldc(u8),
+    LdcW(u16),
+    Ldc2W(u16),
+    Iload(u8),
+    Lload(u8),
+    Fload(u8),
+    Dload(u8),
+    Aload(u8),
+    Iload0,
+    Iload1,
+    Iload2,
+    Iload3,
+    Lload0,
+    Lload1,
+    Lload2,
+    Lload3,
+    Fload0,
+    Fload1,
+    Fload2,
+    Fload3,
+    Dload0,
+    Dload1,
+    Dload2,
+    Dload3,
+    Aload0,
+    Aload1,
+    Aload2,
+    Aload3,
+    Iaload,
+    Laload,
+    Faload,
+    Daload,
+    Aaload,
+    Baload,
+    Caload,
+    Saload,
+    Istore(u8),
+    Lstore(u8),
+    Fstore(u8),
+    Dstore(u8),
+    Astore(u8),
+    Istore0,
+    Istore1,
+    Istore2,
+    Istore3,
+    Lstore0,
+    Lstore1,
+    Lstore2,
+    Lstore3,
+    Fstore0,
+    Fstore1,
+    Fstore2,
+    Fstore3,
+    Dstore0,
+    Dstore1,
+    Dstore2,
+    Dstore3,
+    Astore0,
+    Astore1,
+    Astore2,
+    Astore3,
+    Iastore,
+    Lastore,
+    Fastore,
+    Dastore,
+    Aastore,
+    Bastore,
+    Castore,
+    Sastore,
+    Pop,
+    Pop2,
+    Dup,
+    DupX1,
+    DupX2,
+    Dup2,
+    Dup2X1,
+    Dup2X2,
+    Swap,
+    Iadd,
+    Ladd,
+    Fadd,
+    Dadd,
+    Isub,
+    Lsub,
+    Fsub,
+    Dsub,
+    Imul,
+    Lmul,
+    Fmul,
+    Dmul,
+    Idiv,
+    Ldiv,
+    Fdiv,
+    Ddiv,
+    Irem,
+    Lrem,
+    Frem,
+    Drem,
+    Ineg,
+    Lneg,
+    Fneg,
+    Dneg,
+    Ishl,
+    Lshl,
+    Ishr,
+    Lshr,
+    Iushr,
+    Lushr,
+    Iand,
+    Land,
+    Ior,
+    Lor,
+    Ixor,
+    Lxor,
+    Iinc(u8, i8),
+    I2l,
+    I2f,
+    I2d,
+    L2i,
+    L2f,
+    L2d,
+    F2i,
+    F2l,
+    F2d,
+    D2i,
+    D2l,
+    D2f,
+    I2b,
+    I2c,
+    I2s,
+    Lcmp,
+    Fcmpl,
+    Fcmpg,
+    Dcmpl,
+    Dcmpg,
+    Ifeq(u32),
+    Ifne(u32),
+    Iflt(u32),
+    Ifge(u32),
+    Ifgt(u32),
+    Ifle(u32),
+    IfIcmpeq(u32),
+    IfIcmpne(u32),
+    IfIcmplt(u32),
+    IfIcmpge(u32),
+    IfIcmpgt(u32),
+    IfIcmple(u32),
+    IfAcmpeq(u32),
+    IfAcmpne(u32),
+    Goto(u32),
+    Jsr(u32),
+    Ret(u8),
+    Tableswitch {
+        default: u32,
+        low: i32,
+        high: i32,
+        targets: Vec<u32>,
+    },
+    Lookupswitch {
+        default: u32,
+        pairs: Vec<(i32, u32)>,
+    },
+    Ireturn,
+    Lreturn,
+    Freturn,
+    Dreturn,
+    Areturn,
+    Return,
+    Getstatic(u16),
+    Putstatic(u16),
+    Getfield(u16),
+    Putfield(u16),
+    Invokevirtual(u16),
+    Invokespecial(u16),
+    Invokestatic(u16),
+    Invokeinterface(u16, u8),
+    Invokedynamic(u16),
+    New(u16),
+    Newarray(ArrayType),
+    Anewarray(u16),
+    Arraylength,
+    Athrow,
+    Checkcast(u16),
+    Instanceof(u16),
+    Monitorenter,
+    Monitorexit,
+    Wide(WideInstruction),
+    Multianewarray(u16, u8),
+    Ifnull(u32),
+    Ifnonnull(u32),
+    GotoW(u32),
+    JsrW(u32),
+}
+
+/// The instructions that can be modified by `wide` to take 16 bit local indices
+#[derive(Debug, Clone, PartialEq)]
+pub enum WideInstruction {
+    Iload(u16),
+    Lload(u16),
+    Fload(u16),
+    Dload(u16),
+    Aload(u16),
+    Istore(u16),
+    Lstore(u16),
+    Fstore(u16),
+    Dstore(u16),
+    Astore(u16),
+    Ret(u16),
+    Iinc(u16, i16),
+}
+
+/// Element type operand of `newarray`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArrayType {
+    Boolean = 4,
+    Char = 5,
+    Float = 6,
+    Double = 7,
+    Byte = 8,
+    Short = 9,
+    Int = 10,
+    Long = 11,
+}
+
+impl ArrayType {
+    fn parse(atype: u8) -> Option<ArrayType> {
+        match atype {
+            4 => Some(ArrayType::Boolean),
+            5 => Some(ArrayType::Char),
+            6 => Some(ArrayType::Float),
+            7 => Some(ArrayType::Double),
+            8 => Some(ArrayType::Byte),
+            9 => Some(ArrayType::Short),
+            10 => Some(ArrayType::Int),
+            11 => Some(ArrayType::Long),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ArrayType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ArrayType::Boolean => "boolean",
+            ArrayType::Char => "char",
+            ArrayType::Float => "float",
+            ArrayType::Double => "double",
+            ArrayType::Byte => "byte",
+            ArrayType::Short => "short",
+            ArrayType::Int => "int",
+            ArrayType::Long => "long",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl Instruction {
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Self::Nop => 0x00,
+            Self::AconstNull => 0x01,
+            Self::IconstM1 => 0x02,
+            Self::Iconst0 => 0x03,
+            Self::Iconst1 => 0x04,
+            Self::Iconst2 => 0x05,
+            Self::Iconst3 => 0x06,
+            Self::Iconst4 => 0x07,
+            Self::Iconst5 => 0x08,
+            Self::Lconst0 => 0x09,
+            Self::Lconst1 => 0x0a,
+            Self::Fconst0 => 0x0b,
+            Self::Fconst1 => 0x0c,
+            Self::Fconst2 => 0x0d,
+            Self::Dconst0 => 0x0e,
+            Self::Dconst1 => 0x0f,
+            Self::Bipush(..) => 0x10,
+            Self::Sipush(..) => 0x11,
+            Self::Ldc(..) => 0x12,
+            Self::LdcW(..) => 0x13,
+            Self::Ldc2W(..) => 0x14,
+            Self::Iload(..) => 0x15,
+            Self::Lload(..) => 0x16,
+            Self::Fload(..) => 0x17,
+            Self::Dload(..) => 0x18,
+            Self::Aload(..) => 0x19,
+            Self::Iload0 => 0x1a,
+            Self::Iload1 => 0x1b,
+            Self::Iload2 => 0x1c,
+            Self::Iload3 => 0x1d,
+            Self::Lload0 => 0x1e,
+            Self::Lload1 => 0x1f,
+            Self::Lload2 => 0x20,
+            Self::Lload3 => 0x21,
+            Self::Fload0 => 0x22,
+            Self::Fload1 => 0x23,
+            Self::Fload2 => 0x24,
+            Self::Fload3 => 0x25,
+            Self::Dload0 => 0x26,
+            Self::Dload1 => 0x27,
+            Self::Dload2 => 0x28,
+            Self::Dload3 => 0x29,
+            Self::Aload0 => 0x2a,
+            Self::Aload1 => 0x2b,
+            Self::Aload2 => 0x2c,
+            Self::Aload3 => 0x2d,
+            Self::Iaload => 0x2e,
+            Self::Laload => 0x2f,
+            Self::Faload => 0x30,
+            Self::Daload => 0x31,
+            Self::Aaload => 0x32,
+            Self::Baload => 0x33,
+            Self::Caload => 0x34,
+            Self::Saload => 0x35,
+            Self::Istore(..) => 0x36,
+            Self::Lstore(..) => 0x37,
+            Self::Fstore(..) => 0x38,
+            Self::Dstore(..) => 0x39,
+            Self::Astore(..) => 0x3a,
+            Self::Istore0 => 0x3b,
+            Self::Istore1 => 0x3c,
+            Self::Istore2 => 0x3d,
+            Self::Istore3 => 0x3e,
+            Self::Lstore0 => 0x3f,
+            Self::Lstore1 => 0x40,
+            Self::Lstore2 => 0x41,
+            Self::Lstore3 => 0x42,
+            Self::Fstore0 => 0x43,
+            Self::Fstore1 => 0x44,
+            Self::Fstore2 => 0x45,
+            Self::Fstore3 => 0x46,
+            Self::Dstore0 => 0x47,
+            Self::Dstore1 => 0x48,
+            Self::Dstore2 => 0x49,
+            Self::Dstore3 => 0x4a,
+            Self::Astore0 => 0x4b,
+            Self::Astore1 => 0x4c,
+            Self::Astore2 => 0x4d,
+            Self::Astore3 => 0x4e,
+            Self::Iastore => 0x4f,
+            Self::Lastore => 0x50,
+            Self::Fastore => 0x51,
+            Self::Dastore => 0x52,
+            Self::Aastore => 0x53,
+            Self::Bastore => 0x54,
+            Self::Castore => 0x55,
+            Self::Sastore => 0x56,
+            Self::Pop => 0x57,
+            Self::Pop2 => 0x58,
+            Self::Dup => 0x59,
+            Self::DupX1 => 0x5a,
+            Self::DupX2 => 0x5b,
+            Self::Dup2 => 0x5c,
+            Self::Dup2X1 => 0x5d,
+            Self::Dup2X2 => 0x5e,
+            Self::Swap => 0x5f,
+            Self::Iadd => 0x60,
+            Self::Ladd => 0x61,
+            Self::Fadd => 0x62,
+            Self::Dadd => 0x63,
+            Self::Isub => 0x64,
+            Self::Lsub => 0x65,
+            Self::Fsub => 0x66,
+            Self::Dsub => 0x67,
+            Self::Imul => 0x68,
+            Self::Lmul => 0x69,
+            Self::Fmul => 0x6a,
+            Self::Dmul => 0x6b,
+            Self::Idiv => 0x6c,
+            Self::Ldiv => 0x6d,
+            Self::Fdiv => 0x6e,
+            Self::Ddiv => 0x6f,
+            Self::Irem => 0x70,
+            Self::Lrem => 0x71,
+            Self::Frem => 0x72,
+            Self::Drem => 0x73,
+            Self::Ineg => 0x74,
+            Self::Lneg => 0x75,
+            Self::Fneg => 0x76,
+            Self::Dneg => 0x77,
+            Self::Ishl => 0x78,
+            Self::Lshl => 0x79,
+            Self::Ishr => 0x7a,
+            Self::Lshr => 0x7b,
+            Self::Iushr => 0x7c,
+            Self::Lushr => 0x7d,
+            Self::Iand => 0x7e,
+            Self::Land => 0x7f,
+            Self::Ior => 0x80,
+            Self::Lor => 0x81,
+            Self::Ixor => 0x82,
+            Self::Lxor => 0x83,
+            Self::Iinc(..) => 0x84,
+            Self::I2l => 0x85,
+            Self::I2f => 0x86,
+            Self::I2d => 0x87,
+            Self::L2i => 0x88,
+            Self::L2f => 0x89,
+            Self::L2d => 0x8a,
+            Self::F2i => 0x8b,
+            Self::F2l => 0x8c,
+            Self::F2d => 0x8d,
+            Self::D2i => 0x8e,
+            Self::D2l => 0x8f,
+            Self::D2f => 0x90,
+            Self::I2b => 0x91,
+            Self::I2c => 0x92,
+            Self::I2s => 0x93,
+            Self::Lcmp => 0x94,
+            Self::Fcmpl => 0x95,
+            Self::Fcmpg => 0x96,
+            Self::Dcmpl => 0x97,
+            Self::Dcmpg => 0x98,
+            Self::Ifeq(..) => 0x99,
+            Self::Ifne(..) => 0x9a,
+            Self::Iflt(..) => 0x9b,
+            Self::Ifge(..) => 0x9c,
+            Self::Ifgt(..) => 0x9d,
+            Self::Ifle(..) => 0x9e,
+            Self::IfIcmpeq(..) => 0x9f,
+            Self::IfIcmpne(..) => 0xa0,
+            Self::IfIcmplt(..) => 0xa1,
+            Self::IfIcmpge(..) => 0xa2,
+            Self::IfIcmpgt(..) => 0xa3,
+            Self::IfIcmple(..) => 0xa4,
+            Self::IfAcmpeq(..) => 0xa5,
+            Self::IfAcmpne(..) => 0xa6,
+            Self::Goto(..) => 0xa7,
+            Self::Jsr(..) => 0xa8,
+            Self::Ret(..) => 0xa9,
+            Self::Tableswitch { .. } => 0xaa,
+            Self::Lookupswitch { .. } => 0xab,
+            Self::Ireturn => 0xac,
+            Self::Lreturn => 0xad,
+            Self::Freturn => 0xae,
+            Self::Dreturn => 0xaf,
+            Self::Areturn => 0xb0,
+            Self::Return => 0xb1,
+            Self::Getstatic(..) => 0xb2,
+            Self::Putstatic(..) => 0xb3,
+            Self::Getfield(..) => 0xb4,
+            Self::Putfield(..) => 0xb5,
+            Self::Invokevirtual(..) => 0xb6,
+            Self::Invokespecial(..) => 0xb7,
+            Self::Invokestatic(..) => 0xb8,
+            Self::Invokeinterface(..) => 0xb9,
+            Self::Invokedynamic(..) => 0xba,
+            Self::New(..) => 0xbb,
+            Self::Newarray(..) => 0xbc,
+            Self::Anewarray(..) => 0xbd,
+            Self::Arraylength => 0xbe,
+            Self::Athrow => 0xbf,
+            Self::Checkcast(..) => 0xc0,
+            Self::Instanceof(..) => 0xc1,
+            Self::Monitorenter => 0xc2,
+            Self::Monitorexit => 0xc3,
+            Self::Wide(_) => 0xc4,
+            Self::Multianewarray(..) => 0xc5,
+            Self::Ifnull(..) => 0xc6,
+            Self::Ifnonnull(..) => 0xc7,
+            Self::GotoW(..) => 0xc8,
+            Self::JsrW(..) => 0xc9,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        BYTECODETABLE[self.opcode() as usize].1
+    }
+}
+
+impl WideInstruction {
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Self::Iload(_) => 0x15,
+            Self::Lload(_) => 0x16,
+            Self::Fload(_) => 0x17,
+            Self::Dload(_) => 0x18,
+            Self::Aload(_) => 0x19,
+            Self::Istore(_) => 0x36,
+            Self::Lstore(_) => 0x37,
+            Self::Fstore(_) => 0x38,
+            Self::Dstore(_) => 0x39,
+            Self::Astore(_) => 0x3a,
+            Self::Ret(_) => 0xa9,
+            Self::Iinc(..) => 0x84,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        BYTECODETABLE[self.opcode() as usize].1
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.mnemonic();
+        match self {
+            Self::Bipush(value) => write!(f, "{name} {value}"),
+            Self::Sipush(value) => write!(f, "{name} {value}"),
+            Self::Ldc(index) => write!(f, "{name} #{index}"),
+            Self::LdcW(index)
+            | Self::Ldc2W(index)
+            | Self::Getstatic(index)
+            | Self::Putstatic(index)
+            | Self::Getfield(index)
+            | Self::Putfield(index)
+            | Self::Invokevirtual(index)
+            | Self::Invokespecial(index)
+            | Self::Invokestatic(index)
+            | Self::New(index)
+            | Self::Anewarray(index)
+            | Self::Checkcast(index)
+            | Self::Instanceof(index)
+            | Self::Invokedynamic(index) => write!(f, "{name} #{index}"),
+            Self::Iload(index)
+            | Self::Lload(index)
+            | Self::Fload(index)
+            | Self::Dload(index)
+            | Self::Aload(index)
+            | Self::Istore(index)
+            | Self::Lstore(index)
+            | Self::Fstore(index)
+            | Self::Dstore(index)
+            | Self::Astore(index)
+            | Self::Ret(index) => write!(f, "{name} {index}"),
+            Self::Iinc(index, constant) => write!(f, "{name} {index}, {constant}"),
+            Self::Ifeq(target)
+            | Self::Ifne(target)
+            | Self::Iflt(target)
+            | Self::Ifge(target)
+            | Self::Ifgt(target)
+            | Self::Ifle(target)
+            | Self::IfIcmpeq(target)
+            | Self::IfIcmpne(target)
+            | Self::IfIcmplt(target)
+            | Self::IfIcmpge(target)
+            | Self::IfIcmpgt(target)
+            | Self::IfIcmple(target)
+            | Self::IfAcmpeq(target)
+            | Self::IfAcmpne(target)
+            | Self::Goto(target)
+            | Self::Jsr(target)
+            | Self::Ifnull(target)
+            | Self::Ifnonnull(target)
+            | Self::GotoW(target)
+            | Self::JsrW(target) => write!(f, "{name} {target}"),
+            Self::Tableswitch {
+                default,
+                low,
+                targets,
+                ..
+            } => {
+                write!(f, "{name} {{ ")?;
+                for (key, target) in (*low..).zip(targets) {
+                    write!(f, "{key}: {target}, ")?;
+                }
+                write!(f, "default: {default} }}")
+            }
+            Self::Lookupswitch { default, pairs } => {
+                write!(f, "{name} {{ ")?;
+                for (key, target) in pairs {
+                    write!(f, "{key}: {target}, ")?;
+                }
+                write!(f, "default: {default} }}")
+            }
+            Self::Invokeinterface(index, count) => write!(f, "{name} #{index}, {count}"),
+            Self::Newarray(atype) => write!(f, "{name} {atype}"),
+            Self::Multianewarray(index, dimensions) => {
+                write!(f, "{name} #{index}, {dimensions}")
+            }
+            Self::Wide(instr) => write!(f, "{name} {instr}"),
+            _ => write!(f, "{name}"),
+        }
+    }
+}
 
-    let mut instructions: Vec<String> = vec![];
-    while !bytes.xs.is_empty() {
-        let x = bytes.parse_u1();
+impl fmt::Display for WideInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = self.mnemonic();
+        match self {
+            Self::Iinc(index, constant) => write!(f, "{name} {index}, {constant}"),
+            Self::Iload(index)
+            | Self::Lload(index)
+            | Self::Fload(index)
+            | Self::Dload(index)
+            | Self::Aload(index)
+            | Self::Istore(index)
+            | Self::Lstore(index)
+            | Self::Fstore(index)
+            | Self::Dstore(index)
+            | Self::Astore(index)
+            | Self::Ret(index) => write!(f, "{name} {index}"),
+        }
+    }
+}
 
-        if !bytecodes.contains_key(&x) {
-            eprintln!("[ERROR]: Unknown Instruction 0x{:x}", x);
-            continue;
+impl Instruction {
+    /// The constant pool index this instruction refers to, if any
+    pub fn constant_pool_index(&self) -> Option<u16> {
+        match self {
+            Self::Ldc(index) => Some(*index as u16),
+            Self::LdcW(index)
+            | Self::Ldc2W(index)
+            | Self::Getstatic(index)
+            | Self::Putstatic(index)
+            | Self::Getfield(index)
+            | Self::Putfield(index)
+            | Self::Invokevirtual(index)
+            | Self::Invokespecial(index)
+            | Self::Invokestatic(index)
+            | Self::Invokeinterface(index, _)
+            | Self::Invokedynamic(index)
+            | Self::New(index)
+            | Self::Anewarray(index)
+            | Self::Checkcast(index)
+            | Self::Instanceof(index)
+            | Self::Multianewarray(index, _) => Some(*index),
+            _ => None,
         }
+    }
+}
 
-        match x {
-            LDC => {
-                let _ = bytes.parse_u1();
-                let instr = bytecodes.get(&x).unwrap();
-                instructions.push(instr.to_string())
+/// Renders decoded instructions javap style, with constant pool operands resolved
+/// into a trailing comment such as `// Field java/lang/System.out:Ljava/io/PrintStream;`
+pub fn disassemble(code: &[(u32, Instruction)], cp: &ConstantPool) -> Vec<String> {
+    code.iter()
+        .map(|(pc, instr)| match instr.constant_pool_index() {
+            Some(index) => format!(
+                "{pc:>6}: {:<40}// {}",
+                instr.to_string(),
+                cp.describe(index as usize)
+            ),
+            None => format!("{pc:>6}: {instr}"),
+        })
+        .collect()
+}
+
+/// Decodes a code array into its instructions, each paired with its pc
+pub fn parse_byte_code(mut bytes: ByteStream) -> Result<Vec<(u32, Instruction)>, ParseError> {
+    let code_start = bytes.offset();
+    let code_length = bytes.remaining();
+    let mut instructions: Vec<(u32, Instruction)> = vec![];
+    while !bytes.is_empty() {
+        let pc = bytes.offset() - code_start;
+        let instr = parse_instruction(&mut bytes, pc, code_length)?;
+        instructions.push((pc as u32, instr));
+    }
+
+    Ok(instructions)
+}
+
+fn parse_instruction(
+    bytes: &mut ByteStream,
+    pc: usize,
+    code_length: usize,
+) -> Result<Instruction, ParseError> {
+    let x = bytes.parse_u1()?;
+    let instr = match x {
+        0x00 => Instruction::Nop,
+        0x01 => Instruction::AconstNull,
+        0x02 => Instruction::IconstM1,
+        0x03 => Instruction::Iconst0,
+        0x04 => Instruction::Iconst1,
+        0x05 => Instruction::Iconst2,
+        0x06 => Instruction::Iconst3,
+        0x07 => Instruction::Iconst4,
+        0x08 => Instruction::Iconst5,
+        0x09 => Instruction::Lconst0,
+        0x0a => Instruction::Lconst1,
+        0x0b => Instruction::Fconst0,
+        0x0c => Instruction::Fconst1,
+        0x0d => Instruction::Fconst2,
+        0x0e => Instruction::Dconst0,
+        0x0f => Instruction::Dconst1,
+        0x10 => Instruction::Bipush(bytes.parse_u1()? as i8),
+        0x11 => Instruction::Sipush(bytes.parse_u2()? as i16),
+        0x12 => Instruction::Ldc(bytes.parse_u1()?),
+        0x13 => Instruction::LdcW(bytes.parse_u2()?),
+        0x14 => Instruction::Ldc2W(bytes.parse_u2()?),
+        0x15 => Instruction::Iload(bytes.parse_u1()?),
+        0x16 => Instruction::Lload(bytes.parse_u1()?),
+        0x17 => Instruction::Fload(bytes.parse_u1()?),
+        0x18 => Instruction::Dload(bytes.parse_u1()?),
+        0x19 => Instruction::Aload(bytes.parse_u1()?),
+        0x1a => Instruction::Iload0,
+        0x1b => Instruction::Iload1,
+        0x1c => Instruction::Iload2,
+        0x1d => Instruction::Iload3,
+        0x1e => Instruction::Lload0,
+        0x1f => Instruction::Lload1,
+        0x20 => Instruction::Lload2,
+        0x21 => Instruction::Lload3,
+        0x22 => Instruction::Fload0,
+        0x23 => Instruction::Fload1,
+        0x24 => Instruction::Fload2,
+        0x25 => Instruction::Fload3,
+        0x26 => Instruction::Dload0,
+        0x27 => Instruction::Dload1,
+        0x28 => Instruction::Dload2,
+        0x29 => Instruction::Dload3,
+        0x2a => Instruction::Aload0,
+        0x2b => Instruction::Aload1,
+        0x2c => Instruction::Aload2,
+        0x2d => Instruction::Aload3,
+        0x2e => Instruction::Iaload,
+        0x2f => Instruction::Laload,
+        0x30 => Instruction::Faload,
+        0x31 => Instruction::Daload,
+        0x32 => Instruction::Aaload,
+        0x33 => Instruction::Baload,
+        0x34 => Instruction::Caload,
+        0x35 => Instruction::Saload,
+        0x36 => Instruction::Istore(bytes.parse_u1()?),
+        0x37 => Instruction::Lstore(bytes.parse_u1()?),
+        0x38 => Instruction::Fstore(bytes.parse_u1()?),
+        0x39 => Instruction::Dstore(bytes.parse_u1()?),
+        0x3a => Instruction::Astore(bytes.parse_u1()?),
+        0x3b => Instruction::Istore0,
+        0x3c => Instruction::Istore1,
+        0x3d => Instruction::Istore2,
+        0x3e => Instruction::Istore3,
+        0x3f => Instruction::Lstore0,
+        0x40 => Instruction::Lstore1,
+        0x41 => Instruction::Lstore2,
+        0x42 => Instruction::Lstore3,
+        0x43 => Instruction::Fstore0,
+        0x44 => Instruction::Fstore1,
+        0x45 => Instruction::Fstore2,
+        0x46 => Instruction::Fstore3,
+        0x47 => Instruction::Dstore0,
+        0x48 => Instruction::Dstore1,
+        0x49 => Instruction::Dstore2,
+        0x4a => Instruction::Dstore3,
+        0x4b => Instruction::Astore0,
+        0x4c => Instruction::Astore1,
+        0x4d => Instruction::Astore2,
+        0x4e => Instruction::Astore3,
+        0x4f => Instruction::Iastore,
+        0x50 => Instruction::Lastore,
+        0x51 => Instruction::Fastore,
+        0x52 => Instruction::Dastore,
+        0x53 => Instruction::Aastore,
+        0x54 => Instruction::Bastore,
+        0x55 => Instruction::Castore,
+        0x56 => Instruction::Sastore,
+        0x57 => Instruction::Pop,
+        0x58 => Instruction::Pop2,
+        0x59 => Instruction::Dup,
+        0x5a => Instruction::DupX1,
+        0x5b => Instruction::DupX2,
+        0x5c => Instruction::Dup2,
+        0x5d => Instruction::Dup2X1,
+        0x5e => Instruction::Dup2X2,
+        0x5f => Instruction::Swap,
+        0x60 => Instruction::Iadd,
+        0x61 => Instruction::Ladd,
+        0x62 => Instruction::Fadd,
+        0x63 => Instruction::Dadd,
+        0x64 => Instruction::Isub,
+        0x65 => Instruction::Lsub,
+        0x66 => Instruction::Fsub,
+        0x67 => Instruction::Dsub,
+        0x68 => Instruction::Imul,
+        0x69 => Instruction::Lmul,
+        0x6a => Instruction::Fmul,
+        0x6b => Instruction::Dmul,
+        0x6c => Instruction::Idiv,
+        0x6d => Instruction::Ldiv,
+        0x6e => Instruction::Fdiv,
+        0x6f => Instruction::Ddiv,
+        0x70 => Instruction::Irem,
+        0x71 => Instruction::Lrem,
+        0x72 => Instruction::Frem,
+        0x73 => Instruction::Drem,
+        0x74 => Instruction::Ineg,
+        0x75 => Instruction::Lneg,
+        0x76 => Instruction::Fneg,
+        0x77 => Instruction::Dneg,
+        0x78 => Instruction::Ishl,
+        0x79 => Instruction::Lshl,
+        0x7a => Instruction::Ishr,
+        0x7b => Instruction::Lshr,
+        0x7c => Instruction::Iushr,
+        0x7d => Instruction::Lushr,
+        0x7e => Instruction::Iand,
+        0x7f => Instruction::Land,
+        0x80 => Instruction::Ior,
+        0x81 => Instruction::Lor,
+        0x82 => Instruction::Ixor,
+        0x83 => Instruction::Lxor,
+        0x84 => Instruction::Iinc(bytes.parse_u1()?, bytes.parse_u1()? as i8),
+        0x85 => Instruction::I2l,
+        0x86 => Instruction::I2f,
+        0x87 => Instruction::I2d,
+        0x88 => Instruction::L2i,
+        0x89 => Instruction::L2f,
+        0x8a => Instruction::L2d,
+        0x8b => Instruction::F2i,
+        0x8c => Instruction::F2l,
+        0x8d => Instruction::F2d,
+        0x8e => Instruction::D2i,
+        0x8f => Instruction::D2l,
+        0x90 => Instruction::D2f,
+        0x91 => Instruction::I2b,
+        0x92 => Instruction::I2c,
+        0x93 => Instruction::I2s,
+        0x94 => Instruction::Lcmp,
+        0x95 => Instruction::Fcmpl,
+        0x96 => Instruction::Fcmpg,
+        0x97 => Instruction::Dcmpl,
+        0x98 => Instruction::Dcmpg,
+        0x99 => Instruction::Ifeq(branch(bytes, pc, code_length)?),
+        0x9a => Instruction::Ifne(branch(bytes, pc, code_length)?),
+        0x9b => Instruction::Iflt(branch(bytes, pc, code_length)?),
+        0x9c => Instruction::Ifge(branch(bytes, pc, code_length)?),
+        0x9d => Instruction::Ifgt(branch(bytes, pc, code_length)?),
+        0x9e => Instruction::Ifle(branch(bytes, pc, code_length)?),
+        0x9f => Instruction::IfIcmpeq(branch(bytes, pc, code_length)?),
+        0xa0 => Instruction::IfIcmpne(branch(bytes, pc, code_length)?),
+        0xa1 => Instruction::IfIcmplt(branch(bytes, pc, code_length)?),
+        0xa2 => Instruction::IfIcmpge(branch(bytes, pc, code_length)?),
+        0xa3 => Instruction::IfIcmpgt(branch(bytes, pc, code_length)?),
+        0xa4 => Instruction::IfIcmple(branch(bytes, pc, code_length)?),
+        0xa5 => Instruction::IfAcmpeq(branch(bytes, pc, code_length)?),
+        0xa6 => Instruction::IfAcmpne(branch(bytes, pc, code_length)?),
+        0xa7 => Instruction::Goto(branch(bytes, pc, code_length)?),
+        0xa8 => Instruction::Jsr(branch(bytes, pc, code_length)?),
+        0xa9 => Instruction::Ret(bytes.parse_u1()?),
+        0xac => Instruction::Ireturn,
+        0xad => Instruction::Lreturn,
+        0xae => Instruction::Freturn,
+        0xaf => Instruction::Dreturn,
+        0xb0 => Instruction::Areturn,
+        0xb1 => Instruction::Return,
+        0xb2 => Instruction::Getstatic(bytes.parse_u2()?),
+        0xb3 => Instruction::Putstatic(bytes.parse_u2()?),
+        0xb4 => Instruction::Getfield(bytes.parse_u2()?),
+        0xb5 => Instruction::Putfield(bytes.parse_u2()?),
+        0xb6 => Instruction::Invokevirtual(bytes.parse_u2()?),
+        0xb7 => Instruction::Invokespecial(bytes.parse_u2()?),
+        0xb8 => Instruction::Invokestatic(bytes.parse_u2()?),
+        0xbb => Instruction::New(bytes.parse_u2()?),
+        0xbc => {
+            let atype = bytes.parse_u1()?;
+            match ArrayType::parse(atype) {
+                Some(atype) => Instruction::Newarray(atype),
+                None => return Err(bytes.error(ParseErrorKind::UnknownArrayType { atype, pc })),
+            }
+        }
+        0xbd => Instruction::Anewarray(bytes.parse_u2()?),
+        0xbe => Instruction::Arraylength,
+        0xbf => Instruction::Athrow,
+        0xc0 => Instruction::Checkcast(bytes.parse_u2()?),
+        0xc1 => Instruction::Instanceof(bytes.parse_u2()?),
+        0xc2 => Instruction::Monitorenter,
+        0xc3 => Instruction::Monitorexit,
+        0xc5 => {
+            let index = bytes.parse_u2()?;
+            let dimensions = bytes.parse_u1()?;
+            if dimensions == 0 {
+                return Err(bytes.error(ParseErrorKind::ZeroDimensions { pc }));
+            }
+            Instruction::Multianewarray(index, dimensions)
+        }
+        0xc6 => Instruction::Ifnull(branch(bytes, pc, code_length)?),
+        0xc7 => Instruction::Ifnonnull(branch(bytes, pc, code_length)?),
+        0xc8 => Instruction::GotoW(branch_wide(bytes, pc, code_length)?),
+        0xc9 => Instruction::JsrW(branch_wide(bytes, pc, code_length)?),
+        0xaa => {
+            skip_switch_padding(bytes, pc)?;
+            let default = branch_wide(bytes, pc, code_length)?;
+            let low = bytes.parse_u4()? as i32;
+            let high = bytes.parse_u4()? as i32;
+            if low > high {
+                return Err(bytes.error(ParseErrorKind::InvalidTableSwitch { low, high, pc }));
+            }
+            let mut targets: Vec<u32> = vec![];
+            for _ in low..=high {
+                targets.push(branch_wide(bytes, pc, code_length)?);
             }
-            INVOKESPECIAL | GETSTATIC | INVOKEVIRTUAL => {
-                let _ = bytes.parse_u2();
-                let instr = bytecodes.get(&x).unwrap();
-                instructions.push(instr.to_string())
+            Instruction::Tableswitch {
+                default,
+                low,
+                high,
+                targets,
+            }
+        }
+        0xab => {
+            skip_switch_padding(bytes, pc)?;
+            let default = branch_wide(bytes, pc, code_length)?;
+            let mut pairs: Vec<(i32, u32)> = vec![];
+            for _ in 0..bytes.parse_u4()? {
+                let key = bytes.parse_u4()? as i32;
+                pairs.push((key, branch_wide(bytes, pc, code_length)?));
+            }
+            Instruction::Lookupswitch { default, pairs }
+        }
+        0xb9 => {
+            let index = bytes.parse_u2()?;
+            let count = bytes.parse_u1()?;
+            // the fourth operand byte is always zero
+            let _ = bytes.parse_u1()?;
+            Instruction::Invokeinterface(index, count)
+        }
+        0xba => {
+            let index = bytes.parse_u2()?;
+            // the last two operand bytes are always zero
+            let _ = bytes.parse_u2()?;
+            Instruction::Invokedynamic(index)
+        }
+        0xc4 => Instruction::Wide(parse_wide_instruction(bytes, pc)?),
+        _ => {
+            return Err(bytes.error(ParseErrorKind::UnknownInstruction { opcode: x, pc }));
+        }
+    };
+    Ok(instr)
+}
+
+fn parse_wide_instruction(
+    bytes: &mut ByteStream,
+    pc: usize,
+) -> Result<WideInstruction, ParseError> {
+    let x = bytes.parse_u1()?;
+    let instr = match x {
+        0x15 => WideInstruction::Iload(bytes.parse_u2()?),
+        0x16 => WideInstruction::Lload(bytes.parse_u2()?),
+        0x17 => WideInstruction::Fload(bytes.parse_u2()?),
+        0x18 => WideInstruction::Dload(bytes.parse_u2()?),
+        0x19 => WideInstruction::Aload(bytes.parse_u2()?),
+        0x36 => WideInstruction::Istore(bytes.parse_u2()?),
+        0x37 => WideInstruction::Lstore(bytes.parse_u2()?),
+        0x38 => WideInstruction::Fstore(bytes.parse_u2()?),
+        0x39 => WideInstruction::Dstore(bytes.parse_u2()?),
+        0x3a => WideInstruction::Astore(bytes.parse_u2()?),
+        0xa9 => WideInstruction::Ret(bytes.parse_u2()?),
+        0x84 => WideInstruction::Iinc(bytes.parse_u2()?, bytes.parse_u2()? as i16),
+        _ => {
+            return Err(bytes.error(ParseErrorKind::InvalidWideInstruction { opcode: x, pc }));
+        }
+    };
+    Ok(instr)
+}
+
+/// Reads a 16 bit branch offset and resolves it to an absolute pc
+fn branch(bytes: &mut ByteStream, pc: usize, code_length: usize) -> Result<u32, ParseError> {
+    let offset = bytes.parse_u2()? as i16 as i32;
+    branch_target(bytes, pc, offset, code_length)
+}
+
+/// Reads a 32 bit branch offset and resolves it to an absolute pc
+fn branch_wide(bytes: &mut ByteStream, pc: usize, code_length: usize) -> Result<u32, ParseError> {
+    let offset = bytes.parse_u4()? as i32;
+    branch_target(bytes, pc, offset, code_length)
+}
+
+fn branch_target(
+    bytes: &ByteStream,
+    pc: usize,
+    offset: i32,
+    code_length: usize,
+) -> Result<u32, ParseError> {
+    let target = pc as i64 + offset as i64;
+    if target < 0 || target >= code_length as i64 {
+        return Err(bytes.error(ParseErrorKind::InvalidBranchTarget { target, pc }));
+    }
+    Ok(target as u32)
+}
+
+/// tableswitch and lookupswitch operands start at an address that is a multiple of four
+/// bytes from the start of the code array
+fn skip_switch_padding(bytes: &mut ByteStream, pc: usize) -> Result<(), ParseError> {
+    let padding = (4 - (pc + 1) % 4) % 4;
+    bytes.parse_n(padding)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::constantpool::{Constant, Utf8};
+    use crate::parsers::error::Section;
+
+    fn parse(code: &[u8]) -> Result<Vec<(u32, Instruction)>, ParseError> {
+        parse_byte_code(ByteStream::new(code))
+    }
+
+    fn instructions(code: &[u8]) -> Vec<Instruction> {
+        parse(code)
+            .unwrap()
+            .into_iter()
+            .map(|(_, instr)| instr)
+            .collect()
+    }
+
+    #[test]
+    fn decodes_operands() {
+        #[rustfmt::skip]
+        let code = [
+            0x10, 0xFF,             // bipush -1
+            0x11, 0x80, 0x00,       // sipush -32768
+            0x12, 0x07,             // ldc #7
+            0x13, 0x01, 0x02,       // ldc_w #258
+            0x19, 0xFF,             // aload 255
+            0x84, 0x03, 0xFE,       // iinc 3, -2
+            0xB9, 0x00, 0x05, 0x02, 0x00, // invokeinterface #5, 2
+            0xBA, 0x00, 0x06, 0x00, 0x00, // invokedynamic #6
+            0xBC, 0x05,             // newarray char
+            0xC5, 0x00, 0x08, 0x03, // multianewarray #8, 3
+        ];
+        assert_eq!(
+            instructions(&code),
+            vec![
+                Instruction::Bipush(-1),
+                Instruction::Sipush(-32768),
+                Instruction::Ldc(7),
+                Instruction::LdcW(258),
+                Instruction::Aload(255),
+                Instruction::Iinc(3, -2),
+                Instruction::Invokeinterface(5, 2),
+                Instruction::Invokedynamic(6),
+                Instruction::Newarray(ArrayType::Char),
+                Instruction::Multianewarray(8, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn pairs_instructions_with_their_pc() {
+        let pcs: Vec<u32> = parse(&[0x00, 0x10, 0x01, 0x11, 0x00, 0x01, 0xB1])
+            .unwrap()
+            .into_iter()
+            .map(|(pc, _)| pc)
+            .collect();
+        assert_eq!(pcs, vec![0, 1, 3, 6]);
+    }
+
+    #[test]
+    fn resolves_branches_to_absolute_targets() {
+        #[rustfmt::skip]
+        let code = [
+            0x00,                         // 0: nop
+            0x99, 0x00, 0x07,             // 1: ifeq +7
+            0xA7, 0xFF, 0xFC,             // 4: goto -4
+            0xC8, 0xFF, 0xFF, 0xFF, 0xF9, // 7: goto_w -7
+            0xB1,                         // 12: return
+        ];
+        assert_eq!(
+            instructions(&code),
+            vec![
+                Instruction::Nop,
+                Instruction::Ifeq(8),
+                Instruction::Goto(0),
+                Instruction::GotoW(0),
+                Instruction::Return,
+            ]
+        );
+    }
+
+    #[test]
+    fn decodes_wide_instructions() {
+        #[rustfmt::skip]
+        let code = [
+            0xC4, 0x15, 0x01, 0x00,             // wide iload 256
+            0xC4, 0x84, 0x01, 0x00, 0xFF, 0x00, // wide iinc 256, -256
+            0xC4, 0xA9, 0x00, 0x02,             // wide ret 2
+        ];
+        assert_eq!(
+            instructions(&code),
+            vec![
+                Instruction::Wide(WideInstruction::Iload(256)),
+                Instruction::Wide(WideInstruction::Iinc(256, -256)),
+                Instruction::Wide(WideInstruction::Ret(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_tableswitch_padding() {
+        #[rustfmt::skip]
+        let code = [
+            0xAA, 0, 0, 0,       // 0: tableswitch, 3 bytes of padding
+            0, 0, 0, 24,         // default: 24
+            0, 0, 0, 1,          // low: 1
+            0, 0, 0, 2,          // high: 2
+            0, 0, 0, 20,         // 1: 20
+            0, 0, 0, 21,         // 2: 21
+            0x00, 0x00, 0x00,    // 20: nop, nop, nop
+            0xB1,                // 23: return
+            0xB1,                // 24: return
+        ];
+        assert_eq!(
+            instructions(&code)[0],
+            Instruction::Tableswitch {
+                default: 24,
+                low: 1,
+                high: 2,
+                targets: vec![20, 21],
             }
-            _ => {
-                let instr = bytecodes.get(&x).unwrap();
-                instructions.push(instr.to_string())
+        );
+    }
+
+    #[test]
+    fn skips_lookupswitch_padding() {
+        #[rustfmt::skip]
+        let code = [
+            0x00, 0x00, 0x00,    // 0: nop, nop, nop
+            0xAB,                // 3: lookupswitch, no padding
+            0, 0, 0, 17,         // default: 3 + 17
+            0, 0, 0, 1,          // npairs: 1
+            0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 18, // -1: 3 + 18
+            0xB1, 0xB1,          // 20: return, return
+        ];
+        assert_eq!(
+            instructions(&code)[3],
+            Instruction::Lookupswitch {
+                default: 20,
+                pairs: vec![(-1, 21)],
             }
+        );
+    }
+
+    #[test]
+    fn aligns_switches_to_the_start_of_the_code() {
+        // the code starts at offset 1 of the class file, the padding must not depend on it
+        #[rustfmt::skip]
+        let bytes = [
+            0xFF,
+            0x00,                // 0: nop
+            0xAB, 0, 0,          // 1: lookupswitch, 2 bytes of padding
+            0, 0, 0, 11,         // default: 1 + 11
+            0, 0, 0, 0,          // npairs: 0
+            0xB1,                // 12: return
+        ];
+        let mut stream = ByteStream::new(&bytes);
+        stream.parse_u1().unwrap();
+        let code = parse_byte_code(stream.sub_stream(bytes.len() - 1).unwrap()).unwrap();
+        assert_eq!(
+            code,
+            vec![
+                (0, Instruction::Nop),
+                (
+                    1,
+                    Instruction::Lookupswitch {
+                        default: 12,
+                        pairs: vec![],
+                    }
+                ),
+                (12, Instruction::Return),
+            ]
+        );
+    }
+
+    /// Operand bytes that make `opcode` decode at pc 0 of a code array ending in `return`,
+    /// with every branch landing inside that code
+    fn operands(opcode: u8) -> Vec<u8> {
+        match opcode {
+            0x10 | 0x12 | 0x15..=0x19 | 0x36..=0x3a | 0xa9 => vec![1],
+            0xbc => vec![10],
+            0x11 | 0x13 | 0x14 | 0x84 | 0x99..=0xa8 | 0xb2..=0xb8 | 0xbb | 0xbd => vec![0, 1],
+            0xc0 | 0xc1 | 0xc6 | 0xc7 => vec![0, 0],
+            0xc5 => vec![0, 1, 1],
+            0xb9 | 0xba | 0xc8 | 0xc9 => vec![0, 0, 0, 0],
+            0xaa => vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            0xab => vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            0xc4 => vec![0x15, 0, 1],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn every_opcode_round_trips_through_its_instruction() {
+        for (opcode, mnemonic) in BYTECODETABLE {
+            let code = [&[opcode][..], &operands(opcode), &[0xb1]].concat();
+            let (pc, instr) = parse(&code).unwrap().remove(0);
+            assert_eq!(pc, 0);
+            assert_eq!(instr.opcode(), opcode, "{mnemonic}");
+            assert_eq!(instr.mnemonic(), mnemonic);
+        }
+    }
+
+    #[test]
+    fn wide_instructions_report_the_opcode_they_widen() {
+        for (opcode, mnemonic) in [(0x15, "iload"), (0x3a, "astore"), (0xa9, "ret")] {
+            let instr = match instructions(&[0xc4, opcode, 0, 1]).remove(0) {
+                Instruction::Wide(instr) => instr,
+                instr => panic!("expected a wide instruction, got {instr:?}"),
+            };
+            assert_eq!(instr.opcode(), opcode);
+            assert_eq!(instr.mnemonic(), mnemonic);
         }
     }
 
-    instructions
+    #[test]
+    fn displays_operands() {
+        let shown = |instr: Instruction| instr.to_string();
+        assert_eq!(shown(Instruction::Iconst0), "iconst_0");
+        assert_eq!(shown(Instruction::Bipush(-1)), "bipush -1");
+        assert_eq!(shown(Instruction::Ldc(7)), "ldc #7");
+        assert_eq!(shown(Instruction::Getstatic(2)), "getstatic #2");
+        assert_eq!(shown(Instruction::Aload(4)), "aload 4");
+        assert_eq!(shown(Instruction::Iinc(1, -2)), "iinc 1, -2");
+        assert_eq!(shown(Instruction::IfIcmpge(12)), "if_icmpge 12");
+        assert_eq!(
+            shown(Instruction::Invokeinterface(5, 2)),
+            "invokeinterface #5, 2"
+        );
+        assert_eq!(
+            shown(Instruction::Newarray(ArrayType::Boolean)),
+            "newarray boolean"
+        );
+        assert_eq!(
+            shown(Instruction::Multianewarray(8, 3)),
+            "multianewarray #8, 3"
+        );
+        assert_eq!(
+            shown(Instruction::Wide(WideInstruction::Iinc(256, -256))),
+            "wide iinc 256, -256"
+        );
+        assert_eq!(
+            shown(Instruction::Tableswitch {
+                default: 30,
+                low: -1,
+                high: 0,
+                targets: vec![20, 25],
+            }),
+            "tableswitch { -1: 20, 0: 25, default: 30 }"
+        );
+        assert_eq!(
+            shown(Instruction::Lookupswitch {
+                default: 30,
+                pairs: vec![(7, 20)],
+            }),
+            "lookupswitch { 7: 20, default: 30 }"
+        );
+    }
+
+    #[test]
+    fn reports_constant_pool_operands() {
+        assert_eq!(Instruction::Ldc(255).constant_pool_index(), Some(255));
+        assert_eq!(Instruction::Ldc2W(300).constant_pool_index(), Some(300));
+        assert_eq!(
+            Instruction::Invokeinterface(5, 2).constant_pool_index(),
+            Some(5)
+        );
+        assert_eq!(
+            Instruction::Multianewarray(8, 3).constant_pool_index(),
+            Some(8)
+        );
+        assert_eq!(Instruction::Bipush(7).constant_pool_index(), None);
+        assert_eq!(Instruction::Aload(7).constant_pool_index(), None);
+        assert_eq!(Instruction::Goto(7).constant_pool_index(), None);
+    }
+
+    #[test]
+    fn disassembles_with_resolved_operands() {
+        let cp = ConstantPool {
+            constants: vec![
+                Constant::Utf8(Utf8::new("hello")),
+                Constant::String { string_index: 1 },
+            ],
+        };
+        #[rustfmt::skip]
+        let code = [
+            0x12, 0x02,       // ldc #2
+            0x13, 0x00, 0x01, // ldc_w #1, not a loadable constant
+            0x10, 0x02,       // bipush 2
+            0xB1,             // return
+        ];
+        assert_eq!(
+            disassemble(&parse(&code).unwrap(), &cp),
+            vec![
+                "     0: ldc #2                                  // String hello",
+                "     2: ldc_w #1                                // Utf8 hello",
+                "     5: bipush 2",
+                "     7: return",
+            ]
+        );
+    }
+
+    #[test]
+    fn disassembles_dangling_operands() {
+        let lines = disassemble(&[(0, Instruction::Getstatic(3))], &ConstantPool::default());
+        assert_eq!(
+            lines,
+            vec!["     0: getstatic #3                            // <invalid: Constant Pool Index 3 out of bound>"]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_opcodes() {
+        let err = parse(&[0x00, 0xCA]).unwrap_err();
+        assert_eq!(err.offset, 2);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::UnknownInstruction {
+                opcode: 0xCA,
+                pc: 1
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_wide_opcodes() {
+        let err = parse(&[0xC4, 0x60, 0x00, 0x00]).unwrap_err();
+        assert_eq!(err.offset, 2);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::InvalidWideInstruction {
+                opcode: 0x60,
+                pc: 0
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_branches_outside_the_code() {
+        // jumping to the end of the code is as invalid as jumping before its start
+        let err = parse(&[0x00, 0xA7, 0x00, 0x03]).unwrap_err();
+        assert_eq!(err.offset, 4);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::InvalidBranchTarget { target: 4, pc: 1 }
+        );
+        let err = parse(&[0x00, 0xA7, 0xFF, 0xFE]).unwrap_err();
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::InvalidBranchTarget { target: -1, pc: 1 }
+        );
+    }
+
+    #[test]
+    fn rejects_tableswitch_with_low_above_high() {
+        #[rustfmt::skip]
+        let code = [
+            0xAA, 0, 0, 0,
+            0, 0, 0, 0,          // default: 0
+            0, 0, 0, 2,          // low: 2
+            0, 0, 0, 1,          // high: 1
+        ];
+        let err = parse(&code).unwrap_err();
+        assert_eq!(err.offset, 16);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::InvalidTableSwitch {
+                low: 2,
+                high: 1,
+                pc: 0
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_array_types() {
+        let err = parse(&[0xBC, 0x0C]).unwrap_err();
+        assert_eq!(err.offset, 2);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::UnknownArrayType { atype: 12, pc: 0 }
+        );
+    }
+
+    #[test]
+    fn rejects_multianewarray_of_no_dimensions() {
+        let err = parse(&[0x00, 0xC5, 0x00, 0x02, 0x00]).unwrap_err();
+        assert_eq!(err.offset, 5);
+        assert_eq!(err.cause, ParseErrorKind::ZeroDimensions { pc: 1 });
+    }
+
+    #[test]
+    fn rejects_truncated_operands() {
+        let mut stream = ByteStream::new(&[0x00, 0x11, 0x00]);
+        stream.section = Section::Code;
+        let err = parse_byte_code(stream).unwrap_err();
+        assert_eq!(err.offset, 2);
+        assert_eq!(err.section, Section::Code);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::UnexpectedEof {
+                needed: 2,
+                remaining: 1
+            }
+        );
+    }
 }
@@ -1,62 +1,457 @@
-use crate::{utils::bytestream::ByteStream, JavaClassFile};
+use crate::utils::bytestream::ByteStream;
 
 use super::{
-    accessflags::parse_class_access_flags, attributes::parse_attrs, constantpool::ConstantPool,
-    method::parse_methods,
+    accessflags::parse_class_access_flags,
+    attributes::{parse_attrs, Attr},
+    constantpool::ConstantPool,
+    error::{ParseError, ParseErrorKind, Section},
+    field::{parse_fields, Field},
+    method::{parse_methods, Method},
+    version::{self, VersionPolicy},
 };
 
-pub struct JavaClassFileParser {
-    bytes: ByteStream,
+/// A parsed class file. Names and descriptors are resolved through the constant pool,
+/// access flags are decoded into their `ACC_*` names.
+#[derive(Default, Debug, Clone)]
+pub struct JavaClassFile {
+    pub magic: u32,
+    pub minor: u16,
+    pub major: u16,
+    pub constant_pool: ConstantPool,
+    pub access_flags: Vec<String>,
+    /// The access flags as read, including the bits that have no `ACC_*` name
+    pub access_mask: u16,
+    pub this_class: String,
+    /// Empty for java/lang/Object, the only class without a super class
+    pub super_class: String,
+    pub interfaces: Vec<String>,
+    /// Constant pool indices `this_class`, `super_class` and `interfaces` were read
+    /// from, which the pool may hold duplicates of. 0 for classes that weren't parsed.
+    pub this_class_index: u16,
+    pub super_class_index: u16,
+    pub interface_indices: Vec<u16>,
+    pub fields: Vec<Field>,
+    pub methods: Vec<Method>,
+    pub attrs: Vec<Attr>,
+}
+
+impl JavaClassFile {
+    /// Java release matching the major version, e.g. `Java 17` for 61
+    pub fn java_release(&self) -> Option<&'static str> {
+        version::java_release(self.major)
+    }
+
+    /// Whether the class depends on preview features of its Java release
+    pub fn is_preview(&self) -> bool {
+        version::is_preview(self.major, self.minor)
+    }
+}
+
+/// Parses the bytes of a single `.class` file into a [`JavaClassFile`]
+pub struct JavaClassFileParser<'a> {
+    input: &'a [u8],
+    bytes: ByteStream<'a>,
     class: JavaClassFile,
+    version_policy: VersionPolicy,
+    warnings: Vec<String>,
 }
 
-impl JavaClassFileParser {
-    pub fn new(bytes: Vec<u8>) -> Self {
+impl<'a> JavaClassFileParser<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
         Self {
-            bytes: ByteStream { xs: bytes },
+            input: bytes,
+            bytes: ByteStream::new(bytes),
             class: JavaClassFile::default(),
+            version_policy: VersionPolicy::default(),
+            warnings: vec![],
+        }
+    }
+
+    /// Chooses what happens to class files with an unsupported version, `Warn` by default
+    pub fn with_version_policy(mut self, version_policy: VersionPolicy) -> Self {
+        self.version_policy = version_policy;
+        self
+    }
+
+    /// Problems that did not stop the parser, such as an unsupported version under
+    /// `VersionPolicy::Warn`
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    fn check_version(&mut self) -> Result<(), ParseError> {
+        let (major, minor) = (self.class.major, self.class.minor);
+        if version::is_supported_version(major, minor) {
+            return Ok(());
+        }
+        match self.version_policy {
+            VersionPolicy::Reject => Err(self
+                .bytes
+                .error(ParseErrorKind::UnsupportedVersion { major, minor })),
+            VersionPolicy::Warn => {
+                self.warnings.push(format!(
+                    "Unsupported class file version {major}.{minor}, parsing anyway"
+                ));
+                Ok(())
+            }
+            VersionPolicy::Allow => Ok(()),
         }
     }
 
-    pub fn parse(&mut self) -> JavaClassFile {
-        self.class.magic = self.bytes.parse_u4();
-        self.class.minor = self.bytes.parse_u2();
-        self.class.major = self.bytes.parse_u2();
-        self.class.constant_pool = ConstantPool::default().parse(&mut self.bytes);
-        self.class.access_flags = parse_class_access_flags(self.bytes.parse_u2());
+    /// Parses the class file from its first byte, each call giving the same result
+    pub fn parse(&mut self) -> Result<JavaClassFile, ParseError> {
+        self.bytes = ByteStream::new(self.input);
+        self.warnings.clear();
+        self.bytes.section = Section::Header;
+        self.class.magic = self.bytes.parse_u4()?;
+        if self.class.magic != version::MAGIC {
+            return Err(ParseError {
+                offset: 0,
+                section: Section::Header,
+                cause: ParseErrorKind::InvalidMagic(self.class.magic),
+            });
+        }
+        self.class.minor = self.bytes.parse_u2()?;
+        self.class.major = self.bytes.parse_u2()?;
+        self.check_version()?;
 
+        self.bytes.section = Section::ConstantPool;
+        self.class.constant_pool = ConstantPool::default().parse(&mut self.bytes)?;
+
+        self.bytes.section = Section::ClassInfo;
+        self.class.access_mask = self.bytes.parse_u2()?;
+        self.class.access_flags = parse_class_access_flags(self.class.access_mask);
+
+        let cp = &self.class.constant_pool;
+        let this_class = self.bytes.parse_u2()?;
         self.class.this_class = self
-            .class
-            .constant_pool
-            .query(self.bytes.parse_u2() as usize);
-        self.class.super_class = self
-            .class
-            .constant_pool
-            .query(self.bytes.parse_u2() as usize);
-
-        let interfaces_count = self.bytes.parse_u2();
-        for _ in 0..interfaces_count {
-            assert!(
-                false,
-                "[ERROR]:{}:{}: Interface parser not implemented yet\n",
-                file!(),
-                line!()
-            );
+            .bytes
+            .resolve(cp.class_name(this_class as usize))?
+            .to_string();
+        self.class.this_class_index = this_class;
+        // only java/lang/Object has no super class, its super_class index is 0
+        self.class.super_class_index = self.bytes.parse_u2()?;
+        self.class.super_class = match self.class.super_class_index {
+            0 => String::new(),
+            index => self
+                .bytes
+                .resolve(cp.class_name(index as usize))?
+                .to_string(),
+        };
+
+        self.bytes.section = Section::Interfaces;
+        let mut interfaces: Vec<String> = vec![];
+        let mut interface_indices: Vec<u16> = vec![];
+        for _ in 0..self.bytes.parse_u2()? {
+            let index = self.bytes.parse_u2()?;
+            let interface = self.bytes.resolve(cp.class_name(index as usize))?;
+            interfaces.push(interface.to_string());
+            interface_indices.push(index);
         }
+        self.class.interfaces = interfaces;
+        self.class.interface_indices = interface_indices;
+
+        self.bytes.section = Section::Fields;
+        self.class.fields = parse_fields(&self.class, &mut self.bytes)?;
 
-        let fields_count = self.bytes.parse_u2();
-        for _ in 0..fields_count {
-            assert!(
-                false,
-                "[ERROR]:{}:{}: Field parser not implemented yet\n",
-                file!(),
-                line!()
-            );
+        self.bytes.section = Section::Methods;
+        self.class.methods = parse_methods(&self.class, &mut self.bytes)?;
+
+        self.bytes.section = Section::Attributes;
+        self.class.attrs = parse_attrs(&mut self.bytes, &self.class.constant_pool)?;
+
+        if !self.bytes.is_empty() {
+            return Err(self
+                .bytes
+                .error(ParseErrorKind::TrailingBytes(self.bytes.remaining())));
         }
 
-        self.class.methods = parse_methods(&self.class, &mut self.bytes);
-        self.class.attrs = parse_attrs(&mut self.bytes, &self.class.constant_pool);
+        Ok(self.class.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::version::{MAGIC, PREVIEW_MINOR_VERSION};
+    use super::*;
+    use crate::parsers::constantpool::ConstantPoolError;
+
+    /// A class file of version `major.minor` declaring only `public class A`
+    fn class_file(major: u16, minor: u16) -> Vec<u8> {
+        let mut bytes = MAGIC.to_be_bytes().to_vec();
+        bytes.extend(minor.to_be_bytes());
+        bytes.extend(major.to_be_bytes());
+        // Utf8 "A" and a Class entry for it
+        bytes.extend([0, 3, 1, 0, 1, b'A', 7, 0, 1]);
+        // ACC_PUBLIC | ACC_SUPER, this_class #2, no super class, interfaces, fields,
+        // methods or attributes
+        bytes.extend([0, 0x21, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        bytes
+    }
+
+    /// A Java 17 class file with the constant pool `pool`, its access flags and
+    /// everything after them being `rest`
+    fn with_pool(pool: &[Vec<u8>], rest: &[u8]) -> Vec<u8> {
+        let mut bytes = MAGIC.to_be_bytes().to_vec();
+        bytes.extend([0, 0, 0, 61]);
+        bytes.extend((pool.len() as u16 + 1).to_be_bytes());
+        bytes.extend(pool.concat());
+        bytes.extend(rest);
+        bytes
+    }
+
+    fn utf8(value: &str) -> Vec<u8> {
+        let mut bytes = vec![1];
+        bytes.extend((value.len() as u16).to_be_bytes());
+        bytes.extend(value.as_bytes());
+        bytes
+    }
+
+    /// `A extends Object` and the classes it may implement, Utf8 entries at odd
+    /// indices and their Class entries right after them
+    fn names() -> Vec<Vec<u8>> {
+        [
+            "A",
+            "java/lang/Object",
+            "java/lang/Runnable",
+            "java/io/Serializable",
+        ]
+        .iter()
+        .enumerate()
+        .flat_map(|(i, name)| [utf8(name), vec![7, 0, 2 * i as u8 + 1]])
+        .collect()
+    }
+
+    #[test]
+    fn parses_the_header() {
+        let bytes = class_file(61, 0);
+        let class = JavaClassFileParser::new(&bytes).parse().unwrap();
+        assert_eq!((class.magic, class.major, class.minor), (MAGIC, 61, 0));
+        assert_eq!(class.java_release(), Some("Java 17"));
+        assert!(!class.is_preview());
+        assert_eq!(class.this_class, "A");
+    }
+
+    #[test]
+    fn parses_interfaces_in_declaration_order() {
+        let pool = names();
+        // this_class #2, super_class #4, interfaces #8 and #6
+        let rest = [0, 0x21, 0, 2, 0, 4, 0, 2, 0, 8, 0, 6, 0, 0, 0, 0, 0, 0];
+        let class = JavaClassFileParser::new(&with_pool(&pool, &rest))
+            .parse()
+            .unwrap();
+        assert_eq!(class.super_class, "java/lang/Object");
+        assert_eq!(
+            class.interfaces,
+            ["java/io/Serializable", "java/lang/Runnable"]
+        );
+        assert_eq!(class.interface_indices, [8, 6]);
+    }
+
+    #[test]
+    fn parses_the_same_interfaces_twice() {
+        let pool = names();
+        let rest = [0, 0x21, 0, 2, 0, 4, 0, 1, 0, 6, 0, 0, 0, 0, 0, 0];
+        let bytes = with_pool(&pool, &rest);
+        let mut parser = JavaClassFileParser::new(&bytes);
+        let first = parser.parse().unwrap();
+        let second = parser.parse().unwrap();
+        assert_eq!(second.interfaces, ["java/lang/Runnable"]);
+        assert_eq!(second.interface_indices, first.interface_indices);
+    }
+
+    #[test]
+    fn parses_an_empty_interfaces_table() {
+        let pool = names();
+        let rest = [0, 0x21, 0, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0];
+        let class = JavaClassFileParser::new(&with_pool(&pool, &rest))
+            .parse()
+            .unwrap();
+        assert!(class.interfaces.is_empty());
+    }
+
+    #[test]
+    fn rejects_interfaces_that_are_not_classes() {
+        let pool = names();
+        // the interface index #5 is the Utf8 entry of java/lang/Runnable
+        let rest = [0, 0x21, 0, 2, 0, 4, 0, 1, 0, 5, 0, 0, 0, 0, 0, 0];
+        let bytes = with_pool(&pool, &rest);
+        let err = JavaClassFileParser::new(&bytes).parse().unwrap_err();
+        assert_eq!(err.section, Section::Interfaces);
+        // right after the interface index, 10 bytes into `rest`
+        assert_eq!(err.offset, bytes.len() - rest.len() + 10);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::ConstantPool(ConstantPoolError::UnexpectedTag {
+                index: 5,
+                expected: "Class",
+                found: "Utf8",
+            })
+        );
+    }
+
+    #[test]
+    fn reports_a_truncated_interfaces_table() {
+        let pool = names();
+        let rest = [0, 0x21, 0, 2, 0, 4, 0, 2, 0, 6];
+        let bytes = with_pool(&pool, &rest);
+        let err = JavaClassFileParser::new(&bytes).parse().unwrap_err();
+        assert_eq!(err.section, Section::Interfaces);
+        assert_eq!(err.offset, bytes.len());
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::UnexpectedEof {
+                needed: 2,
+                remaining: 0
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_a_bad_magic() {
+        let mut bytes = class_file(61, 0);
+        bytes[3] = 0xBF;
+        let err = JavaClassFileParser::new(&bytes).parse().unwrap_err();
+        assert_eq!(
+            err,
+            ParseError {
+                offset: 0,
+                section: Section::Header,
+                cause: ParseErrorKind::InvalidMagic(0xCAFEBABF),
+            }
+        );
+    }
+
+    #[test]
+    fn reports_a_truncated_header() {
+        let bytes = class_file(61, 0);
+        let err = JavaClassFileParser::new(&bytes[..6]).parse().unwrap_err();
+        assert_eq!(
+            err,
+            ParseError {
+                offset: 6,
+                section: Section::Header,
+                cause: ParseErrorKind::UnexpectedEof {
+                    needed: 2,
+                    remaining: 0,
+                },
+            }
+        );
+        let err = JavaClassFileParser::new(&bytes[..2]).parse().unwrap_err();
+        assert_eq!(err.offset, 0);
+    }
+
+    #[test]
+    fn rejects_unsupported_versions_under_reject() {
+        let bytes = class_file(61, 3);
+        let mut parser =
+            JavaClassFileParser::new(&bytes).with_version_policy(VersionPolicy::Reject);
+        assert_eq!(
+            parser.parse().unwrap_err(),
+            ParseError {
+                offset: 8,
+                section: Section::Header,
+                cause: ParseErrorKind::UnsupportedVersion {
+                    major: 61,
+                    minor: 3
+                },
+            }
+        );
+        let bytes = class_file(61, 0);
+        let mut parser =
+            JavaClassFileParser::new(&bytes).with_version_policy(VersionPolicy::Reject);
+        assert!(parser.parse().is_ok());
+    }
+
+    #[test]
+    fn warns_about_unsupported_versions_by_default() {
+        let bytes = class_file(99, 0);
+        let mut parser = JavaClassFileParser::new(&bytes);
+        assert_eq!(parser.parse().unwrap().major, 99);
+        assert_eq!(
+            parser.warnings(),
+            ["Unsupported class file version 99.0, parsing anyway"]
+        );
+
+        let bytes = class_file(61, 0);
+        let mut parser = JavaClassFileParser::new(&bytes);
+        parser.parse().unwrap();
+        assert!(parser.warnings().is_empty());
+    }
+
+    #[test]
+    fn allows_unsupported_versions_silently_under_allow() {
+        let bytes = class_file(44, 0);
+        let mut parser = JavaClassFileParser::new(&bytes).with_version_policy(VersionPolicy::Allow);
+        assert_eq!(parser.parse().unwrap().major, 44);
+        assert!(parser.warnings().is_empty());
+    }
+
+    #[test]
+    fn detects_preview_classes() {
+        let bytes = class_file(65, PREVIEW_MINOR_VERSION);
+        let mut parser =
+            JavaClassFileParser::new(&bytes).with_version_policy(VersionPolicy::Reject);
+        let class = parser.parse().unwrap();
+        assert!(class.is_preview());
+        assert_eq!(class.java_release(), Some("Java 21"));
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let mut bytes = class_file(61, 0);
+        let end = bytes.len();
+        bytes.extend([0xCA, 0xFE]);
+        let err = JavaClassFileParser::new(&bytes).parse().unwrap_err();
+        assert_eq!(
+            err,
+            ParseError {
+                offset: end,
+                section: Section::Attributes,
+                cause: ParseErrorKind::TrailingBytes(2),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_utf8_with_its_pool_index() {
+        // a Long takes indices 1 and 2, so the Utf8 holding a raw NUL byte is #3
+        let pool = [vec![5, 0, 0, 0, 0, 0, 0, 0, 1], vec![1, 0, 1, 0]];
+        let mut bytes = with_pool(&pool, &[]);
+        // with_pool counts entries, the Long needs one more slot
+        bytes[9] = 4;
+        let err = JavaClassFileParser::new(&bytes).parse().unwrap_err();
+        assert_eq!(
+            err,
+            ParseError {
+                offset: 10 + 9 + 4,
+                section: Section::ConstantPool,
+                cause: ParseErrorKind::MalformedUtf8 { index: 3 },
+            }
+        );
+    }
 
-        self.class.to_owned()
+    #[test]
+    fn rejects_attributes_longer_than_their_contents() {
+        let mut pool = names();
+        pool.push(utf8("SourceFile"));
+        // a SourceFile attribute naming #1 but declaring 3 bytes instead of 2
+        let rest = [
+            0, 0x21, 0, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 1, 0, 9, 0, 0, 0, 3, 0, 1, 0,
+        ];
+        let bytes = with_pool(&pool, &rest);
+        let err = JavaClassFileParser::new(&bytes).parse().unwrap_err();
+        assert_eq!(
+            err,
+            ParseError {
+                offset: bytes.len() - 1,
+                section: Section::Attributes,
+                cause: ParseErrorKind::AttributeLength {
+                    name: "SourceFile".to_string(),
+                    length: 3,
+                    remaining: 1,
+                },
+            }
+        );
     }
 }
@@ -1,125 +1,1027 @@
-use std::process::exit;
+use std::fmt;
 
-use crate::ByteStream;
+use super::error::{ParseError, ParseErrorKind};
+use crate::utils::bytestream::ByteStream;
 
 const CONSTANT_CLASS: u8 = 7;
 const CONSTANT_FIELDREF: u8 = 9;
 const CONSTANT_METHODREF: u8 = 10;
-// const CONSTANT_INTERFACEMETHODREF: u8 = 11;
+const CONSTANT_INTERFACEMETHODREF: u8 = 11;
 const CONSTANT_STRING: u8 = 8;
-// const CONSTANT_INTEGER: u8 = 3;
-//const CONSTANT_FLOAT: u8 = 4;
-// const CONSTANT_LONG: u8 = 5;
-// const CONSTANT_DOUBLE: u8 = 6;
+const CONSTANT_INTEGER: u8 = 3;
+const CONSTANT_FLOAT: u8 = 4;
+const CONSTANT_LONG: u8 = 5;
+const CONSTANT_DOUBLE: u8 = 6;
 const CONSTANT_NAMEANDTYPE: u8 = 12;
 const CONSTANT_UTF8: u8 = 1;
-// const CONSTANT_METHODHANDLE: u8 = 15;
-// const CONSTANT_METHODTYPE: u8 = 16;
-// const CONSTANT_INVOKEDYNAMIC: u8 = 18;
-
-#[derive(Debug, Clone)]
-#[allow(unused)]
-pub struct ConstantPoolInfo {
-    tag: u8,
-    tag_name: String,
-    entries: Vec<(String, u16)>,
-    bytes: Option<String>,
+const CONSTANT_METHODHANDLE: u8 = 15;
+const CONSTANT_METHODTYPE: u8 = 16;
+const CONSTANT_DYNAMIC: u8 = 17;
+const CONSTANT_INVOKEDYNAMIC: u8 = 18;
+const CONSTANT_MODULE: u8 = 19;
+const CONSTANT_PACKAGE: u8 = 20;
+
+/// The content of a CONSTANT_Utf8 entry. Java strings may contain unpaired
+/// surrogates, which Rust strings cannot: `as_str` has them replaced by U+FFFD,
+/// `units` and `bytes` keep the exact content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Utf8 {
+    value: String,
+    bytes: Vec<u8>,
+}
+
+impl Utf8 {
+    /// The entry for `value`, encoded as modified UTF-8
+    pub fn new(value: &str) -> Self {
+        Self {
+            value: value.to_string(),
+            bytes: encode_modified_utf8(value),
+        }
+    }
+
+    /// The entry for the modified UTF-8 `bytes`, `None` when they are not valid
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let units = decode_modified_utf8_units(bytes)?;
+        Some(Self {
+            value: String::from_utf16_lossy(&units),
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    /// The entry as a Rust string, unpaired surrogates replaced by U+FFFD
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    /// The modified UTF-8 bytes of the entry, as they were read from the class file
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The UTF-16 code units of the entry, unpaired surrogates included
+    pub fn units(&self) -> Vec<u16> {
+        decode_modified_utf8_units(&self.bytes).expect("entries hold valid modified UTF-8")
+    }
+}
+
+/// One constant pool entry, one variant per tag of JVMS 4.4
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constant {
+    Utf8(Utf8),
+    Integer(i32),
+    Float(f32),
+    Long(i64),
+    Double(f64),
+    Class {
+        name_index: u16,
+    },
+    String {
+        string_index: u16,
+    },
+    Fieldref {
+        class_index: u16,
+        name_and_type_index: u16,
+    },
+    Methodref {
+        class_index: u16,
+        name_and_type_index: u16,
+    },
+    InterfaceMethodref {
+        class_index: u16,
+        name_and_type_index: u16,
+    },
+    NameAndType {
+        name_index: u16,
+        descriptor_index: u16,
+    },
+    MethodHandle {
+        reference_kind: u8,
+        reference_index: u16,
+    },
+    MethodType {
+        descriptor_index: u16,
+    },
+    Dynamic {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    },
+    InvokeDynamic {
+        bootstrap_method_attr_index: u16,
+        name_and_type_index: u16,
+    },
+    Module {
+        name_index: u16,
+    },
+    Package {
+        name_index: u16,
+    },
+    /// Long and Double take two slots in the pool, the second one is not usable
+    Unusable,
+}
+
+impl Constant {
+    /// The tag byte of the entry in the class file, 0 for an unusable slot
+    pub fn tag(&self) -> u8 {
+        match self {
+            Constant::Utf8(_) => CONSTANT_UTF8,
+            Constant::Integer(_) => CONSTANT_INTEGER,
+            Constant::Float(_) => CONSTANT_FLOAT,
+            Constant::Long(_) => CONSTANT_LONG,
+            Constant::Double(_) => CONSTANT_DOUBLE,
+            Constant::Class { .. } => CONSTANT_CLASS,
+            Constant::String { .. } => CONSTANT_STRING,
+            Constant::Fieldref { .. } => CONSTANT_FIELDREF,
+            Constant::Methodref { .. } => CONSTANT_METHODREF,
+            Constant::InterfaceMethodref { .. } => CONSTANT_INTERFACEMETHODREF,
+            Constant::NameAndType { .. } => CONSTANT_NAMEANDTYPE,
+            Constant::MethodHandle { .. } => CONSTANT_METHODHANDLE,
+            Constant::MethodType { .. } => CONSTANT_METHODTYPE,
+            Constant::Dynamic { .. } => CONSTANT_DYNAMIC,
+            Constant::InvokeDynamic { .. } => CONSTANT_INVOKEDYNAMIC,
+            Constant::Module { .. } => CONSTANT_MODULE,
+            Constant::Package { .. } => CONSTANT_PACKAGE,
+            Constant::Unusable => 0,
+        }
+    }
+
+    /// The name of the entry's tag without the `CONSTANT_` prefix, e.g. `Methodref`
+    pub fn tag_name(&self) -> &'static str {
+        match self {
+            Constant::Utf8(_) => "Utf8",
+            Constant::Integer(_) => "Integer",
+            Constant::Float(_) => "Float",
+            Constant::Long(_) => "Long",
+            Constant::Double(_) => "Double",
+            Constant::Class { .. } => "Class",
+            Constant::String { .. } => "String",
+            Constant::Fieldref { .. } => "Fieldref",
+            Constant::Methodref { .. } => "Methodref",
+            Constant::InterfaceMethodref { .. } => "InterfaceMethodref",
+            Constant::NameAndType { .. } => "NameAndType",
+            Constant::MethodHandle { .. } => "MethodHandle",
+            Constant::MethodType { .. } => "MethodType",
+            Constant::Dynamic { .. } => "Dynamic",
+            Constant::InvokeDynamic { .. } => "InvokeDynamic",
+            Constant::Module { .. } => "Module",
+            Constant::Package { .. } => "Package",
+            Constant::Unusable => "Unusable",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstantPoolError {
+    IndexOutOfBounds(usize),
+    UnusableIndex(usize),
+    UnexpectedTag {
+        index: usize,
+        expected: &'static str,
+        found: &'static str,
+    },
 }
 
+impl fmt::Display for ConstantPoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstantPoolError::IndexOutOfBounds(index) => {
+                write!(f, "Constant Pool Index {index} out of bound")
+            }
+            ConstantPoolError::UnusableIndex(index) => {
+                write!(f, "Constant Pool Index {index} is not usable")
+            }
+            ConstantPoolError::UnexpectedTag {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Constant Pool Index {index} is a {found}, expected a {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConstantPoolError {}
+
+/// The constant pool of a class, indexed from 1 like in the class file
 #[derive(Default, Debug, Clone)]
 pub struct ConstantPool {
-    pub count: usize,
-    pub info: Vec<ConstantPoolInfo>,
+    /// Entries in pool order, `constants[0]` is the entry at index 1
+    pub constants: Vec<Constant>,
 }
 
 impl ConstantPool {
-    pub fn query(&self, index: usize) -> String {
-        let index: usize = index - 1;
+    /// The entry at `index`, an error for index 0, an index past the end or the
+    /// slot after a Long or Double
+    pub fn get(&self, index: usize) -> Result<&Constant, ConstantPoolError> {
+        if index == 0 || index > self.constants.len() {
+            return Err(ConstantPoolError::IndexOutOfBounds(index));
+        }
+        match &self.constants[index - 1] {
+            Constant::Unusable => Err(ConstantPoolError::UnusableIndex(index)),
+            constant => Ok(constant),
+        }
+    }
 
-        if index > self.count {
-            eprintln!(
-                "[ERROR]:{}:{}: Constant Pool Index out of bound",
-                file!(),
-                line!()
-            );
-            exit(1);
+    fn unexpected(&self, index: usize, expected: &'static str) -> ConstantPoolError {
+        ConstantPoolError::UnexpectedTag {
+            index,
+            expected,
+            found: self.constants[index - 1].tag_name(),
         }
+    }
+
+    /// The content of the Utf8 entry at `index`
+    pub fn utf8(&self, index: usize) -> Result<&str, ConstantPoolError> {
+        Ok(self.utf8_entry(index)?.as_str())
+    }
 
-        if self.info[index].tag != CONSTANT_UTF8 {
-            return self.query(self.info[index].entries[0].1 as usize);
+    /// The Utf8 entry at `index`, which keeps unpaired surrogates unlike `utf8`
+    pub fn utf8_entry(&self, index: usize) -> Result<&Utf8, ConstantPoolError> {
+        match self.get(index)? {
+            Constant::Utf8(value) => Ok(value),
+            _ => Err(self.unexpected(index, "Utf8")),
         }
+    }
 
-        self.info[index].bytes.clone().unwrap()
+    /// The binary name of a Class entry, e.g. `java/lang/Object`
+    pub fn class_name(&self, index: usize) -> Result<&str, ConstantPoolError> {
+        match self.get(index)? {
+            Constant::Class { name_index } => self.utf8(*name_index as usize),
+            _ => Err(self.unexpected(index, "Class")),
+        }
     }
 
-    pub fn parse(&mut self, bytes: &mut ByteStream) -> ConstantPool {
-        let count = bytes.parse_u2();
+    /// The content of the Utf8 entry a String entry refers to
+    pub fn string(&self, index: usize) -> Result<&str, ConstantPoolError> {
+        Ok(self.string_entry(index)?.as_str())
+    }
 
-        let mut constant_pool = ConstantPool {
-            count: count as usize,
-            info: vec![],
+    /// The Utf8 entry a String entry refers to, which keeps unpaired surrogates
+    /// unlike `string`
+    pub fn string_entry(&self, index: usize) -> Result<&Utf8, ConstantPoolError> {
+        match self.get(index)? {
+            Constant::String { string_index } => self.utf8_entry(*string_index as usize),
+            _ => Err(self.unexpected(index, "String")),
+        }
+    }
+
+    /// The value of an Integer entry
+    pub fn integer(&self, index: usize) -> Result<i32, ConstantPoolError> {
+        match self.get(index)? {
+            Constant::Integer(value) => Ok(*value),
+            _ => Err(self.unexpected(index, "Integer")),
+        }
+    }
+
+    /// The value of a Float entry
+    pub fn float(&self, index: usize) -> Result<f32, ConstantPoolError> {
+        match self.get(index)? {
+            Constant::Float(value) => Ok(*value),
+            _ => Err(self.unexpected(index, "Float")),
+        }
+    }
+
+    /// The value of a Long entry
+    pub fn long(&self, index: usize) -> Result<i64, ConstantPoolError> {
+        match self.get(index)? {
+            Constant::Long(value) => Ok(*value),
+            _ => Err(self.unexpected(index, "Long")),
+        }
+    }
+
+    /// The value of a Double entry
+    pub fn double(&self, index: usize) -> Result<f64, ConstantPoolError> {
+        match self.get(index)? {
+            Constant::Double(value) => Ok(*value),
+            _ => Err(self.unexpected(index, "Double")),
+        }
+    }
+
+    /// (name, descriptor) of a NameAndType entry
+    pub fn name_and_type(&self, index: usize) -> Result<(&str, &str), ConstantPoolError> {
+        match self.get(index)? {
+            Constant::NameAndType {
+                name_index,
+                descriptor_index,
+            } => Ok((
+                self.utf8(*name_index as usize)?,
+                self.utf8(*descriptor_index as usize)?,
+            )),
+            _ => Err(self.unexpected(index, "NameAndType")),
+        }
+    }
+
+    /// (reference_kind, class, name, descriptor) of a MethodHandle entry
+    pub fn method_handle(&self, index: usize) -> Result<(u8, &str, &str, &str), ConstantPoolError> {
+        match self.get(index)? {
+            Constant::MethodHandle {
+                reference_kind,
+                reference_index,
+            } => {
+                let (class, name, descriptor) = self.member_ref(*reference_index as usize)?;
+                Ok((*reference_kind, class, name, descriptor))
+            }
+            _ => Err(self.unexpected(index, "MethodHandle")),
+        }
+    }
+
+    /// The method descriptor of a MethodType entry
+    pub fn method_type(&self, index: usize) -> Result<&str, ConstantPoolError> {
+        match self.get(index)? {
+            Constant::MethodType { descriptor_index } => self.utf8(*descriptor_index as usize),
+            _ => Err(self.unexpected(index, "MethodType")),
+        }
+    }
+
+    /// (bootstrap method index, name, descriptor) of an InvokeDynamic entry
+    pub fn invoke_dynamic(&self, index: usize) -> Result<(u16, &str, &str), ConstantPoolError> {
+        match self.get(index)? {
+            Constant::InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                let (name, descriptor) = self.name_and_type(*name_and_type_index as usize)?;
+                Ok((*bootstrap_method_attr_index, name, descriptor))
+            }
+            _ => Err(self.unexpected(index, "InvokeDynamic")),
+        }
+    }
+
+    /// (class, name, descriptor) of a Fieldref, Methodref or InterfaceMethodref entry
+    pub fn member_ref(&self, index: usize) -> Result<(&str, &str, &str), ConstantPoolError> {
+        match self.get(index)? {
+            Constant::Fieldref {
+                class_index,
+                name_and_type_index,
+            }
+            | Constant::Methodref {
+                class_index,
+                name_and_type_index,
+            }
+            | Constant::InterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            } => {
+                let class = self.class_name(*class_index as usize)?;
+                let (name, descriptor) = self.name_and_type(*name_and_type_index as usize)?;
+                Ok((class, name, descriptor))
+            }
+            _ => Err(self.unexpected(index, "Fieldref, Methodref or InterfaceMethodref")),
+        }
+    }
+
+    /// Describes the entry at `index` the way javap comments it, e.g.
+    /// `Field java/lang/System.out:Ljava/io/PrintStream;` or `String Hello, World!`
+    pub fn describe(&self, index: usize) -> String {
+        self.try_describe(index)
+            .unwrap_or_else(|err| format!("<invalid: {err}>"))
+    }
+
+    fn try_describe(&self, index: usize) -> Result<String, ConstantPoolError> {
+        let member = |index: u16| -> Result<String, ConstantPoolError> {
+            let (class, name, descriptor) = self.member_ref(index as usize)?;
+            Ok(format!("{class}.{name}:{descriptor}"))
+        };
+        let name_and_type = |index: u16| -> Result<String, ConstantPoolError> {
+            let (name, descriptor) = self.name_and_type(index as usize)?;
+            Ok(format!("{name}:{descriptor}"))
         };
 
-        for _ in 0..(count - 1) {
-            let tag = bytes.parse_u1();
-            match tag {
-                CONSTANT_CLASS => constant_pool.info.push(ConstantPoolInfo {
-                    tag,
-                    tag_name: "CONSTANT_CLASS".to_string(),
-                    entries: vec![("name_index".to_string(), bytes.parse_u2())],
-                    bytes: None,
-                }),
-                CONSTANT_METHODREF => constant_pool.info.push(ConstantPoolInfo {
-                    tag,
-                    tag_name: "CONSTANT_METHODREF".to_string(),
-                    entries: vec![
-                        ("class_index".to_string(), bytes.parse_u2()),
-                        ("name_and_type_index".to_string(), bytes.parse_u2()),
-                    ],
-                    bytes: None,
-                }),
-                CONSTANT_NAMEANDTYPE => constant_pool.info.push(ConstantPoolInfo {
-                    tag,
-                    tag_name: "CONSTANT_NAMEANDTYPE".to_string(),
-                    entries: vec![
-                        ("name_index".to_string(), bytes.parse_u2()),
-                        ("descriptor_index".to_string(), bytes.parse_u2()),
-                    ],
-                    bytes: None,
-                }),
+        Ok(match self.get(index)? {
+            Constant::Utf8(value) => format!("Utf8 {}", value.as_str()),
+            Constant::Integer(value) => format!("int {value}"),
+            Constant::Float(value) => format!("float {value}f"),
+            Constant::Long(value) => format!("long {value}l"),
+            Constant::Double(value) => format!("double {value}d"),
+            Constant::Class { .. } => format!("class {}", self.class_name(index)?),
+            Constant::String { .. } => format!("String {}", self.string(index)?),
+            Constant::Fieldref { .. } => format!("Field {}", member(index as u16)?),
+            Constant::Methodref { .. } => format!("Method {}", member(index as u16)?),
+            Constant::InterfaceMethodref { .. } => {
+                format!("InterfaceMethod {}", member(index as u16)?)
+            }
+            Constant::NameAndType { .. } => {
+                format!("NameAndType {}", name_and_type(index as u16)?)
+            }
+            Constant::MethodHandle {
+                reference_kind,
+                reference_index,
+            } => {
+                let kind = match reference_kind {
+                    1 => "REF_getField",
+                    2 => "REF_getStatic",
+                    3 => "REF_putField",
+                    4 => "REF_putStatic",
+                    5 => "REF_invokeVirtual",
+                    6 => "REF_invokeStatic",
+                    7 => "REF_invokeSpecial",
+                    8 => "REF_newInvokeSpecial",
+                    9 => "REF_invokeInterface",
+                    _ => "REF_unknown",
+                };
+                format!("MethodHandle {kind} {}", member(*reference_index)?)
+            }
+            Constant::MethodType { descriptor_index } => {
+                format!("MethodType {}", self.utf8(*descriptor_index as usize)?)
+            }
+            Constant::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => format!(
+                "Dynamic #{bootstrap_method_attr_index}:{}",
+                name_and_type(*name_and_type_index)?
+            ),
+            Constant::InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => format!(
+                "InvokeDynamic #{bootstrap_method_attr_index}:{}",
+                name_and_type(*name_and_type_index)?
+            ),
+            Constant::Module { name_index } => {
+                format!("Module {}", self.utf8(*name_index as usize)?)
+            }
+            Constant::Package { name_index } => {
+                format!("Package {}", self.utf8(*name_index as usize)?)
+            }
+            Constant::Unusable => unreachable!("get() rejects unusable slots"),
+        })
+    }
+
+    /// Reads a constant pool, its count included, from `bytes`
+    pub fn parse(&mut self, bytes: &mut ByteStream) -> Result<ConstantPool, ParseError> {
+        let count = bytes.parse_u2()?;
+
+        let mut constant_pool = ConstantPool { constants: vec![] };
+
+        while constant_pool.constants.len() + 1 < count as usize {
+            let tag = bytes.parse_u1()?;
+            let constant = match tag {
+                CONSTANT_CLASS => Constant::Class {
+                    name_index: bytes.parse_u2()?,
+                },
+                CONSTANT_METHODREF => Constant::Methodref {
+                    class_index: bytes.parse_u2()?,
+                    name_and_type_index: bytes.parse_u2()?,
+                },
+                CONSTANT_INTERFACEMETHODREF => Constant::InterfaceMethodref {
+                    class_index: bytes.parse_u2()?,
+                    name_and_type_index: bytes.parse_u2()?,
+                },
+                CONSTANT_NAMEANDTYPE => Constant::NameAndType {
+                    name_index: bytes.parse_u2()?,
+                    descriptor_index: bytes.parse_u2()?,
+                },
                 CONSTANT_UTF8 => {
-                    let length = bytes.parse_u2();
-                    constant_pool.info.push(ConstantPoolInfo {
-                        tag,
-                        tag_name: "CONSTANT_UTF8".to_string(),
-                        entries: vec![("length".to_string(), length)],
-                        bytes: Some(
-                            String::from_utf8(bytes.parse_n(length as usize)).unwrap(),
-                        ),
-                    })
+                    let length = bytes.parse_u2()?;
+                    match Utf8::from_bytes(bytes.parse_n(length as usize)?) {
+                        Some(value) => Constant::Utf8(value),
+                        None => {
+                            return Err(bytes.error(ParseErrorKind::MalformedUtf8 {
+                                index: constant_pool.constants.len() + 1,
+                            }));
+                        }
+                    }
                 }
-                CONSTANT_FIELDREF => constant_pool.info.push(ConstantPoolInfo {
-                    tag,
-                    tag_name: "CONSTANT_FIELDREF".to_string(),
-                    entries: vec![
-                        ("class_index".to_string(), bytes.parse_u2()),
-                        ("name_and_type_index".to_string(), bytes.parse_u2()),
-                    ],
-                    bytes: None,
-                }),
-                CONSTANT_STRING => constant_pool.info.push(ConstantPoolInfo {
-                    tag,
-                    tag_name: "CONSTANT_STRING".to_string(),
-                    entries: vec![("string_index".to_string(), bytes.parse_u2())],
-                    bytes: None,
-                }),
-                _ => {
-                    eprintln!("[ERROR]:{}:{}: Unknown tag {}", file!(), line!(), tag);
-                    exit(1);
+                CONSTANT_FIELDREF => Constant::Fieldref {
+                    class_index: bytes.parse_u2()?,
+                    name_and_type_index: bytes.parse_u2()?,
+                },
+                CONSTANT_STRING => Constant::String {
+                    string_index: bytes.parse_u2()?,
+                },
+                CONSTANT_INTEGER => Constant::Integer(bytes.parse_u4()? as i32),
+                CONSTANT_FLOAT => Constant::Float(f32::from_bits(bytes.parse_u4()?)),
+                CONSTANT_LONG | CONSTANT_DOUBLE => {
+                    let high_bytes = bytes.parse_u4()? as u64;
+                    let low_bytes = bytes.parse_u4()? as u64;
+                    let bits = (high_bytes << 32) | low_bytes;
+                    if tag == CONSTANT_LONG {
+                        constant_pool.constants.push(Constant::Long(bits as i64));
+                    } else {
+                        constant_pool
+                            .constants
+                            .push(Constant::Double(f64::from_bits(bits)));
+                    }
+                    Constant::Unusable
                 }
+                CONSTANT_METHODHANDLE => Constant::MethodHandle {
+                    reference_kind: bytes.parse_u1()?,
+                    reference_index: bytes.parse_u2()?,
+                },
+                CONSTANT_METHODTYPE => Constant::MethodType {
+                    descriptor_index: bytes.parse_u2()?,
+                },
+                CONSTANT_DYNAMIC => Constant::Dynamic {
+                    bootstrap_method_attr_index: bytes.parse_u2()?,
+                    name_and_type_index: bytes.parse_u2()?,
+                },
+                CONSTANT_INVOKEDYNAMIC => Constant::InvokeDynamic {
+                    bootstrap_method_attr_index: bytes.parse_u2()?,
+                    name_and_type_index: bytes.parse_u2()?,
+                },
+                CONSTANT_MODULE => Constant::Module {
+                    name_index: bytes.parse_u2()?,
+                },
+                CONSTANT_PACKAGE => Constant::Package {
+                    name_index: bytes.parse_u2()?,
+                },
+                _ => return Err(bytes.error(ParseErrorKind::UnknownConstantTag(tag))),
+            };
+            constant_pool.constants.push(constant);
+        }
+
+        Ok(constant_pool)
+    }
+}
+
+/// Decodes the "modified UTF-8" used by CONSTANT_Utf8 entries (JVMS 4.4.7) into
+/// UTF-16 code units. NUL is encoded as `0xC0 0x80` and supplementary characters
+/// are encoded as a surrogate pair of two 3-byte sequences. Returns `None` when
+/// the bytes are not valid modified UTF-8.
+pub fn decode_modified_utf8_units(bytes: &[u8]) -> Option<Vec<u16>> {
+    let mut units: Vec<u16> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    let continuation = |i: usize| -> Option<u16> {
+        match bytes.get(i) {
+            Some(&b) if b & 0xC0 == 0x80 => Some((b & 0x3F) as u16),
+            _ => None,
+        }
+    };
+
+    while i < bytes.len() {
+        let x = bytes[i];
+        match x {
+            0x01..=0x7F => {
+                units.push(x as u16);
+                i += 1;
+            }
+            0xC0..=0xDF => {
+                let y = continuation(i + 1)?;
+                units.push(((x as u16 & 0x1F) << 6) | y);
+                i += 2;
+            }
+            0xE0..=0xEF => {
+                let y = continuation(i + 1)?;
+                let z = continuation(i + 2)?;
+                units.push(((x as u16 & 0x0F) << 12) | (y << 6) | z);
+                i += 3;
+            }
+            // 0x00, stray continuation bytes and 0xF0..=0xFF never appear in modified UTF-8
+            _ => return None,
+        }
+    }
+
+    Some(units)
+}
+
+/// Decodes modified UTF-8 into a Rust string. Unpaired surrogates, which Java
+/// strings may contain but Rust strings cannot, are replaced by U+FFFD.
+pub fn decode_modified_utf8(bytes: &[u8]) -> Option<String> {
+    Some(String::from_utf16_lossy(&decode_modified_utf8_units(
+        bytes,
+    )?))
+}
+
+/// Encodes a Rust string as modified UTF-8, the inverse of `decode_modified_utf8`.
+pub fn encode_modified_utf8(s: &str) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(s.len());
+
+    for unit in s.encode_utf16() {
+        match unit {
+            0x0001..=0x007F => bytes.push(unit as u8),
+            0x0000 | 0x0080..=0x07FF => {
+                bytes.push(0xC0 | (unit >> 6) as u8);
+                bytes.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                bytes.push(0xE0 | (unit >> 12) as u8);
+                bytes.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                bytes.push(0x80 | (unit & 0x3F) as u8);
             }
         }
+    }
+
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses a pool of `count - 1` slots from `entries`, the bytes after the count
+    fn parse(count: u16, entries: &[u8]) -> Result<ConstantPool, ParseError> {
+        let bytes = [&count.to_be_bytes()[..], entries].concat();
+        ConstantPool::default().parse(&mut ByteStream::new(&bytes))
+    }
+
+    /// `System.out` with the entries it refers to, a few numbers and a dangling String
+    fn pool() -> ConstantPool {
+        let utf8 = |value: &str| Constant::Utf8(Utf8::new(value));
+        let constants = vec![
+            utf8("java/lang/System"),
+            Constant::Class { name_index: 1 },
+            utf8("out"),
+            utf8("Ljava/io/PrintStream;"),
+            Constant::NameAndType {
+                name_index: 3,
+                descriptor_index: 4,
+            },
+            Constant::Fieldref {
+                class_index: 2,
+                name_and_type_index: 5,
+            },
+            Constant::String { string_index: 3 },
+            Constant::Integer(-2),
+            Constant::Long(7),
+            Constant::Unusable,
+            Constant::Float(1.5),
+            Constant::Double(2.5),
+            Constant::Unusable,
+            Constant::MethodHandle {
+                reference_kind: 2,
+                reference_index: 6,
+            },
+            Constant::InvokeDynamic {
+                bootstrap_method_attr_index: 0,
+                name_and_type_index: 5,
+            },
+            Constant::String { string_index: 8 },
+        ];
+        ConstantPool { constants }
+    }
+
+    #[test]
+    fn parses_every_tag() {
+        #[rustfmt::skip]
+        let entries = [
+            1, 0, 2, b'h', b'i',        // #1 Utf8 "hi"
+            3, 0xFF, 0xFF, 0xFF, 0xFE,  // #2 Integer -2
+            4, 0x3F, 0xC0, 0, 0,        // #3 Float 1.5
+            5, 0, 0, 0, 1, 0, 0, 0, 2,  // #4 and #5 Long 1 << 32 | 2
+            6, 0x40, 0x04, 0, 0, 0, 0, 0, 0, // #6 and #7 Double 2.5
+            7, 0, 1,                    // #8 Class
+            8, 0, 1,                    // #9 String
+            9, 0, 8, 0, 13,             // #10 Fieldref
+            10, 0, 8, 0, 13,            // #11 Methodref
+            11, 0, 8, 0, 13,            // #12 InterfaceMethodref
+            12, 0, 1, 0, 1,             // #13 NameAndType
+            15, 6, 0, 11,               // #14 MethodHandle REF_invokeStatic
+            16, 0, 1,                   // #15 MethodType
+            17, 0, 0, 0, 13,            // #16 Dynamic
+            18, 0, 1, 0, 13,            // #17 InvokeDynamic
+            19, 0, 1,                   // #18 Module
+            20, 0, 1,                   // #19 Package
+        ];
+        let pool = parse(20, &entries).unwrap();
+        let expected = [
+            Constant::Utf8(Utf8::new("hi")),
+            Constant::Integer(-2),
+            Constant::Float(1.5),
+            Constant::Long((1 << 32) | 2),
+            Constant::Unusable,
+            Constant::Double(2.5),
+            Constant::Unusable,
+            Constant::Class { name_index: 1 },
+            Constant::String { string_index: 1 },
+            Constant::Fieldref {
+                class_index: 8,
+                name_and_type_index: 13,
+            },
+            Constant::Methodref {
+                class_index: 8,
+                name_and_type_index: 13,
+            },
+            Constant::InterfaceMethodref {
+                class_index: 8,
+                name_and_type_index: 13,
+            },
+            Constant::NameAndType {
+                name_index: 1,
+                descriptor_index: 1,
+            },
+            Constant::MethodHandle {
+                reference_kind: 6,
+                reference_index: 11,
+            },
+            Constant::MethodType {
+                descriptor_index: 1,
+            },
+            Constant::Dynamic {
+                bootstrap_method_attr_index: 0,
+                name_and_type_index: 13,
+            },
+            Constant::InvokeDynamic {
+                bootstrap_method_attr_index: 1,
+                name_and_type_index: 13,
+            },
+            Constant::Module { name_index: 1 },
+            Constant::Package { name_index: 1 },
+        ];
+        assert_eq!(pool.constants, expected);
+        for (constant, tag) in pool.constants.iter().zip([
+            1, 3, 4, 5, 0, 6, 0, 7, 8, 9, 10, 11, 12, 15, 16, 17, 18, 19, 20,
+        ]) {
+            assert_eq!(constant.tag(), tag);
+        }
+    }
+
+    #[test]
+    fn longs_and_doubles_take_two_slots() {
+        #[rustfmt::skip]
+        let entries = [
+            5, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, // #1 Long -1
+            6, 0x3F, 0xF0, 0, 0, 0, 0, 0, 0,                   // #3 Double 1.0
+            3, 0, 0, 0, 7,                                     // #5 Integer 7
+        ];
+        let pool = parse(6, &entries).unwrap();
+        assert_eq!(pool.long(1), Ok(-1));
+        assert_eq!(pool.get(2), Err(ConstantPoolError::UnusableIndex(2)));
+        assert_eq!(pool.double(3), Ok(1.0));
+        assert_eq!(pool.get(4), Err(ConstantPoolError::UnusableIndex(4)));
+        assert_eq!(pool.integer(5), Ok(7));
+        assert_eq!(pool.get(6), Err(ConstantPoolError::IndexOutOfBounds(6)));
+    }
+
+    #[test]
+    fn parses_an_empty_pool() {
+        assert!(parse(1, &[]).unwrap().constants.is_empty());
+        assert!(parse(0, &[]).unwrap().constants.is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_tags() {
+        // no entry has tag 2, the offset is the one of the bytes after it
+        let err = parse(3, &[3, 0, 0, 0, 1, 2, 0, 0]).unwrap_err();
+        assert_eq!(err.cause, ParseErrorKind::UnknownConstantTag(2));
+        assert_eq!(err.offset, 8);
+    }
 
-        constant_pool
+    #[test]
+    fn reports_truncated_entries() {
+        let err = parse(2, &[9, 0, 1, 0]).unwrap_err();
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::UnexpectedEof {
+                needed: 2,
+                remaining: 1,
+            }
+        );
+        assert_eq!(err.offset, 5);
+    }
+
+    #[test]
+    fn resolves_entries_through_their_references() {
+        let cp = pool();
+        assert_eq!(cp.utf8(3), Ok("out"));
+        assert_eq!(cp.class_name(2), Ok("java/lang/System"));
+        assert_eq!(cp.string(7), Ok("out"));
+        assert_eq!(cp.integer(8), Ok(-2));
+        assert_eq!(cp.long(9), Ok(7));
+        assert_eq!(cp.float(11), Ok(1.5));
+        assert_eq!(cp.double(12), Ok(2.5));
+        assert_eq!(cp.name_and_type(5), Ok(("out", "Ljava/io/PrintStream;")));
+        assert_eq!(
+            cp.member_ref(6),
+            Ok(("java/lang/System", "out", "Ljava/io/PrintStream;"))
+        );
+        assert_eq!(
+            cp.method_handle(14),
+            Ok((2, "java/lang/System", "out", "Ljava/io/PrintStream;"))
+        );
+        assert_eq!(
+            cp.invoke_dynamic(15),
+            Ok((0, "out", "Ljava/io/PrintStream;"))
+        );
+    }
+
+    #[test]
+    fn rejects_indices_outside_the_pool() {
+        let cp = pool();
+        assert_eq!(cp.get(0), Err(ConstantPoolError::IndexOutOfBounds(0)));
+        assert_eq!(cp.get(17), Err(ConstantPoolError::IndexOutOfBounds(17)));
+        assert!(cp.get(16).is_ok());
+    }
+
+    #[test]
+    fn rejects_the_second_slot_of_longs_and_doubles() {
+        let cp = pool();
+        assert_eq!(cp.long(10), Err(ConstantPoolError::UnusableIndex(10)));
+        assert_eq!(cp.double(13), Err(ConstantPoolError::UnusableIndex(13)));
+    }
+
+    #[test]
+    fn rejects_entries_of_the_wrong_kind() {
+        let cp = pool();
+        assert_eq!(
+            cp.class_name(1),
+            Err(ConstantPoolError::UnexpectedTag {
+                index: 1,
+                expected: "Class",
+                found: "Utf8",
+            })
+        );
+        assert_eq!(
+            cp.member_ref(5),
+            Err(ConstantPoolError::UnexpectedTag {
+                index: 5,
+                expected: "Fieldref, Methodref or InterfaceMethodref",
+                found: "NameAndType",
+            })
+        );
+        // the error names the entry that is wrong, not the one that was asked for
+        assert_eq!(
+            cp.string(16),
+            Err(ConstantPoolError::UnexpectedTag {
+                index: 8,
+                expected: "Utf8",
+                found: "Integer",
+            })
+        );
+    }
+
+    #[test]
+    fn constants_report_their_tag() {
+        let cp = pool();
+        let tags: Vec<(u8, &str)> = cp
+            .constants
+            .iter()
+            .map(|constant| (constant.tag(), constant.tag_name()))
+            .collect();
+        assert_eq!(tags[0], (CONSTANT_UTF8, "Utf8"));
+        assert_eq!(tags[5], (CONSTANT_FIELDREF, "Fieldref"));
+        assert_eq!(tags[9], (0, "Unusable"));
+        assert_eq!(tags[13], (CONSTANT_METHODHANDLE, "MethodHandle"));
+    }
+
+    #[test]
+    fn describes_entries_like_javap() {
+        let cp = pool();
+        let described = [
+            (1, "Utf8 java/lang/System"),
+            (2, "class java/lang/System"),
+            (5, "NameAndType out:Ljava/io/PrintStream;"),
+            (6, "Field java/lang/System.out:Ljava/io/PrintStream;"),
+            (7, "String out"),
+            (8, "int -2"),
+            (9, "long 7l"),
+            (11, "float 1.5f"),
+            (12, "double 2.5d"),
+            (
+                14,
+                "MethodHandle REF_getStatic java/lang/System.out:Ljava/io/PrintStream;",
+            ),
+            (15, "InvokeDynamic #0:out:Ljava/io/PrintStream;"),
+        ];
+        for (index, description) in described {
+            assert_eq!(cp.describe(index), description);
+        }
+    }
+
+    #[test]
+    fn describes_invalid_indices_instead_of_failing() {
+        let cp = pool();
+        assert_eq!(
+            cp.describe(0),
+            "<invalid: Constant Pool Index 0 out of bound>"
+        );
+        assert_eq!(
+            cp.describe(17),
+            "<invalid: Constant Pool Index 17 out of bound>"
+        );
+        assert_eq!(
+            cp.describe(10),
+            "<invalid: Constant Pool Index 10 is not usable>"
+        );
+        assert_eq!(
+            cp.describe(16),
+            "<invalid: Constant Pool Index 8 is a Integer, expected a Utf8>"
+        );
+    }
+
+    #[test]
+    fn decodes_ascii() {
+        assert_eq!(
+            decode_modified_utf8(b"Hello, World!").unwrap(),
+            "Hello, World!"
+        );
+        assert_eq!(decode_modified_utf8(b"").unwrap(), "");
+    }
+
+    #[test]
+    fn decodes_two_and_three_byte_sequences() {
+        assert_eq!(decode_modified_utf8(&[0xC3, 0xA9]).unwrap(), "\u{e9}");
+        assert_eq!(
+            decode_modified_utf8(&[0xE2, 0x82, 0xAC]).unwrap(),
+            "\u{20ac}"
+        );
+    }
+
+    #[test]
+    fn decodes_encoded_nul() {
+        assert_eq!(
+            decode_modified_utf8(&[b'a', 0xC0, 0x80, b'b']).unwrap(),
+            "a\0b"
+        );
+    }
+
+    #[test]
+    fn decodes_surrogate_pairs() {
+        // U+1F600 is the surrogate pair D83D DE00
+        let bytes = [0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80];
+        assert_eq!(decode_modified_utf8(&bytes).unwrap(), "\u{1f600}");
+        assert_eq!(
+            decode_modified_utf8_units(&bytes).unwrap(),
+            vec![0xD83D, 0xDE00]
+        );
+    }
+
+    #[test]
+    fn replaces_unpaired_surrogates() {
+        assert_eq!(
+            decode_modified_utf8_units(&[0xED, 0xA0, 0xBD]).unwrap(),
+            vec![0xD83D]
+        );
+        assert_eq!(
+            decode_modified_utf8(&[0xED, 0xA0, 0xBD]).unwrap(),
+            "\u{fffd}"
+        );
+    }
+
+    #[test]
+    fn utf8_entries_keep_unpaired_surrogates() {
+        let bytes = [0xED, 0xA0, 0x80, b'x'];
+        let entry = Utf8::from_bytes(&bytes).unwrap();
+        assert_eq!(entry.as_str(), "\u{fffd}x");
+        assert_eq!(entry.units(), vec![0xD800, 'x' as u16]);
+        assert_eq!(entry.bytes(), bytes);
+        assert!(Utf8::from_bytes(&[0xED, 0xA0]).is_none());
+    }
+
+    #[test]
+    fn utf8_entries_keep_the_bytes_they_were_read_from() {
+        // an overlong encoding of 'A' decodes fine but isn't what `new` would write
+        let entry = Utf8::from_bytes(&[0xC1, 0x81]).unwrap();
+        assert_eq!(entry.as_str(), "A");
+        assert_eq!(entry.bytes(), [0xC1, 0x81]);
+        assert_eq!(Utf8::new("A").bytes(), b"A");
+        assert_eq!(Utf8::new("a\0b").bytes(), [b'a', 0xC0, 0x80, b'b']);
+    }
+
+    #[test]
+    fn decodes_the_largest_code_point_of_each_length() {
+        assert_eq!(decode_modified_utf8_units(&[0x7F]).unwrap(), vec![0x7F]);
+        assert_eq!(
+            decode_modified_utf8_units(&[0xDF, 0xBF]).unwrap(),
+            vec![0x7FF]
+        );
+        assert_eq!(
+            decode_modified_utf8_units(&[0xEF, 0xBF, 0xBF]).unwrap(),
+            vec![0xFFFF]
+        );
+    }
+
+    #[test]
+    fn keeps_surrogates_in_the_wrong_order_as_two_units() {
+        let bytes = [0xED, 0xB8, 0x80, 0xED, 0xA0, 0xBD];
+        assert_eq!(
+            decode_modified_utf8_units(&bytes).unwrap(),
+            vec![0xDE00, 0xD83D]
+        );
+        assert_eq!(decode_modified_utf8(&bytes).unwrap(), "\u{fffd}\u{fffd}");
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert!(decode_modified_utf8(&[0x00]).is_none());
+        assert!(decode_modified_utf8(&[0xF0, 0x9F, 0x98, 0x80]).is_none());
+        assert!(decode_modified_utf8(&[0x80]).is_none());
+        assert!(decode_modified_utf8(&[0xC3]).is_none());
+        assert!(decode_modified_utf8(&[0xE2, 0x82]).is_none());
+        assert!(decode_modified_utf8(&[0xE2, 0x41, 0xAC]).is_none());
+    }
+
+    #[test]
+    fn encodes_nul_and_supplementary_characters() {
+        assert_eq!(encode_modified_utf8("a\0b"), vec![b'a', 0xC0, 0x80, b'b']);
+        assert_eq!(
+            encode_modified_utf8("\u{1f600}"),
+            vec![0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80]
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        for s in [
+            "",
+            "plain",
+            "caf\u{e9}",
+            "\0\0",
+            "\u{20ac}\u{1f600}x\u{7ff}\u{800}",
+        ] {
+            assert_eq!(decode_modified_utf8(&encode_modified_utf8(s)).unwrap(), s);
+        }
     }
 }
@@ -0,0 +1,112 @@
+use std::fmt;
+
+/// A field type as written in field and method descriptors, e.g. `I`,
+/// `Ljava/lang/String;` or `[[D`
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldType {
+    Byte,
+    Char,
+    Double,
+    Float,
+    Int,
+    Long,
+    Short,
+    Boolean,
+    /// A class or interface, by its binary name e.g. `java/lang/String`
+    Object(String),
+    Array(Box<FieldType>),
+}
+
+impl FieldType {
+    pub fn parse(descriptor: &str) -> Option<FieldType> {
+        match FieldType::parse_prefix(descriptor)? {
+            (field_type, "") => Some(field_type),
+            _ => None,
+        }
+    }
+
+    /// Parses the field type at the start of `descriptor`, returning it with the rest
+    fn parse_prefix(descriptor: &str) -> Option<(FieldType, &str)> {
+        let rest = descriptor.get(1..)?;
+        let field_type = match descriptor.as_bytes().first()? {
+            b'B' => FieldType::Byte,
+            b'C' => FieldType::Char,
+            b'D' => FieldType::Double,
+            b'F' => FieldType::Float,
+            b'I' => FieldType::Int,
+            b'J' => FieldType::Long,
+            b'S' => FieldType::Short,
+            b'Z' => FieldType::Boolean,
+            b'L' => {
+                let (name, rest) = rest.split_once(';')?;
+                if name.is_empty() {
+                    return None;
+                }
+                return Some((FieldType::Object(name.to_string()), rest));
+            }
+            b'[' => {
+                let (component, rest) = FieldType::parse_prefix(rest)?;
+                return Some((FieldType::Array(Box::new(component)), rest));
+            }
+            _ => return None,
+        };
+        Some((field_type, rest))
+    }
+
+    /// Number of local variable or operand stack slots a value of this type takes
+    pub fn slots(&self) -> usize {
+        match self {
+            FieldType::Long | FieldType::Double => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Displays the type as written in Java source, e.g. `int`, `java.lang.String` or
+/// `double[][]`
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldType::Byte => write!(f, "byte"),
+            FieldType::Char => write!(f, "char"),
+            FieldType::Double => write!(f, "double"),
+            FieldType::Float => write!(f, "float"),
+            FieldType::Int => write!(f, "int"),
+            FieldType::Long => write!(f, "long"),
+            FieldType::Short => write!(f, "short"),
+            FieldType::Boolean => write!(f, "boolean"),
+            FieldType::Object(name) => write!(f, "{}", name.replace('/', ".")),
+            FieldType::Array(component) => write!(f, "{component}[]"),
+        }
+    }
+}
+
+/// A parsed method descriptor, e.g. `(II)I`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodDescriptor {
+    pub params: Vec<FieldType>,
+    /// None for `V`
+    pub ret: Option<FieldType>,
+}
+
+impl MethodDescriptor {
+    pub fn parse(descriptor: &str) -> Option<MethodDescriptor> {
+        let (mut params_descriptor, ret) = descriptor.strip_prefix('(')?.split_once(')')?;
+        let mut params = vec![];
+        while !params_descriptor.is_empty() {
+            let (param, rest) = FieldType::parse_prefix(params_descriptor)?;
+            params.push(param);
+            params_descriptor = rest;
+        }
+        let ret = match ret {
+            "V" => None,
+            ret => Some(FieldType::parse(ret)?),
+        };
+        Some(MethodDescriptor { params, ret })
+    }
+
+    /// Number of local variable slots the parameters take, not counting `this`
+    pub fn param_slots(&self) -> usize {
+        self.params.iter().map(FieldType::slots).sum()
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt;
+
+use super::constantpool::ConstantPoolError;
+
+/// The part of the class file being parsed when an error occurred
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Section {
+    Header,
+    ConstantPool,
+    ClassInfo,
+    Interfaces,
+    Fields,
+    Methods,
+    Code,
+    Attributes,
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Section::Header => "header",
+            Section::ConstantPool => "constant pool",
+            Section::ClassInfo => "class info",
+            Section::Interfaces => "interfaces",
+            Section::Fields => "fields",
+            Section::Methods => "methods",
+            Section::Code => "code",
+            Section::Attributes => "attributes",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    UnexpectedEof {
+        needed: usize,
+        remaining: usize,
+    },
+    InvalidMagic(u32),
+    UnsupportedVersion {
+        major: u16,
+        minor: u16,
+    },
+    ConstantPool(ConstantPoolError),
+    UnknownConstantTag(u8),
+    MalformedUtf8 {
+        index: usize,
+    },
+    UnknownInstruction {
+        opcode: u8,
+        pc: usize,
+    },
+    InvalidWideInstruction {
+        opcode: u8,
+        pc: usize,
+    },
+    InvalidBranchTarget {
+        target: i64,
+        pc: usize,
+    },
+    InvalidTableSwitch {
+        low: i32,
+        high: i32,
+        pc: usize,
+    },
+    UnknownArrayType {
+        atype: u8,
+        pc: usize,
+    },
+    /// A `multianewarray` creating an array of 0 dimensions
+    ZeroDimensions {
+        pc: usize,
+    },
+    AttributeLength {
+        name: String,
+        length: u32,
+        remaining: usize,
+    },
+    TrailingBytes(usize),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnexpectedEof { needed, remaining } => write!(
+                f,
+                "Out of bound, needed {needed} bytes but only {remaining} are left"
+            ),
+            ParseErrorKind::InvalidMagic(magic) => {
+                write!(f, "Invalid magic 0x{magic:x}, not a class file")
+            }
+            ParseErrorKind::UnsupportedVersion { major, minor } => {
+                write!(f, "Unsupported class file version {major}.{minor}")
+            }
+            ParseErrorKind::ConstantPool(err) => write!(f, "{err}"),
+            ParseErrorKind::UnknownConstantTag(tag) => write!(f, "Unknown tag {tag}"),
+            ParseErrorKind::MalformedUtf8 { index } => {
+                write!(f, "Malformed modified UTF-8 at constant pool index {index}")
+            }
+            ParseErrorKind::UnknownInstruction { opcode, pc } => {
+                write!(f, "Unknown Instruction 0x{opcode:x} at pc {pc}")
+            }
+            ParseErrorKind::InvalidWideInstruction { opcode, pc } => {
+                write!(f, "Invalid wide Instruction 0x{opcode:x} at pc {pc}")
+            }
+            ParseErrorKind::InvalidBranchTarget { target, pc } => {
+                write!(f, "Branch target {target} out of code bounds at pc {pc}")
+            }
+            ParseErrorKind::InvalidTableSwitch { low, high, pc } => {
+                write!(f, "tableswitch low {low} > high {high} at pc {pc}")
+            }
+            ParseErrorKind::UnknownArrayType { atype, pc } => {
+                write!(f, "Unknown newarray type {atype} at pc {pc}")
+            }
+            ParseErrorKind::ZeroDimensions { pc } => {
+                write!(f, "multianewarray with 0 dimensions at pc {pc}")
+            }
+            ParseErrorKind::AttributeLength {
+                name,
+                length,
+                remaining,
+            } => write!(
+                f,
+                "Attribute {name} declares {length} bytes but {remaining} were left unread"
+            ),
+            ParseErrorKind::TrailingBytes(n) => {
+                write!(f, "{n} extra bytes at the end of the class file")
+            }
+        }
+    }
+}
+
+/// Error returned by the class file parser
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// Byte offset into the class file where the error was detected
+    pub offset: usize,
+    pub section: Section,
+    pub cause: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "offset 0x{:x} ({}): {}",
+            self.offset, self.section, self.cause
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn displays_offset_section_and_cause() {
+        let err = ParseError {
+            offset: 0x1a,
+            section: Section::ConstantPool,
+            cause: ParseErrorKind::MalformedUtf8 { index: 2 },
+        };
+        assert_eq!(
+            err.to_string(),
+            "offset 0x1a (constant pool): Malformed modified UTF-8 at constant pool index 2"
+        );
+    }
+
+    #[test]
+    fn displays_constant_pool_errors_as_is() {
+        let err = ParseError {
+            offset: 8,
+            section: Section::Interfaces,
+            cause: ParseErrorKind::ConstantPool(ConstantPoolError::UnexpectedTag {
+                index: 3,
+                expected: "Class",
+                found: "Utf8",
+            }),
+        };
+        assert_eq!(
+            err.to_string(),
+            "offset 0x8 (interfaces): Constant Pool Index 3 is a Utf8, expected a Class"
+        );
+    }
+
+    #[test]
+    fn displays_causes() {
+        let causes = [
+            (
+                ParseErrorKind::UnexpectedEof {
+                    needed: 4,
+                    remaining: 1,
+                },
+                "Out of bound, needed 4 bytes but only 1 are left",
+            ),
+            (
+                ParseErrorKind::InvalidMagic(0xcafebabf),
+                "Invalid magic 0xcafebabf, not a class file",
+            ),
+            (
+                ParseErrorKind::UnknownInstruction {
+                    opcode: 0xca,
+                    pc: 3,
+                },
+                "Unknown Instruction 0xca at pc 3",
+            ),
+            (
+                ParseErrorKind::AttributeLength {
+                    name: "SourceFile".to_string(),
+                    length: 3,
+                    remaining: 1,
+                },
+                "Attribute SourceFile declares 3 bytes but 1 were left unread",
+            ),
+            (
+                ParseErrorKind::TrailingBytes(2),
+                "2 extra bytes at the end of the class file",
+            ),
+        ];
+        for (cause, message) in causes {
+            assert_eq!(cause.to_string(), message);
+        }
+    }
+}
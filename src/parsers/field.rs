@@ -0,0 +1,129 @@
+use crate::utils::bytestream::ByteStream;
+use crate::{Attr, JavaClassFile};
+
+use super::accessflags::parse_field_access_flags;
+use super::attributes::parse_attrs;
+use super::error::ParseError;
+
+/// A field_info entry
+#[derive(Default, Debug, Clone)]
+pub struct Field {
+    pub access_flags: Vec<String>,
+    pub name: String,
+    pub descriptor: String,
+    pub attrs: Vec<Attr>,
+    /// The access flags as read, including the bits that have no `ACC_*` name
+    pub access_mask: u16,
+    /// Utf8 entries `name` and `descriptor` were read from, 0 if it wasn't parsed
+    pub name_index: u16,
+    pub descriptor_index: u16,
+}
+
+pub fn parse_fields(
+    class: &JavaClassFile,
+    bytes: &mut ByteStream,
+) -> Result<Vec<Field>, ParseError> {
+    let mut fields: Vec<Field> = vec![];
+
+    for _ in 0..bytes.parse_u2()? {
+        let mask = bytes.parse_u2()?;
+        let name_index = bytes.parse_u2()?;
+        let descriptor_index = bytes.parse_u2()?;
+
+        fields.push(Field {
+            access_flags: parse_field_access_flags(mask),
+            name: bytes
+                .resolve(class.constant_pool.utf8(name_index as usize))?
+                .to_string(),
+            descriptor: bytes
+                .resolve(class.constant_pool.utf8(descriptor_index as usize))?
+                .to_string(),
+            attrs: parse_attrs(bytes, &class.constant_pool)?,
+            access_mask: mask,
+            name_index,
+            descriptor_index,
+        });
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::constantpool::{Constant, ConstantPool, ConstantPoolError, Utf8};
+    use crate::parsers::error::ParseErrorKind;
+
+    fn class() -> JavaClassFile {
+        let constants = ["count", "I", "ConstantValue", "name", "Ljava/lang/String;"]
+            .map(|value| Constant::Utf8(Utf8::new(value)));
+        let mut constants = constants.to_vec();
+        constants.push(Constant::Integer(7));
+        JavaClassFile {
+            constant_pool: ConstantPool { constants },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_fields_with_their_attributes() {
+        let bytes = [
+            0, 2, // two fields
+            0x00, 0x1A, 0, 1, 0, 2, 0, 1, // private static final int count
+            0, 3, 0, 0, 0, 2, 0, 6, // ConstantValue #6
+            0x00, 0x01, 0, 4, 0, 5, 0, 0, // public String name
+        ];
+        let fields = parse_fields(&class(), &mut ByteStream::new(&bytes)).unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(
+            fields[0].access_flags,
+            ["ACC_PRIVATE", "ACC_STATIC", "ACC_FINAL"]
+        );
+        assert_eq!(
+            (fields[0].name.as_str(), fields[0].descriptor.as_str()),
+            ("count", "I")
+        );
+        assert!(matches!(
+            fields[0].attrs[..],
+            [Attr::ConstantValue { value_index: 6, .. }]
+        ));
+        assert_eq!(fields[1].access_flags, ["ACC_PUBLIC"]);
+        assert_eq!(fields[1].name, "name");
+        assert_eq!(fields[1].descriptor, "Ljava/lang/String;");
+        assert!(fields[1].attrs.is_empty());
+    }
+
+    #[test]
+    fn parses_an_empty_fields_table() {
+        let fields = parse_fields(&class(), &mut ByteStream::new(&[0, 0])).unwrap();
+        assert!(fields.is_empty());
+    }
+
+    #[test]
+    fn rejects_names_that_are_not_utf8() {
+        let bytes = [0, 1, 0, 1, 0, 6, 0, 2, 0, 0];
+        let err = parse_fields(&class(), &mut ByteStream::new(&bytes)).unwrap_err();
+        assert_eq!(err.offset, 8);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::ConstantPool(ConstantPoolError::UnexpectedTag {
+                index: 6,
+                expected: "Utf8",
+                found: "Integer",
+            })
+        );
+    }
+
+    #[test]
+    fn reports_truncated_fields() {
+        let bytes = [0, 1, 0, 1, 0, 1];
+        let err = parse_fields(&class(), &mut ByteStream::new(&bytes)).unwrap_err();
+        assert_eq!(err.offset, 6);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::UnexpectedEof {
+                needed: 2,
+                remaining: 0,
+            }
+        );
+    }
+}
@@ -3,30 +3,46 @@ use crate::{Attr, JavaClassFile};
 
 use super::accessflags::parse_method_access_flags;
 use super::attributes::parse_attrs;
+use super::error::ParseError;
 
-#[derive(Debug, Clone)]
-#[allow(unused)]
+/// A method_info entry, its bytecode lives in the `Attr::Code` attribute
+#[derive(Default, Debug, Clone)]
 pub struct Method {
     pub access_flags: Vec<String>,
     pub name: String,
     pub descriptor: String,
     pub attrs: Vec<Attr>,
+    /// The access flags as read, including the bits that have no `ACC_*` name
+    pub access_mask: u16,
+    /// Utf8 entries `name` and `descriptor` were read from, 0 if it wasn't parsed
+    pub name_index: u16,
+    pub descriptor_index: u16,
 }
 
-pub fn parse_methods(class: &JavaClassFile, bytes: &mut ByteStream) -> Vec<Method> {
+pub fn parse_methods(
+    class: &JavaClassFile,
+    bytes: &mut ByteStream,
+) -> Result<Vec<Method>, ParseError> {
     let mut methods: Vec<Method> = vec![];
 
-    for _ in 0..bytes.parse_u2() {
-        let mask = bytes.parse_u2();
-        let name_index = bytes.parse_u2();
-        let descriptor_index = bytes.parse_u2();
+    for _ in 0..bytes.parse_u2()? {
+        let mask = bytes.parse_u2()?;
+        let name_index = bytes.parse_u2()?;
+        let descriptor_index = bytes.parse_u2()?;
 
         methods.push(Method {
             access_flags: parse_method_access_flags(mask),
-            name: class.constant_pool.query(name_index as usize),
-            descriptor: class.constant_pool.query(descriptor_index as usize),
-            attrs: parse_attrs(bytes, &class.constant_pool),
+            name: bytes
+                .resolve(class.constant_pool.utf8(name_index as usize))?
+                .to_string(),
+            descriptor: bytes
+                .resolve(class.constant_pool.utf8(descriptor_index as usize))?
+                .to_string(),
+            attrs: parse_attrs(bytes, &class.constant_pool)?,
+            access_mask: mask,
+            name_index,
+            descriptor_index,
         });
     }
-    methods
+    Ok(methods)
 }
@@ -1,6 +1,10 @@
 pub mod class;
+pub mod field;
 pub mod method;
 pub mod bytecode;
 pub mod attributes;
 pub mod accessflags;
 pub mod constantpool;
+pub mod error;
+pub mod version;
+pub mod descriptor;
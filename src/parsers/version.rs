@@ -0,0 +1,111 @@
+pub const MAGIC: u32 = 0xCAFEBABE;
+
+/// Minor version marking a class file that depends on preview features of its release
+pub const PREVIEW_MINOR_VERSION: u16 = 0xFFFF;
+
+pub const MIN_SUPPORTED_MAJOR: u16 = 45;
+pub const MAX_SUPPORTED_MAJOR: u16 = 67;
+
+/// Preview features exist since Java 12
+const FIRST_PREVIEW_MAJOR: u16 = 56;
+
+/// What the parser does with a class file whose version it does not support
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum VersionPolicy {
+    /// Fail with `ParseErrorKind::UnsupportedVersion`
+    Reject,
+    /// Parse anyway and record a warning on the parser
+    #[default]
+    Warn,
+    /// Parse anyway without saying anything
+    Allow,
+}
+
+/// Java release that introduced a class file major version, e.g. `Java 8` for 52
+pub fn java_release(major: u16) -> Option<&'static str> {
+    let release = match major {
+        45 => "Java 1.1",
+        46 => "Java 1.2",
+        47 => "Java 1.3",
+        48 => "Java 1.4",
+        49 => "Java 5",
+        50 => "Java 6",
+        51 => "Java 7",
+        52 => "Java 8",
+        53 => "Java 9",
+        54 => "Java 10",
+        55 => "Java 11",
+        56 => "Java 12",
+        57 => "Java 13",
+        58 => "Java 14",
+        59 => "Java 15",
+        60 => "Java 16",
+        61 => "Java 17",
+        62 => "Java 18",
+        63 => "Java 19",
+        64 => "Java 20",
+        65 => "Java 21",
+        66 => "Java 22",
+        67 => "Java 23",
+        _ => return None,
+    };
+    Some(release)
+}
+
+pub fn is_preview(major: u16, minor: u16) -> bool {
+    major >= FIRST_PREVIEW_MAJOR && minor == PREVIEW_MINOR_VERSION
+}
+
+/// Whether this parser knows the class file format of `major.minor`
+pub fn is_supported_version(major: u16, minor: u16) -> bool {
+    if !(MIN_SUPPORTED_MAJOR..=MAX_SUPPORTED_MAJOR).contains(&major) {
+        return false;
+    }
+    // before Java 12 the minor version may be anything, since then it is either 0
+    // or marks a class depending on preview features
+    major < FIRST_PREVIEW_MAJOR || minor == 0 || minor == PREVIEW_MINOR_VERSION
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn supports_every_minor_version_before_java_12() {
+        assert!(is_supported_version(45, 3));
+        assert!(is_supported_version(52, 0));
+        assert!(is_supported_version(55, 7));
+        assert!(is_supported_version(55, PREVIEW_MINOR_VERSION));
+    }
+
+    #[test]
+    fn supports_only_minor_0_and_preview_since_java_12() {
+        assert!(is_supported_version(56, 0));
+        assert!(is_supported_version(61, PREVIEW_MINOR_VERSION));
+        assert!(!is_supported_version(56, 1));
+        assert!(!is_supported_version(61, 3));
+    }
+
+    #[test]
+    fn rejects_unknown_major_versions() {
+        assert!(!is_supported_version(44, 0));
+        assert!(!is_supported_version(MAX_SUPPORTED_MAJOR + 1, 0));
+        assert!(is_supported_version(MAX_SUPPORTED_MAJOR, 0));
+    }
+
+    #[test]
+    fn detects_preview_minor_versions() {
+        assert!(is_preview(61, PREVIEW_MINOR_VERSION));
+        assert!(!is_preview(61, 0));
+        // there were no preview features before Java 12
+        assert!(!is_preview(55, PREVIEW_MINOR_VERSION));
+    }
+
+    #[test]
+    fn names_java_releases() {
+        assert_eq!(java_release(45), Some("Java 1.1"));
+        assert_eq!(java_release(52), Some("Java 8"));
+        assert_eq!(java_release(61), Some("Java 17"));
+        assert_eq!(java_release(44), None);
+    }
+}
@@ -1,37 +1,69 @@
-use crate::{parsers::method::Method, JavaClassFile};
+use crate::{
+    parsers::{bytecode::disassemble, field::Field, method::Method},
+    Attr, JavaClassFile,
+};
 
+/// The answer to a [`QueryType`], in the variant of the same name. Methods and fields
+/// are returned without their attributes.
 #[derive(Debug)]
+// every variant carries the `Q` prefix of the QueryType it answers, renaming them would
+// break the library's users
+#[allow(clippy::enum_variant_names)]
 pub enum QueryResult {
     QMethod(Method),
     QMethodList(Vec<Method>),
+    QField(Field),
+    QFieldList(Vec<Field>),
+    QInterfaceList(Vec<String>),
+    /// One javap style line per instruction
+    QDisassembly(Vec<String>),
 }
 
+/// A question [`Query::query`] can answer
 #[derive(Debug)]
+// the `Q` prefix is part of the public API, see QueryResult
+#[allow(clippy::enum_variant_names)]
 pub enum QueryType {
+    /// The first method with the given name
     QMethod(String),
+    /// Every method of the class
     QMethodList,
+    /// The field with the given name
+    QField(String),
+    /// Every field of the class
+    QFieldList,
+    /// The binary names of the interfaces the class implements
+    QInterfaceList,
+    /// The disassembled code of the first method with the given name
+    QDisassemble(String),
 }
 
+/// Answers questions about a parsed class without exposing its whole structure
 pub struct Query<'a> {
     class_file: &'a JavaClassFile,
 }
 
 impl<'a> Query<'a> {
+    /// A query over `class_file`
     pub fn new(class_file: &'a JavaClassFile) -> Self {
         Self { class_file }
     }
 
+    /// Answers `q`, `None` when the method or field asked for does not exist or the
+    /// method to disassemble has no code
     pub fn query(&self, q: QueryType) -> Option<QueryResult> {
         match q {
             QueryType::QMethod(name) => {
                 let m = self.class_file.methods.iter().find(|m| m.name == name);
-                if m.is_some() {
-                    let m = m.unwrap();
+                if let Some(m) = m {
                     return Some(QueryResult::QMethod(Method {
                         access_flags: m.access_flags.clone(),
                         name: m.name.clone(),
                         descriptor: m.descriptor.clone(),
                         attrs: vec![],
+                        access_mask: m.access_mask,
+                        name_index: m.name_index,
+                        descriptor_index: m.descriptor_index,
                     }));
                 }
                 None
@@ -41,16 +73,60 @@ impl<'a> Query<'a> {
                     .class_file
                     .methods
                     .iter()
-                    .map(|m| {
-                        return Method {
-                            access_flags: m.access_flags.clone(),
-                            name: m.name.clone(),
-                            descriptor: m.descriptor.clone(),
-                            attrs: vec![],
-                        };
+                    .map(|m| Method {
+                        access_flags: m.access_flags.clone(),
+                        name: m.name.clone(),
+                        descriptor: m.descriptor.clone(),
+                        attrs: vec![],
+                        access_mask: m.access_mask,
+                        name_index: m.name_index,
+                        descriptor_index: m.descriptor_index,
                     })
                     .collect::<Vec<Method>>();
-                return Some(QueryResult::QMethodList(list));
+                Some(QueryResult::QMethodList(list))
+            }
+            QueryType::QField(name) => {
+                let f = self.class_file.fields.iter().find(|f| f.name == name)?;
+                Some(QueryResult::QField(Field {
+                    access_flags: f.access_flags.clone(),
+                    name: f.name.clone(),
+                    descriptor: f.descriptor.clone(),
+                    attrs: vec![],
+                    access_mask: f.access_mask,
+                    name_index: f.name_index,
+                    descriptor_index: f.descriptor_index,
+                }))
+            }
+            QueryType::QFieldList => {
+                let list = self
+                    .class_file
+                    .fields
+                    .iter()
+                    .map(|f| Field {
+                        access_flags: f.access_flags.clone(),
+                        name: f.name.clone(),
+                        descriptor: f.descriptor.clone(),
+                        attrs: vec![],
+                        access_mask: f.access_mask,
+                        name_index: f.name_index,
+                        descriptor_index: f.descriptor_index,
+                    })
+                    .collect::<Vec<Field>>();
+                Some(QueryResult::QFieldList(list))
+            }
+            QueryType::QInterfaceList => Some(QueryResult::QInterfaceList(
+                self.class_file.interfaces.clone(),
+            )),
+            QueryType::QDisassemble(name) => {
+                let m = self.class_file.methods.iter().find(|m| m.name == name)?;
+                let code = m.attrs.iter().find_map(|attr| match attr {
+                    Attr::Code { code, .. } => Some(code),
+                    _ => None,
+                })?;
+                Some(QueryResult::QDisassembly(disassemble(
+                    code,
+                    &self.class_file.constant_pool,
+                )))
             }
         }
     }
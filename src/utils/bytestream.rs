@@ -1,41 +1,190 @@
-use std::process::exit;
+use crate::parsers::{
+    constantpool::ConstantPoolError,
+    error::{ParseError, ParseErrorKind, Section},
+};
 
-#[derive(Debug)]
-pub struct ByteStream {
-    pub xs: Vec<u8>,
+/// Big endian reader over a borrowed class file. Sub-streams share the same buffer and
+/// only narrow the readable window, so offsets always refer to the whole class file.
+#[derive(Debug, Clone)]
+pub struct ByteStream<'a> {
+    xs: &'a [u8],
+    pos: usize,
+    end: usize,
+    /// Section reported by errors raised while reading from this stream
+    pub section: Section,
 }
 
-impl ByteStream {
-    pub fn parse_u1(&mut self) -> u8 {
-        self.check_bound(1);
-        self.xs.remove(0)
+impl<'a> ByteStream<'a> {
+    pub fn new(xs: &'a [u8]) -> Self {
+        Self {
+            xs,
+            pos: 0,
+            end: xs.len(),
+            section: Section::Header,
+        }
+    }
+
+    pub fn parse_u1(&mut self) -> Result<u8, ParseError> {
+        Ok(self.parse_n(1)?[0])
+    }
+
+    pub fn parse_u2(&mut self) -> Result<u16, ParseError> {
+        let res = self.parse_n(2)?;
+        Ok(u16::from_be_bytes([res[0], res[1]]))
+    }
+
+    pub fn parse_u4(&mut self) -> Result<u32, ParseError> {
+        let res = self.parse_n(4)?;
+        Ok(u32::from_be_bytes([res[0], res[1], res[2], res[3]]))
+    }
+
+    pub fn parse_n(&mut self, n: usize) -> Result<&'a [u8], ParseError> {
+        self.check_bound(n)?;
+        let res = &self.xs[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(res)
+    }
+
+    /// Splits off the next `n` bytes as a stream of their own without copying them
+    pub fn sub_stream(&mut self, n: usize) -> Result<ByteStream<'a>, ParseError> {
+        self.check_bound(n)?;
+        let sub = ByteStream {
+            xs: self.xs,
+            pos: self.pos,
+            end: self.pos + n,
+            section: self.section,
+        };
+        self.pos += n;
+        Ok(sub)
+    }
+
+    /// Position of the next byte to be read, relative to the start of the class file
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.end - self.pos
     }
 
-    pub fn parse_u2(&mut self) -> u16 {
-        self.check_bound(2);
-        let res = self.xs[0..2].to_vec();
-        self.xs = self.xs[2..].to_vec();
-        u16::from_be_bytes(res.try_into().unwrap())
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
     }
 
-    pub fn parse_u4(&mut self) -> u32 {
-        self.check_bound(4);
-        let res = self.xs[0..4].to_vec();
-        self.xs = self.xs[4..].to_vec();
-        u32::from_be_bytes(res.try_into().unwrap())
+    /// Builds an error located at the current position of the stream
+    pub fn error(&self, cause: ParseErrorKind) -> ParseError {
+        ParseError {
+            offset: self.pos,
+            section: self.section,
+            cause,
+        }
     }
 
-    pub fn parse_n(&mut self, n: usize) -> Vec<u8> {
-        self.check_bound(n);
-        let res = self.xs[0..n].to_vec();
-        self.xs = self.xs[n..].to_vec();
-        res
+    /// Turns a failed constant pool lookup into an error at the current position
+    pub fn resolve<T>(&self, result: Result<T, ConstantPoolError>) -> Result<T, ParseError> {
+        result.map_err(|err| self.error(ParseErrorKind::ConstantPool(err)))
     }
 
-    fn check_bound(&self, n: usize) {
-        if self.xs.len() < n {
-            eprintln!("[ERROR]:{}:{}: Out of bound", file!(), line!());
-            exit(1);
+    fn check_bound(&self, n: usize) -> Result<(), ParseError> {
+        if self.remaining() < n {
+            return Err(self.error(ParseErrorKind::UnexpectedEof {
+                needed: n,
+                remaining: self.remaining(),
+            }));
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_big_endian_values() {
+        let bytes = [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+        let mut stream = ByteStream::new(&bytes);
+        assert_eq!(stream.parse_u1(), Ok(0x01));
+        assert_eq!(stream.parse_u2(), Ok(0x0203));
+        assert_eq!(stream.parse_u4(), Ok(0x04050607));
+        assert!(stream.is_empty());
+    }
+
+    #[test]
+    fn parse_n_borrows_from_the_input() {
+        let bytes = [1, 2, 3, 4];
+        let mut stream = ByteStream::new(&bytes);
+        stream.parse_u1().unwrap();
+        let slice = stream.parse_n(2).unwrap();
+        assert_eq!(slice, [2, 3]);
+        assert!(std::ptr::eq(slice.as_ptr(), &bytes[1]));
+        assert_eq!((stream.offset(), stream.remaining()), (3, 1));
+    }
+
+    #[test]
+    fn sub_streams_keep_offsets_into_the_whole_input() {
+        let bytes = [0, 1, 2, 3, 4, 5];
+        let mut stream = ByteStream::new(&bytes);
+        stream.parse_u2().unwrap();
+        let mut sub = stream.sub_stream(3).unwrap();
+        assert_eq!(stream.offset(), 5);
+        assert_eq!((sub.offset(), sub.remaining()), (2, 3));
+        assert_eq!(sub.parse_n(3), Ok(&bytes[2..5]));
+        assert!(sub.is_empty());
+    }
+
+    #[test]
+    fn sub_streams_stop_at_their_end() {
+        let bytes = [0, 1, 2, 3, 4, 5];
+        let mut stream = ByteStream::new(&bytes);
+        stream.section = Section::Code;
+        stream.parse_u1().unwrap();
+        let mut sub = stream.sub_stream(3).unwrap();
+        sub.parse_u2().unwrap();
+        // bytes are left in the whole input but not in the window of the sub-stream
+        assert_eq!(
+            sub.parse_u2(),
+            Err(ParseError {
+                offset: 3,
+                section: Section::Code,
+                cause: ParseErrorKind::UnexpectedEof {
+                    needed: 2,
+                    remaining: 1,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn reports_reads_past_the_end_without_consuming() {
+        let mut stream = ByteStream::new(&[1, 2, 3]);
+        stream.parse_u1().unwrap();
+        let err = stream.parse_u4().unwrap_err();
+        assert_eq!(err.offset, 1);
+        assert_eq!(
+            err.cause,
+            ParseErrorKind::UnexpectedEof {
+                needed: 4,
+                remaining: 2,
+            }
+        );
+        assert_eq!(stream.parse_u2(), Ok(0x0203));
+        assert!(stream.sub_stream(1).is_err());
+    }
+
+    #[test]
+    fn resolves_constant_pool_errors_at_the_current_offset() {
+        let mut stream = ByteStream::new(&[0, 7]);
+        stream.section = Section::Interfaces;
+        stream.parse_u2().unwrap();
+        let result: Result<(), _> = Err(ConstantPoolError::IndexOutOfBounds(7));
+        assert_eq!(
+            stream.resolve(result),
+            Err(ParseError {
+                offset: 2,
+                section: Section::Interfaces,
+                cause: ParseErrorKind::ConstantPool(ConstantPoolError::IndexOutOfBounds(7)),
+            })
+        );
     }
 }
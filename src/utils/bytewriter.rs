@@ -0,0 +1,31 @@
+/// Big endian writer, the counterpart of `ByteStream`
+#[derive(Debug, Default)]
+pub struct ByteWriter {
+    pub xs: Vec<u8>,
+}
+
+impl ByteWriter {
+    pub fn write_u1(&mut self, x: u8) {
+        self.xs.push(x);
+    }
+
+    pub fn write_u2(&mut self, x: u16) {
+        self.xs.extend_from_slice(&x.to_be_bytes());
+    }
+
+    pub fn write_u4(&mut self, x: u32) {
+        self.xs.extend_from_slice(&x.to_be_bytes());
+    }
+
+    pub fn write_n(&mut self, xs: &[u8]) {
+        self.xs.extend_from_slice(xs);
+    }
+
+    pub fn len(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.xs.is_empty()
+    }
+}
@@ -1,4 +1,2 @@
-pub mod dump;
-pub mod prompt;
 pub mod bytestream;
-pub mod read_file_to_bytes;
+pub mod bytewriter;
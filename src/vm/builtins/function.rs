@@ -0,0 +1,151 @@
+use crate::parsers::{class::JavaClassFile, method::Method, version::MAGIC};
+
+/// A JDK functional interface, so lambdas can implement it without a JDK
+pub(super) struct Interface {
+    name: &'static str,
+    super_interfaces: &'static [&'static str],
+    /// (name, descriptor) of its abstract method, None when it's inherited
+    method: Option<(&'static str, &'static str)>,
+}
+
+/// In superinterface first order
+pub(super) const INTERFACES: [Interface; 21] = [
+    Interface {
+        name: "java/lang/Runnable",
+        super_interfaces: &[],
+        method: Some(("run", "()V")),
+    },
+    Interface {
+        name: "java/util/concurrent/Callable",
+        super_interfaces: &[],
+        method: Some(("call", "()Ljava/lang/Object;")),
+    },
+    Interface {
+        name: "java/util/Comparator",
+        super_interfaces: &[],
+        method: Some(("compare", "(Ljava/lang/Object;Ljava/lang/Object;)I")),
+    },
+    Interface {
+        name: "java/util/function/Supplier",
+        super_interfaces: &[],
+        method: Some(("get", "()Ljava/lang/Object;")),
+    },
+    Interface {
+        name: "java/util/function/Consumer",
+        super_interfaces: &[],
+        method: Some(("accept", "(Ljava/lang/Object;)V")),
+    },
+    Interface {
+        name: "java/util/function/BiConsumer",
+        super_interfaces: &[],
+        method: Some(("accept", "(Ljava/lang/Object;Ljava/lang/Object;)V")),
+    },
+    Interface {
+        name: "java/util/function/Function",
+        super_interfaces: &[],
+        method: Some(("apply", "(Ljava/lang/Object;)Ljava/lang/Object;")),
+    },
+    Interface {
+        name: "java/util/function/BiFunction",
+        super_interfaces: &[],
+        method: Some((
+            "apply",
+            "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+        )),
+    },
+    Interface {
+        name: "java/util/function/UnaryOperator",
+        super_interfaces: &["java/util/function/Function"],
+        method: None,
+    },
+    Interface {
+        name: "java/util/function/BinaryOperator",
+        super_interfaces: &["java/util/function/BiFunction"],
+        method: None,
+    },
+    Interface {
+        name: "java/util/function/Predicate",
+        super_interfaces: &[],
+        method: Some(("test", "(Ljava/lang/Object;)Z")),
+    },
+    Interface {
+        name: "java/util/function/BiPredicate",
+        super_interfaces: &[],
+        method: Some(("test", "(Ljava/lang/Object;Ljava/lang/Object;)Z")),
+    },
+    Interface {
+        name: "java/util/function/IntBinaryOperator",
+        super_interfaces: &[],
+        method: Some(("applyAsInt", "(II)I")),
+    },
+    Interface {
+        name: "java/util/function/IntUnaryOperator",
+        super_interfaces: &[],
+        method: Some(("applyAsInt", "(I)I")),
+    },
+    Interface {
+        name: "java/util/function/IntPredicate",
+        super_interfaces: &[],
+        method: Some(("test", "(I)Z")),
+    },
+    Interface {
+        name: "java/util/function/IntFunction",
+        super_interfaces: &[],
+        method: Some(("apply", "(I)Ljava/lang/Object;")),
+    },
+    Interface {
+        name: "java/util/function/ToIntFunction",
+        super_interfaces: &[],
+        method: Some(("applyAsInt", "(Ljava/lang/Object;)I")),
+    },
+    Interface {
+        name: "java/util/function/IntSupplier",
+        super_interfaces: &[],
+        method: Some(("getAsInt", "()I")),
+    },
+    Interface {
+        name: "java/util/function/IntConsumer",
+        super_interfaces: &[],
+        method: Some(("accept", "(I)V")),
+    },
+    Interface {
+        name: "java/util/function/LongSupplier",
+        super_interfaces: &[],
+        method: Some(("getAsLong", "()J")),
+    },
+    Interface {
+        name: "java/util/function/DoubleUnaryOperator",
+        super_interfaces: &[],
+        method: Some(("applyAsDouble", "(D)D")),
+    },
+];
+
+impl Interface {
+    pub(super) fn class_file(&self) -> JavaClassFile {
+        let flags = |flags: &[&str]| flags.iter().map(|flag| flag.to_string()).collect();
+        let methods = self
+            .method
+            .iter()
+            .map(|(name, descriptor)| Method {
+                access_flags: flags(&["ACC_PUBLIC", "ACC_ABSTRACT"]),
+                name: name.to_string(),
+                descriptor: descriptor.to_string(),
+                attrs: vec![],
+                ..Default::default()
+            })
+            .collect();
+        JavaClassFile {
+            magic: MAGIC,
+            access_flags: flags(&["ACC_PUBLIC", "ACC_INTERFACE", "ACC_ABSTRACT"]),
+            this_class: self.name.to_string(),
+            super_class: "java/lang/Object".to_string(),
+            interfaces: self
+                .super_interfaces
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+            methods,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use super::{boolean, Builtin, NativeResult};
+use crate::vm::{error::VmError, heap::ObjectData, value::Value, vm::Vm};
+
+pub(super) const INTEGER: Builtin = Builtin {
+    name: "java/lang/Integer",
+    super_class: "java/lang/Object",
+    static_fields: &[],
+    static_methods: &[
+        ("valueOf", "(I)Ljava/lang/Integer;", value_of),
+        ("parseInt", "(Ljava/lang/String;)I", parse_int),
+        ("toString", "(I)Ljava/lang/String;", to_string_static),
+        ("toHexString", "(I)Ljava/lang/String;", to_hex_string),
+        ("toBinaryString", "(I)Ljava/lang/String;", to_binary_string),
+        ("compare", "(II)I", compare),
+        ("max", "(II)I", max),
+        ("min", "(II)I", min),
+        ("sum", "(II)I", sum),
+    ],
+    methods: &[
+        ("intValue", "()I", int_value),
+        ("hashCode", "()I", int_value),
+        ("equals", "(Ljava/lang/Object;)Z", equals),
+        ("toString", "()Ljava/lang/String;", to_string),
+    ],
+};
+
+/// The value of a boxed Integer
+fn unbox(vm: &Vm, value: Value) -> Result<i32, VmError> {
+    match vm.object(value)?.data {
+        ObjectData::Integer(value) => Ok(value),
+        _ => Err(VmError::TypeMismatch {
+            expected: "java/lang/Integer",
+            found: "reference",
+        }),
+    }
+}
+
+fn value_of(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let value = args[0].as_int()?;
+    let cached = (-128..=127)
+        .contains(&value)
+        .then(|| (value + 128) as usize);
+    if let Some(obj) = cached.and_then(|slot| vm.integers[slot]) {
+        return Ok(Some(Value::Ref(obj)));
+    }
+    let boxed = vm.new_object("java/lang/Integer", ObjectData::Integer(value))?;
+    if let (Some(slot), Value::Ref(obj)) = (cached, boxed) {
+        vm.integers[slot] = Some(obj);
+    }
+    Ok(Some(boxed))
+}
+
+fn parse_int(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let string = vm.java_string(args[0])?;
+    match string.parse::<i32>() {
+        Ok(value) => Ok(Some(Value::Int(value))),
+        Err(_) => Err(VmError::exception(
+            "java/lang/NumberFormatException",
+            Some(format!("For input string: \"{string}\"")),
+        )),
+    }
+}
+
+fn to_string_static(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let string = args[0].as_int()?.to_string();
+    Ok(Some(vm.new_string(&string)?))
+}
+
+fn to_hex_string(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let string = format!("{:x}", args[0].as_int()? as u32);
+    Ok(Some(vm.new_string(&string)?))
+}
+
+fn to_binary_string(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let string = format!("{:b}", args[0].as_int()? as u32);
+    Ok(Some(vm.new_string(&string)?))
+}
+
+fn compare(_: &mut Vm, args: &[Value]) -> NativeResult {
+    let ordering = args[0].as_int()?.cmp(&args[1].as_int()?);
+    Ok(Some(Value::Int(ordering as i32)))
+}
+
+fn max(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Int(args[0].as_int()?.max(args[1].as_int()?))))
+}
+
+fn min(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Int(args[0].as_int()?.min(args[1].as_int()?))))
+}
+
+fn sum(_: &mut Vm, args: &[Value]) -> NativeResult {
+    let sum = args[0].as_int()?.wrapping_add(args[1].as_int()?);
+    Ok(Some(Value::Int(sum)))
+}
+
+fn int_value(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Int(unbox(vm, args[0])?)))
+}
+
+fn equals(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let is_integer = match args[1] {
+        Value::Ref(obj) => vm.class_of(obj) == "java/lang/Integer",
+        _ => false,
+    };
+    boolean(is_integer && unbox(vm, args[0])? == unbox(vm, args[1])?)
+}
+
+fn to_string(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let string = unbox(vm, args[0])?.to_string();
+    Ok(Some(vm.new_string(&string)?))
+}
@@ -0,0 +1,196 @@
+use super::{Builtin, NativeResult};
+use crate::vm::{error::VmError, value::Value, vm::Vm};
+
+pub(super) const MATH: Builtin = Builtin {
+    name: "java/lang/Math",
+    super_class: "java/lang/Object",
+    static_fields: &[],
+    static_methods: &[
+        ("abs", "(I)I", abs_int),
+        ("abs", "(J)J", abs_long),
+        ("abs", "(F)F", abs_float),
+        ("abs", "(D)D", abs_double),
+        ("max", "(II)I", max_int),
+        ("max", "(JJ)J", max_long),
+        ("max", "(FF)F", max_float),
+        ("max", "(DD)D", max_double),
+        ("min", "(II)I", min_int),
+        ("min", "(JJ)J", min_long),
+        ("min", "(FF)F", min_float),
+        ("min", "(DD)D", min_double),
+        ("floorDiv", "(II)I", floor_div),
+        ("floorMod", "(II)I", floor_mod),
+        ("sqrt", "(D)D", sqrt),
+        ("cbrt", "(D)D", cbrt),
+        ("pow", "(DD)D", pow),
+        ("exp", "(D)D", exp),
+        ("log", "(D)D", log),
+        ("log10", "(D)D", log10),
+        ("floor", "(D)D", floor),
+        ("ceil", "(D)D", ceil),
+        ("sin", "(D)D", sin),
+        ("cos", "(D)D", cos),
+        ("tan", "(D)D", tan),
+        ("atan2", "(DD)D", atan2),
+    ],
+    methods: &[],
+};
+
+fn abs_int(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Int(args[0].as_int()?.wrapping_abs())))
+}
+
+fn abs_long(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Long(args[0].as_long()?.wrapping_abs())))
+}
+
+fn abs_float(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Float(args[0].as_float()?.abs())))
+}
+
+fn abs_double(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Double(args[0].as_double()?.abs())))
+}
+
+fn max_int(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Int(args[0].as_int()?.max(args[1].as_int()?))))
+}
+
+fn max_long(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Long(
+        args[0].as_long()?.max(args[1].as_long()?),
+    )))
+}
+
+fn min_int(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Int(args[0].as_int()?.min(args[1].as_int()?))))
+}
+
+fn min_long(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Long(
+        args[0].as_long()?.min(args[1].as_long()?),
+    )))
+}
+
+/// Java's max and min return NaN if either argument is NaN and order -0.0 below 0.0,
+/// where Rust's ignore NaN
+fn java_max(a: f64, b: f64) -> f64 {
+    match (a.is_nan() || b.is_nan(), a == b) {
+        (true, _) => f64::NAN,
+        (false, true) if a.is_sign_negative() => b,
+        (false, true) => a,
+        (false, false) => a.max(b),
+    }
+}
+
+fn java_min(a: f64, b: f64) -> f64 {
+    match (a.is_nan() || b.is_nan(), a == b) {
+        (true, _) => f64::NAN,
+        (false, true) if a.is_sign_negative() => a,
+        (false, true) => b,
+        (false, false) => a.min(b),
+    }
+}
+
+fn max_float(_: &mut Vm, args: &[Value]) -> NativeResult {
+    let max = java_max(args[0].as_float()? as f64, args[1].as_float()? as f64);
+    Ok(Some(Value::Float(max as f32)))
+}
+
+fn max_double(_: &mut Vm, args: &[Value]) -> NativeResult {
+    let max = java_max(args[0].as_double()?, args[1].as_double()?);
+    Ok(Some(Value::Double(max)))
+}
+
+fn min_float(_: &mut Vm, args: &[Value]) -> NativeResult {
+    let min = java_min(args[0].as_float()? as f64, args[1].as_float()? as f64);
+    Ok(Some(Value::Float(min as f32)))
+}
+
+fn min_double(_: &mut Vm, args: &[Value]) -> NativeResult {
+    let min = java_min(args[0].as_double()?, args[1].as_double()?);
+    Ok(Some(Value::Double(min)))
+}
+
+fn divisor(value: Value) -> Result<i32, VmError> {
+    match value.as_int()? {
+        0 => Err(VmError::exception(
+            "java/lang/ArithmeticException",
+            Some("/ by zero".to_string()),
+        )),
+        divisor => Ok(divisor),
+    }
+}
+
+fn floor_div(_: &mut Vm, args: &[Value]) -> NativeResult {
+    let (x, y) = (args[0].as_int()?, divisor(args[1])?);
+    let quotient = x.wrapping_div(y);
+    // round towards negative infinity when the signs differ and the division is inexact
+    let adjust = (x % y != 0 && (x < 0) != (y < 0)) as i32;
+    Ok(Some(Value::Int(quotient - adjust)))
+}
+
+fn floor_mod(_: &mut Vm, args: &[Value]) -> NativeResult {
+    let (x, y) = (args[0].as_int()?, divisor(args[1])?);
+    let remainder = x.wrapping_rem(y);
+    let adjust = if remainder != 0 && (remainder < 0) != (y < 0) {
+        y
+    } else {
+        0
+    };
+    Ok(Some(Value::Int(remainder + adjust)))
+}
+
+fn double_fn(args: &[Value], f: fn(f64) -> f64) -> NativeResult {
+    Ok(Some(Value::Double(f(args[0].as_double()?))))
+}
+
+fn sqrt(_: &mut Vm, args: &[Value]) -> NativeResult {
+    double_fn(args, f64::sqrt)
+}
+
+fn cbrt(_: &mut Vm, args: &[Value]) -> NativeResult {
+    double_fn(args, f64::cbrt)
+}
+
+fn exp(_: &mut Vm, args: &[Value]) -> NativeResult {
+    double_fn(args, f64::exp)
+}
+
+fn log(_: &mut Vm, args: &[Value]) -> NativeResult {
+    double_fn(args, f64::ln)
+}
+
+fn log10(_: &mut Vm, args: &[Value]) -> NativeResult {
+    double_fn(args, f64::log10)
+}
+
+fn floor(_: &mut Vm, args: &[Value]) -> NativeResult {
+    double_fn(args, f64::floor)
+}
+
+fn ceil(_: &mut Vm, args: &[Value]) -> NativeResult {
+    double_fn(args, f64::ceil)
+}
+
+fn sin(_: &mut Vm, args: &[Value]) -> NativeResult {
+    double_fn(args, f64::sin)
+}
+
+fn cos(_: &mut Vm, args: &[Value]) -> NativeResult {
+    double_fn(args, f64::cos)
+}
+
+fn tan(_: &mut Vm, args: &[Value]) -> NativeResult {
+    double_fn(args, f64::tan)
+}
+
+fn pow(_: &mut Vm, args: &[Value]) -> NativeResult {
+    let pow = args[0].as_double()?.powf(args[1].as_double()?);
+    Ok(Some(Value::Double(pow)))
+}
+
+fn atan2(_: &mut Vm, args: &[Value]) -> NativeResult {
+    let atan2 = args[0].as_double()?.atan2(args[1].as_double()?);
+    Ok(Some(Value::Double(atan2)))
+}
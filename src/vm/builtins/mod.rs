@@ -0,0 +1,220 @@
+//! Rust implementations of the few JDK classes simple programs need, so they run
+//! without a JDK installed. Each class is added to the virtual machine as a class
+//! file whose methods are all native.
+
+mod function;
+mod integer;
+mod math;
+mod object;
+mod objects;
+mod printstream;
+mod string;
+mod stringbuilder;
+mod system;
+mod thread;
+mod throwable;
+
+use super::{error::VmError, native::NativeMethod, value::Value, vm::Vm};
+use crate::parsers::{
+    class::JavaClassFile, descriptor::FieldType, field::Field, method::Method, version::MAGIC,
+};
+
+pub(crate) use thread::new_thread;
+pub(crate) use throwable::print_stack_trace;
+
+type NativeResult = Result<Option<Value>, VmError>;
+
+/// A JDK class implemented in Rust
+struct Builtin {
+    name: &'static str,
+    super_class: &'static str,
+    /// (name, descriptor) of the static fields
+    static_fields: &'static [(&'static str, &'static str)],
+    /// (name, descriptor, implementation) of the static methods
+    static_methods: &'static [(&'static str, &'static str, NativeMethod)],
+    /// (name, descriptor, implementation) of the instance methods
+    methods: &'static [(&'static str, &'static str, NativeMethod)],
+}
+
+/// In superclass first order
+const BUILTINS: [&Builtin; 9] = [
+    &object::OBJECT,
+    &throwable::THROWABLE,
+    &string::STRING,
+    &stringbuilder::STRING_BUILDER,
+    &integer::INTEGER,
+    &math::MATH,
+    &objects::OBJECTS,
+    &printstream::PRINT_STREAM,
+    &system::SYSTEM,
+];
+
+impl Builtin {
+    fn class_file(&self) -> JavaClassFile {
+        let flags = |flags: &[&str]| flags.iter().map(|flag| flag.to_string()).collect();
+        let fields = self
+            .static_fields
+            .iter()
+            .map(|(name, descriptor)| Field {
+                access_flags: flags(&["ACC_PUBLIC", "ACC_STATIC", "ACC_FINAL"]),
+                name: name.to_string(),
+                descriptor: descriptor.to_string(),
+                attrs: vec![],
+                ..Default::default()
+            })
+            .collect();
+        let static_methods = self.static_methods.iter().map(|method| (method, true));
+        let methods = self.methods.iter().map(|method| (method, false));
+        let methods = static_methods
+            .chain(methods)
+            .map(|((name, descriptor, _), is_static)| Method {
+                access_flags: match is_static {
+                    true => flags(&["ACC_PUBLIC", "ACC_STATIC", "ACC_NATIVE"]),
+                    false => flags(&["ACC_PUBLIC", "ACC_NATIVE"]),
+                },
+                name: name.to_string(),
+                descriptor: descriptor.to_string(),
+                attrs: vec![],
+                ..Default::default()
+            })
+            .collect();
+        JavaClassFile {
+            magic: MAGIC,
+            access_flags: flags(&["ACC_PUBLIC", "ACC_SUPER"]),
+            this_class: self.name.to_string(),
+            super_class: self.super_class.to_string(),
+            fields,
+            methods,
+            ..Default::default()
+        }
+    }
+}
+
+pub(crate) fn register(vm: &mut Vm) {
+    for builtin in BUILTINS {
+        add(vm, builtin, builtin.class_file());
+    }
+    for (name, super_class) in throwable::THROWABLES {
+        let builtin = Builtin {
+            name,
+            super_class,
+            static_fields: &[],
+            static_methods: &[],
+            methods: &[],
+        };
+        vm.add_class(builtin.class_file())
+            .expect("built-in classes are only added once");
+    }
+    for interface in function::INTERFACES {
+        vm.add_class(interface.class_file())
+            .expect("built-in classes are only added once");
+    }
+    // Thread implements Runnable, which is only added with the interfaces
+    let mut thread = thread::THREAD.class_file();
+    thread.interfaces.push("java/lang/Runnable".to_string());
+    add(vm, &thread::THREAD, thread);
+    system::init(vm);
+}
+
+/// Registers the natives of `builtin` and adds its class file
+fn add(vm: &mut Vm, builtin: &Builtin, file: JavaClassFile) {
+    for (name, descriptor, method) in builtin.static_methods.iter().chain(builtin.methods) {
+        vm.register_native(builtin.name, name, descriptor, *method);
+    }
+    vm.add_class(file)
+        .expect("built-in classes are only added once");
+}
+
+/// `String.valueOf` for a value of type `field_type`, as UTF-16
+pub(crate) fn value_of(
+    vm: &mut Vm,
+    value: Value,
+    field_type: &FieldType,
+) -> Result<Vec<u16>, VmError> {
+    let string = match field_type {
+        FieldType::Boolean => boolean_to_string(value)?,
+        FieldType::Char => char_to_string(value)?,
+        _ => return display_units(vm, value),
+    };
+    Ok(string.encode_utf16().collect())
+}
+
+/// `String.valueOf` for values whose type is not boolean or char
+fn display(vm: &mut Vm, value: Value) -> Result<String, VmError> {
+    match value {
+        Value::Int(value) => Ok(value.to_string()),
+        Value::Long(value) => Ok(value.to_string()),
+        Value::Float(value) => Ok(float_to_string(value)),
+        Value::Double(value) => Ok(double_to_string(value)),
+        Value::Null | Value::Ref(_) => vm.string_of(value),
+        Value::Top => Err(value.mismatch("value")),
+    }
+}
+
+/// Like `display` but as UTF-16, keeping the code units of strings as they are
+fn display_units(vm: &mut Vm, value: Value) -> Result<Vec<u16>, VmError> {
+    if let Ok(chars) = vm.chars(value) {
+        return Ok(chars.to_vec());
+    }
+    Ok(display(vm, value)?.encode_utf16().collect())
+}
+
+fn char_to_string(value: Value) -> Result<String, VmError> {
+    Ok(String::from_utf16_lossy(&[value.as_int()? as u16]))
+}
+
+fn boolean_to_string(value: Value) -> Result<String, VmError> {
+    Ok((value.as_int()? != 0).to_string())
+}
+
+/// `Float.toString`, which unlike Rust always has a fractional part and switches to
+/// scientific notation outside of [10^-3, 10^7)
+fn float_to_string(value: f32) -> String {
+    java_decimal(
+        value.is_nan(),
+        value.is_infinite(),
+        value.abs() as f64,
+        format!("{value}"),
+        format!("{value:e}"),
+    )
+}
+
+/// `Double.toString`, see `float_to_string`
+fn double_to_string(value: f64) -> String {
+    java_decimal(
+        value.is_nan(),
+        value.is_infinite(),
+        value.abs(),
+        format!("{value}"),
+        format!("{value:e}"),
+    )
+}
+
+fn java_decimal(nan: bool, infinite: bool, abs: f64, plain: String, scientific: String) -> String {
+    if nan {
+        return "NaN".to_string();
+    }
+    if infinite {
+        return match plain.starts_with('-') {
+            true => "-Infinity".to_string(),
+            false => "Infinity".to_string(),
+        };
+    }
+    if abs == 0.0 || (1e-3..1e7).contains(&abs) {
+        return match plain.contains('.') {
+            true => plain,
+            false => format!("{plain}.0"),
+        };
+    }
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("scientific notation has an exponent");
+    match mantissa.contains('.') {
+        true => format!("{mantissa}E{exponent}"),
+        false => format!("{mantissa}.0E{exponent}"),
+    }
+}
+
+fn boolean(value: bool) -> NativeResult {
+    Ok(Some(Value::Int(value as i32)))
+}
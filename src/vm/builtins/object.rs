@@ -0,0 +1,104 @@
+use super::{boolean, Builtin, NativeResult};
+use crate::vm::{error::VmError, heap::ObjectData, value::Value, vm::Vm};
+
+pub(super) const OBJECT: Builtin = Builtin {
+    name: "java/lang/Object",
+    super_class: "",
+    static_fields: &[],
+    static_methods: &[],
+    methods: &[
+        ("<init>", "()V", init),
+        ("hashCode", "()I", hash_code),
+        ("equals", "(Ljava/lang/Object;)Z", equals),
+        ("toString", "()Ljava/lang/String;", to_string),
+        ("clone", "()Ljava/lang/Object;", clone),
+        ("wait", "()V", wait),
+        ("wait", "(J)V", wait),
+        ("notify", "()V", notify),
+        ("notifyAll", "()V", notify_all),
+    ],
+};
+
+fn init(_: &mut Vm, _: &[Value]) -> NativeResult {
+    Ok(None)
+}
+
+/// Objects are never moved, so their heap index doubles as identity hash code
+pub(super) fn identity_hash_code(value: Value) -> i32 {
+    match value {
+        Value::Ref(obj) => obj.index() as i32,
+        _ => 0,
+    }
+}
+
+fn hash_code(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Int(identity_hash_code(args[0]))))
+}
+
+fn equals(_: &mut Vm, args: &[Value]) -> NativeResult {
+    boolean(args[0] == args[1])
+}
+
+/// A shallow copy of an array or of an instance of a class implementing Cloneable
+fn clone(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let object = vm.object(args[0])?;
+    let class = vm.class(object.class);
+    if !matches!(object.data, ObjectData::Array(_)) && !implements_cloneable(vm, class.id) {
+        return Err(VmError::exception(
+            "java/lang/CloneNotSupportedException",
+            Some(class.name.replace('/', ".")),
+        ));
+    }
+    let copy = object.clone();
+    let obj = vm.heap.alloc(copy);
+    Ok(Some(Value::Ref(obj)))
+}
+
+fn implements_cloneable(vm: &Vm, class: usize) -> bool {
+    let mut current = Some(class);
+    while let Some(id) = current {
+        let class = vm.class(id);
+        if class
+            .file
+            .interfaces
+            .iter()
+            .any(|name| name == "java/lang/Cloneable")
+        {
+            return true;
+        }
+        current = class.super_class;
+    }
+    false
+}
+
+/// `wait()` and `wait(long timeout)`, the thread waits once the native returned
+fn wait(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let timeout = match args.get(1) {
+        Some(timeout) => timeout.as_long()?,
+        None => 0,
+    };
+    vm.wait(args[0], timeout)?;
+    Ok(None)
+}
+
+fn notify(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    vm.notify(args[0], false)?;
+    Ok(None)
+}
+
+fn notify_all(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    vm.notify(args[0], true)?;
+    Ok(None)
+}
+
+/// `getClass().getName() + "@" + Integer.toHexString(hashCode())`
+fn to_string(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let class = vm.object(args[0])?.class;
+    let name = vm.class(class).name.replace('/', ".");
+    let hash = vm
+        .invoke_virtual("hashCode", "()I", vec![args[0]])?
+        .unwrap_or(Value::Int(0))
+        .as_int()?;
+    let string = format!("{name}@{:x}", hash as u32);
+    Ok(Some(vm.new_string(&string)?))
+}
@@ -0,0 +1,53 @@
+use super::{boolean, Builtin, NativeResult};
+use crate::vm::{error::VmError, value::Value, vm::Vm};
+
+/// javac checks the receiver of bound method references like `obj::method` with
+/// `requireNonNull`
+pub(super) const OBJECTS: Builtin = Builtin {
+    name: "java/util/Objects",
+    super_class: "java/lang/Object",
+    static_fields: &[],
+    static_methods: &[
+        (
+            "requireNonNull",
+            "(Ljava/lang/Object;)Ljava/lang/Object;",
+            require_non_null,
+        ),
+        (
+            "requireNonNull",
+            "(Ljava/lang/Object;Ljava/lang/String;)Ljava/lang/Object;",
+            require_non_null_message,
+        ),
+        ("isNull", "(Ljava/lang/Object;)Z", is_null),
+        ("nonNull", "(Ljava/lang/Object;)Z", non_null),
+    ],
+    methods: &[],
+};
+
+fn require_non_null(_: &mut Vm, args: &[Value]) -> NativeResult {
+    match args[0] {
+        Value::Null => Err(VmError::null_pointer()),
+        value => Ok(Some(value)),
+    }
+}
+
+fn require_non_null_message(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    match args[0] {
+        Value::Null => Err(VmError::exception(
+            "java/lang/NullPointerException",
+            match args[1] {
+                Value::Null => None,
+                message => Some(vm.java_string(message)?),
+            },
+        )),
+        value => Ok(Some(value)),
+    }
+}
+
+fn is_null(_: &mut Vm, args: &[Value]) -> NativeResult {
+    boolean(args[0] == Value::Null)
+}
+
+fn non_null(_: &mut Vm, args: &[Value]) -> NativeResult {
+    boolean(args[0] != Value::Null)
+}
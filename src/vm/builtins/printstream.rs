@@ -0,0 +1,92 @@
+use std::io::Write;
+
+use super::{boolean_to_string, char_to_string, display, Builtin, NativeResult};
+use crate::vm::{
+    error::VmError,
+    heap::{ObjectData, Stream},
+    value::Value,
+    vm::Vm,
+};
+
+pub(super) const PRINT_STREAM: Builtin = Builtin {
+    name: "java/io/PrintStream",
+    super_class: "java/lang/Object",
+    static_fields: &[],
+    static_methods: &[],
+    methods: &[
+        ("print", "(Ljava/lang/String;)V", print),
+        ("print", "(Ljava/lang/Object;)V", print),
+        ("print", "(I)V", print),
+        ("print", "(J)V", print),
+        ("print", "(F)V", print),
+        ("print", "(D)V", print),
+        ("print", "(Z)V", print_boolean),
+        ("print", "(C)V", print_char),
+        ("println", "()V", println_empty),
+        ("println", "(Ljava/lang/String;)V", println),
+        ("println", "(Ljava/lang/Object;)V", println),
+        ("println", "(I)V", println),
+        ("println", "(J)V", println),
+        ("println", "(F)V", println),
+        ("println", "(D)V", println),
+        ("println", "(Z)V", println_boolean),
+        ("println", "(C)V", println_char),
+        ("flush", "()V", flush),
+    ],
+};
+
+/// Writes `text` to the stream behind `this`. Like java.io.PrintStream, write errors
+/// are not reported to the caller.
+fn write(vm: &mut Vm, this: Value, text: &str) -> NativeResult {
+    let stream = match vm.object(this)?.data {
+        ObjectData::PrintStream(stream) => stream,
+        _ => {
+            return Err(VmError::TypeMismatch {
+                expected: "java/io/PrintStream",
+                found: "reference",
+            })
+        }
+    };
+    let out = match stream {
+        Stream::Out => &mut vm.stdout,
+        Stream::Err => &mut vm.stderr,
+    };
+    let _ = out.write_all(text.as_bytes());
+    Ok(None)
+}
+
+fn print(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let text = display(vm, args[1])?;
+    write(vm, args[0], &text)
+}
+
+fn print_boolean(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    write(vm, args[0], &boolean_to_string(args[1])?)
+}
+
+fn print_char(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    write(vm, args[0], &char_to_string(args[1])?)
+}
+
+fn println_empty(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    write(vm, args[0], "\n")
+}
+
+fn println(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let text = display(vm, args[1])?;
+    write(vm, args[0], &format!("{text}\n"))
+}
+
+fn println_boolean(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    write(vm, args[0], &format!("{}\n", boolean_to_string(args[1])?))
+}
+
+fn println_char(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    write(vm, args[0], &format!("{}\n", char_to_string(args[1])?))
+}
+
+fn flush(vm: &mut Vm, _: &[Value]) -> NativeResult {
+    let _ = vm.stdout.flush();
+    let _ = vm.stderr.flush();
+    Ok(None)
+}
@@ -0,0 +1,143 @@
+use super::{boolean, boolean_to_string, display_units, Builtin, NativeResult};
+use crate::vm::{
+    error::VmError,
+    heap::{Array, ObjectData},
+    value::Value,
+    vm::Vm,
+};
+
+pub(super) const STRING: Builtin = Builtin {
+    name: "java/lang/String",
+    super_class: "java/lang/Object",
+    static_fields: &[],
+    static_methods: &[
+        ("valueOf", "(I)Ljava/lang/String;", value_of),
+        ("valueOf", "(J)Ljava/lang/String;", value_of),
+        ("valueOf", "(F)Ljava/lang/String;", value_of),
+        ("valueOf", "(D)Ljava/lang/String;", value_of),
+        ("valueOf", "(Z)Ljava/lang/String;", value_of_boolean),
+        ("valueOf", "(C)Ljava/lang/String;", value_of_char),
+        (
+            "valueOf",
+            "(Ljava/lang/Object;)Ljava/lang/String;",
+            value_of,
+        ),
+    ],
+    methods: &[
+        ("<init>", "()V", init),
+        ("<init>", "(Ljava/lang/String;)V", init_with),
+        ("<init>", "([C)V", init_with_chars),
+        ("length", "()I", length),
+        ("isEmpty", "()Z", is_empty),
+        ("charAt", "(I)C", char_at),
+        ("equals", "(Ljava/lang/Object;)Z", equals),
+        ("hashCode", "()I", hash_code),
+        ("concat", "(Ljava/lang/String;)Ljava/lang/String;", concat),
+        ("intern", "()Ljava/lang/String;", intern),
+        ("toString", "()Ljava/lang/String;", to_string),
+    ],
+};
+
+fn value_of(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let units = display_units(vm, args[0])?;
+    new_string(vm, units)
+}
+
+fn value_of_boolean(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let string = boolean_to_string(args[0])?;
+    Ok(Some(vm.new_string(&string)?))
+}
+
+fn value_of_char(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    new_string(vm, vec![args[0].as_int()? as u16])
+}
+
+fn new_string(vm: &mut Vm, units: Vec<u16>) -> NativeResult {
+    Ok(Some(vm.new_object(
+        "java/lang/String",
+        ObjectData::Chars(units),
+    )?))
+}
+
+fn init(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    vm.object_mut(args[0])?.data = ObjectData::Chars(vec![]);
+    Ok(None)
+}
+
+/// `new String(original)`, a copy that is not identical to the original
+fn init_with(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let chars = vm.chars(args[1])?.to_vec();
+    vm.object_mut(args[0])?.data = ObjectData::Chars(chars);
+    Ok(None)
+}
+
+fn init_with_chars(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let chars = match vm.array(args[1])? {
+        Array::Char(chars) => chars.clone(),
+        _ => {
+            return Err(VmError::TypeMismatch {
+                expected: "char[]",
+                found: "array",
+            })
+        }
+    };
+    vm.object_mut(args[0])?.data = ObjectData::Chars(chars);
+    Ok(None)
+}
+
+fn length(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Int(vm.chars(args[0])?.len() as i32)))
+}
+
+fn is_empty(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    boolean(vm.chars(args[0])?.is_empty())
+}
+
+fn char_at(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let chars = vm.chars(args[0])?;
+    let index = args[1].as_int()?;
+    match chars.get(index as usize) {
+        Some(c) if index >= 0 => Ok(Some(Value::Int(*c as i32))),
+        _ => Err(VmError::exception(
+            "java/lang/StringIndexOutOfBoundsException",
+            Some(format!("index {index}, length {}", chars.len())),
+        )),
+    }
+}
+
+fn equals(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    if args[0] == args[1] {
+        return boolean(true);
+    }
+    let is_string = match args[1] {
+        Value::Ref(obj) => vm.class_of(obj) == "java/lang/String",
+        _ => false,
+    };
+    boolean(is_string && vm.chars(args[0])? == vm.chars(args[1])?)
+}
+
+/// `s[0]*31^(n-1) + s[1]*31^(n-2) + ... + s[n-1]` over the UTF-16 code units
+fn hash_code(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let hash = vm.chars(args[0])?.iter().fold(0i32, |hash, c| {
+        hash.wrapping_mul(31).wrapping_add(*c as i32)
+    });
+    Ok(Some(Value::Int(hash)))
+}
+
+/// Returns the string itself when `str` is empty, like the JDK
+fn concat(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    if vm.chars(args[1])?.is_empty() {
+        return Ok(Some(args[0]));
+    }
+    let mut units = vm.chars(args[0])?.to_vec();
+    units.extend_from_slice(vm.chars(args[1])?);
+    new_string(vm, units)
+}
+
+fn intern(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(vm.intern(args[0])?))
+}
+
+fn to_string(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(args[0]))
+}
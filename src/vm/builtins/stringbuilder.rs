@@ -0,0 +1,104 @@
+use super::{boolean_to_string, display_units, Builtin, NativeResult};
+use crate::vm::{error::VmError, heap::ObjectData, value::Value, vm::Vm};
+
+pub(super) const STRING_BUILDER: Builtin = Builtin {
+    name: "java/lang/StringBuilder",
+    super_class: "java/lang/Object",
+    static_fields: &[],
+    static_methods: &[],
+    methods: &[
+        ("<init>", "()V", init),
+        ("<init>", "(I)V", init),
+        ("<init>", "(Ljava/lang/String;)V", init_with),
+        (
+            "append",
+            "(Ljava/lang/String;)Ljava/lang/StringBuilder;",
+            append,
+        ),
+        (
+            "append",
+            "(Ljava/lang/Object;)Ljava/lang/StringBuilder;",
+            append,
+        ),
+        (
+            "append",
+            "(Ljava/lang/CharSequence;)Ljava/lang/StringBuilder;",
+            append,
+        ),
+        ("append", "(I)Ljava/lang/StringBuilder;", append),
+        ("append", "(J)Ljava/lang/StringBuilder;", append),
+        ("append", "(F)Ljava/lang/StringBuilder;", append),
+        ("append", "(D)Ljava/lang/StringBuilder;", append),
+        ("append", "(Z)Ljava/lang/StringBuilder;", append_boolean),
+        ("append", "(C)Ljava/lang/StringBuilder;", append_char),
+        ("length", "()I", length),
+        ("reverse", "()Ljava/lang/StringBuilder;", reverse),
+        ("toString", "()Ljava/lang/String;", to_string),
+    ],
+};
+
+fn chars_mut(vm: &mut Vm, this: Value) -> Result<&mut Vec<u16>, VmError> {
+    match &mut vm.object_mut(this)?.data {
+        ObjectData::Chars(chars) => Ok(chars),
+        _ => Err(VmError::TypeMismatch {
+            expected: "java/lang/StringBuilder",
+            found: "reference",
+        }),
+    }
+}
+
+fn init(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    vm.object_mut(args[0])?.data = ObjectData::Chars(vec![]);
+    Ok(None)
+}
+
+fn init_with(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let chars = vm.chars(args[1])?.to_vec();
+    vm.object_mut(args[0])?.data = ObjectData::Chars(chars);
+    Ok(None)
+}
+
+fn append(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let units = display_units(vm, args[1])?;
+    chars_mut(vm, args[0])?.extend(units);
+    Ok(Some(args[0]))
+}
+
+fn append_boolean(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let string = boolean_to_string(args[1])?;
+    chars_mut(vm, args[0])?.extend(string.encode_utf16());
+    Ok(Some(args[0]))
+}
+
+fn append_char(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let c = args[1].as_int()? as u16;
+    chars_mut(vm, args[0])?.push(c);
+    Ok(Some(args[0]))
+}
+
+fn length(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Int(vm.chars(args[0])?.len() as i32)))
+}
+
+/// Reverses by code point, so surrogate pairs stay in order
+fn reverse(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let chars = chars_mut(vm, args[0])?;
+    chars.reverse();
+    let mut i = 0;
+    while i + 1 < chars.len() {
+        if (0xDC00..0xE000).contains(&chars[i]) && (0xD800..0xDC00).contains(&chars[i + 1]) {
+            chars.swap(i, i + 1);
+            i += 1;
+        }
+        i += 1;
+    }
+    Ok(Some(args[0]))
+}
+
+fn to_string(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let chars = vm.chars(args[0])?.to_vec();
+    Ok(Some(vm.new_object(
+        "java/lang/String",
+        ObjectData::Chars(chars),
+    )?))
+}
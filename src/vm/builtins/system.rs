@@ -0,0 +1,113 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{object::identity_hash_code, Builtin, NativeResult};
+use crate::vm::{
+    error::VmError,
+    heap::{ObjectData, Stream},
+    value::Value,
+    vm::Vm,
+};
+
+pub(super) const SYSTEM: Builtin = Builtin {
+    name: "java/lang/System",
+    super_class: "java/lang/Object",
+    static_fields: &[
+        ("out", "Ljava/io/PrintStream;"),
+        ("err", "Ljava/io/PrintStream;"),
+    ],
+    static_methods: &[
+        ("currentTimeMillis", "()J", current_time_millis),
+        ("nanoTime", "()J", nano_time),
+        ("identityHashCode", "(Ljava/lang/Object;)I", identity_hash),
+        ("lineSeparator", "()Ljava/lang/String;", line_separator),
+        (
+            "arraycopy",
+            "(Ljava/lang/Object;ILjava/lang/Object;II)V",
+            arraycopy,
+        ),
+    ],
+    methods: &[],
+};
+
+/// Creates System.out and System.err
+pub(super) fn init(vm: &mut Vm) {
+    for (name, stream) in [("out", Stream::Out), ("err", Stream::Err)] {
+        let print_stream = vm
+            .new_object("java/io/PrintStream", ObjectData::PrintStream(stream))
+            .expect("java/io/PrintStream is built in");
+        let system = vm
+            .class_id("java/lang/System")
+            .expect("java/lang/System is built in");
+        vm.classes[system]
+            .statics
+            .insert(name.to_string(), print_stream);
+    }
+}
+
+fn since_epoch() -> std::time::Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+fn current_time_millis(_: &mut Vm, _: &[Value]) -> NativeResult {
+    Ok(Some(Value::Long(since_epoch().as_millis() as i64)))
+}
+
+fn nano_time(_: &mut Vm, _: &[Value]) -> NativeResult {
+    Ok(Some(Value::Long(since_epoch().as_nanos() as i64)))
+}
+
+fn identity_hash(_: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(Value::Int(identity_hash_code(args[0]))))
+}
+
+fn line_separator(vm: &mut Vm, _: &[Value]) -> NativeResult {
+    Ok(Some(vm.new_string("\n")?))
+}
+
+/// `arraycopy(src, srcPos, dest, destPos, length)`, the source range is read before
+/// anything is written so `src` and `dest` may overlap
+fn arraycopy(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let (src, dest) = (args[0], args[2]);
+    let (src_pos, dest_pos, length) = (args[1].as_int()?, args[3].as_int()?, args[4].as_int()?);
+    let (source, target) = (vm.array(src)?, vm.array(dest)?);
+    if std::mem::discriminant(source) != std::mem::discriminant(target) {
+        return Err(VmError::exception(
+            "java/lang/ArrayStoreException",
+            Some("arraycopy: type mismatch".to_string()),
+        ));
+    }
+    let out_of_bounds = |which: &str, pos: i32, len: usize| {
+        let last = pos as i64 + length as i64;
+        (last > len as i64).then(|| {
+            VmError::exception(
+                "java/lang/ArrayIndexOutOfBoundsException",
+                Some(format!(
+                    "arraycopy: last {which} index {last} out of bounds for length {len}"
+                )),
+            )
+        })
+    };
+    if src_pos < 0 || dest_pos < 0 || length < 0 {
+        return Err(VmError::exception(
+            "java/lang/ArrayIndexOutOfBoundsException",
+            Some(format!(
+                "arraycopy: source index {src_pos}, destination index {dest_pos} or length {length} is negative"
+            )),
+        ));
+    }
+    if let Some(err) = out_of_bounds("source", src_pos, source.len())
+        .or_else(|| out_of_bounds("destination", dest_pos, target.len()))
+    {
+        return Err(err);
+    }
+    let elements: Vec<Value> = (0..length as usize)
+        .filter_map(|offset| source.get(src_pos as usize + offset))
+        .collect();
+    let target = vm.array_mut(dest)?;
+    for (offset, element) in elements.into_iter().enumerate() {
+        target.set(dest_pos as usize + offset, element)?;
+    }
+    Ok(None)
+}
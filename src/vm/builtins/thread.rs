@@ -0,0 +1,163 @@
+use super::{boolean, Builtin, NativeResult};
+use crate::vm::{
+    error::VmError,
+    heap::{ObjectData, ThreadData},
+    thread::ThreadId,
+    value::Value,
+    vm::Vm,
+};
+
+pub(super) const THREAD: Builtin = Builtin {
+    name: "java/lang/Thread",
+    super_class: "java/lang/Object",
+    static_fields: &[],
+    static_methods: &[
+        ("currentThread", "()Ljava/lang/Thread;", current_thread),
+        ("sleep", "(J)V", sleep),
+        ("yield", "()V", yield_thread),
+    ],
+    methods: &[
+        ("<init>", "()V", init),
+        ("<init>", "(Ljava/lang/Runnable;)V", init),
+        ("<init>", "(Ljava/lang/String;)V", init_with_name),
+        (
+            "<init>",
+            "(Ljava/lang/Runnable;Ljava/lang/String;)V",
+            init_with_name,
+        ),
+        ("start", "()V", start),
+        ("run", "()V", run),
+        ("join", "()V", join),
+        ("join", "(J)V", join),
+        ("isAlive", "()Z", is_alive),
+        ("getName", "()Ljava/lang/String;", get_name),
+        ("setName", "(Ljava/lang/String;)V", set_name),
+        ("toString", "()Ljava/lang/String;", to_string),
+    ],
+};
+
+/// A new java/lang/Thread named `name`, `thread` is its id if it's running already
+pub(crate) fn new_thread(
+    vm: &mut Vm,
+    name: String,
+    thread: Option<ThreadId>,
+) -> Result<Value, VmError> {
+    let data = ThreadData {
+        name,
+        target: Value::Null,
+        thread,
+    };
+    vm.new_object("java/lang/Thread", ObjectData::Thread(data))
+}
+
+fn state(vm: &mut Vm, this: Value) -> Result<&mut ThreadData, VmError> {
+    match &mut vm.object_mut(this)?.data {
+        ObjectData::Thread(thread) => Ok(thread),
+        _ => Err(VmError::TypeMismatch {
+            expected: "java/lang/Thread",
+            found: "reference",
+        }),
+    }
+}
+
+/// `Thread()` and `Thread(Runnable target)`, named `Thread-N`
+fn init(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let name = vm.threads.next_name();
+    let target = args.get(1).copied().unwrap_or(Value::Null);
+    init_thread(vm, args[0], target, name)
+}
+
+/// `Thread(String name)` and `Thread(Runnable target, String name)`
+fn init_with_name(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let (target, name) = match args {
+        [_, name] => (Value::Null, *name),
+        _ => (args[1], args[2]),
+    };
+    let name = thread_name(vm, name)?;
+    init_thread(vm, args[0], target, name)
+}
+
+fn init_thread(vm: &mut Vm, this: Value, target: Value, name: String) -> NativeResult {
+    vm.object_mut(this)?.data = ObjectData::Thread(ThreadData {
+        name,
+        target,
+        thread: None,
+    });
+    Ok(None)
+}
+
+fn thread_name(vm: &Vm, name: Value) -> Result<String, VmError> {
+    match name {
+        Value::Null => Err(VmError::exception(
+            "java/lang/NullPointerException",
+            Some("'name' is null".to_string()),
+        )),
+        name => vm.java_string(name),
+    }
+}
+
+fn current_thread(vm: &mut Vm, _: &[Value]) -> NativeResult {
+    Ok(Some(vm.current_thread()?))
+}
+
+/// The thread sleeps once the native returned
+fn sleep(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    vm.sleep(args[0].as_long()?)?;
+    Ok(None)
+}
+
+fn yield_thread(vm: &mut Vm, _: &[Value]) -> NativeResult {
+    vm.yield_thread();
+    Ok(None)
+}
+
+fn start(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    vm.start_thread(args[0])?;
+    Ok(None)
+}
+
+/// Runs the target, when called directly rather than by a started thread
+fn run(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let target = state(vm, args[0])?.target;
+    if target != Value::Null {
+        vm.invoke_virtual("run", "()V", vec![target])?;
+    }
+    Ok(None)
+}
+
+/// `join()` and `join(long millis)`, the thread waits once the native returned
+fn join(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let timeout = match args.get(1) {
+        Some(timeout) => timeout.as_long()?,
+        None => 0,
+    };
+    vm.join(args[0], timeout)?;
+    Ok(None)
+}
+
+fn is_alive(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    boolean(vm.is_alive(args[0])?)
+}
+
+fn get_name(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let name = state(vm, args[0])?.name.clone();
+    Ok(Some(vm.new_string(&name)?))
+}
+
+fn set_name(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let name = thread_name(vm, args[1])?;
+    state(vm, args[0])?.name = name;
+    Ok(None)
+}
+
+/// `Thread[name,priority,group]`, terminated threads have no group
+fn to_string(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let name = state(vm, args[0])?.name.clone();
+    let started = vm.thread_id(args[0])?.is_some();
+    let group = match started && !vm.is_alive(args[0])? {
+        true => "",
+        false => "main",
+    };
+    let string = format!("Thread[{name},5,{group}]");
+    Ok(Some(vm.new_string(&string)?))
+}
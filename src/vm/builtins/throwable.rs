@@ -0,0 +1,283 @@
+use std::io::Write;
+
+use super::{Builtin, NativeResult};
+use crate::vm::{
+    error::VmError,
+    heap::{ObjectData, StackTraceElement, Throwable},
+    value::Value,
+    vm::Vm,
+};
+
+pub(super) const THROWABLE: Builtin = Builtin {
+    name: "java/lang/Throwable",
+    super_class: "java/lang/Object",
+    static_fields: &[],
+    static_methods: &[],
+    methods: &[
+        ("<init>", "()V", init),
+        ("<init>", "(Ljava/lang/String;)V", init),
+        ("<init>", "(Ljava/lang/String;Ljava/lang/Throwable;)V", init),
+        ("<init>", "(Ljava/lang/Throwable;)V", init_with_cause),
+        ("getMessage", "()Ljava/lang/String;", get_message),
+        (
+            "getLocalizedMessage",
+            "()Ljava/lang/String;",
+            get_localized_message,
+        ),
+        ("getCause", "()Ljava/lang/Throwable;", get_cause),
+        (
+            "initCause",
+            "(Ljava/lang/Throwable;)Ljava/lang/Throwable;",
+            init_cause,
+        ),
+        (
+            "fillInStackTrace",
+            "()Ljava/lang/Throwable;",
+            fill_in_stack_trace,
+        ),
+        ("toString", "()Ljava/lang/String;", to_string),
+        ("printStackTrace", "()V", print_stack_trace_native),
+    ],
+};
+
+/// (name, superclass) of the throwables the virtual machine and the built-in classes
+/// throw, in superclass first order. Their constructors and methods are inherited
+/// from java/lang/Throwable.
+pub(super) const THROWABLES: [(&str, &str); 32] = [
+    ("java/lang/Exception", "java/lang/Throwable"),
+    ("java/lang/Error", "java/lang/Throwable"),
+    ("java/lang/RuntimeException", "java/lang/Exception"),
+    (
+        "java/lang/ArithmeticException",
+        "java/lang/RuntimeException",
+    ),
+    (
+        "java/lang/ArrayStoreException",
+        "java/lang/RuntimeException",
+    ),
+    ("java/lang/ClassCastException", "java/lang/RuntimeException"),
+    (
+        "java/lang/IllegalArgumentException",
+        "java/lang/RuntimeException",
+    ),
+    (
+        "java/lang/NumberFormatException",
+        "java/lang/IllegalArgumentException",
+    ),
+    (
+        "java/lang/IllegalStateException",
+        "java/lang/RuntimeException",
+    ),
+    (
+        "java/lang/IllegalThreadStateException",
+        "java/lang/IllegalArgumentException",
+    ),
+    (
+        "java/lang/IllegalMonitorStateException",
+        "java/lang/RuntimeException",
+    ),
+    (
+        "java/lang/IndexOutOfBoundsException",
+        "java/lang/RuntimeException",
+    ),
+    (
+        "java/lang/ArrayIndexOutOfBoundsException",
+        "java/lang/IndexOutOfBoundsException",
+    ),
+    (
+        "java/lang/StringIndexOutOfBoundsException",
+        "java/lang/IndexOutOfBoundsException",
+    ),
+    (
+        "java/lang/NegativeArraySizeException",
+        "java/lang/RuntimeException",
+    ),
+    (
+        "java/lang/NullPointerException",
+        "java/lang/RuntimeException",
+    ),
+    (
+        "java/lang/UnsupportedOperationException",
+        "java/lang/RuntimeException",
+    ),
+    (
+        "java/lang/CloneNotSupportedException",
+        "java/lang/Exception",
+    ),
+    ("java/lang/InterruptedException", "java/lang/Exception"),
+    ("java/lang/LinkageError", "java/lang/Error"),
+    (
+        "java/lang/IncompatibleClassChangeError",
+        "java/lang/LinkageError",
+    ),
+    (
+        "java/lang/AbstractMethodError",
+        "java/lang/IncompatibleClassChangeError",
+    ),
+    (
+        "java/lang/NoSuchFieldError",
+        "java/lang/IncompatibleClassChangeError",
+    ),
+    (
+        "java/lang/NoSuchMethodError",
+        "java/lang/IncompatibleClassChangeError",
+    ),
+    (
+        "java/lang/InstantiationError",
+        "java/lang/IncompatibleClassChangeError",
+    ),
+    ("java/lang/ClassCircularityError", "java/lang/LinkageError"),
+    (
+        "java/lang/ExceptionInInitializerError",
+        "java/lang/LinkageError",
+    ),
+    ("java/lang/NoClassDefFoundError", "java/lang/LinkageError"),
+    ("java/lang/BootstrapMethodError", "java/lang/LinkageError"),
+    ("java/lang/VirtualMachineError", "java/lang/Error"),
+    (
+        "java/lang/StackOverflowError",
+        "java/lang/VirtualMachineError",
+    ),
+    (
+        "java/lang/OutOfMemoryError",
+        "java/lang/VirtualMachineError",
+    ),
+];
+
+/// The native state of a throwable, created on first use for instances whose
+/// constructor hasn't run yet
+fn state(vm: &mut Vm, this: Value) -> Result<&mut Throwable, VmError> {
+    let object = vm.object_mut(this)?;
+    if let ObjectData::Instance = object.data {
+        object.data = ObjectData::Throwable(Throwable {
+            message: Value::Null,
+            cause: Value::Null,
+            stack_trace: vec![],
+        });
+    }
+    match &mut object.data {
+        ObjectData::Throwable(throwable) => Ok(throwable),
+        _ => Err(VmError::TypeMismatch {
+            expected: "java/lang/Throwable",
+            found: "reference",
+        }),
+    }
+}
+
+/// `Throwable()`, `Throwable(String message)` and `Throwable(String message,
+/// Throwable cause)`
+fn init(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let stack_trace = vm.stack_trace(args[0]);
+    *state(vm, args[0])? = Throwable {
+        message: args.get(1).copied().unwrap_or(Value::Null),
+        cause: args.get(2).copied().unwrap_or(Value::Null),
+        stack_trace,
+    };
+    Ok(None)
+}
+
+/// `Throwable(Throwable cause)`, the message is `cause.toString()`
+fn init_with_cause(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let message = match args[1] {
+        Value::Null => Value::Null,
+        cause => {
+            let message = vm.string_of(cause)?;
+            vm.new_string(&message)?
+        }
+    };
+    init(vm, &[args[0], message, args[1]])
+}
+
+fn get_message(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(state(vm, args[0])?.message))
+}
+
+fn get_localized_message(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    vm.invoke_virtual("getMessage", "()Ljava/lang/String;", vec![args[0]])
+}
+
+fn get_cause(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    Ok(Some(state(vm, args[0])?.cause))
+}
+
+fn init_cause(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    if args[0] == args[1] {
+        return Err(VmError::exception(
+            "java/lang/IllegalArgumentException",
+            Some("Self-causation not permitted".to_string()),
+        ));
+    }
+    let throwable = state(vm, args[0])?;
+    if throwable.cause != Value::Null {
+        return Err(VmError::exception(
+            "java/lang/IllegalStateException",
+            Some("Can't overwrite cause".to_string()),
+        ));
+    }
+    throwable.cause = args[1];
+    Ok(Some(args[0]))
+}
+
+fn fill_in_stack_trace(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let stack_trace = vm.stack_trace(Value::Null);
+    state(vm, args[0])?.stack_trace = stack_trace;
+    Ok(Some(args[0]))
+}
+
+/// The class name, followed by `": "` and `getLocalizedMessage()` if that isn't
+/// null
+fn to_string(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    let class = vm.object(args[0])?.class;
+    let mut string = vm.class(class).name.replace('/', ".");
+    let message = vm
+        .invoke_virtual("getLocalizedMessage", "()Ljava/lang/String;", vec![args[0]])?
+        .unwrap_or(Value::Null);
+    if message != Value::Null {
+        string = format!("{string}: {}", vm.java_string(message)?);
+    }
+    Ok(Some(vm.new_string(&string)?))
+}
+
+fn print_stack_trace_native(vm: &mut Vm, args: &[Value]) -> NativeResult {
+    print_stack_trace(vm, args[0], "")?;
+    Ok(None)
+}
+
+/// Writes `prefix`, the throwable and where it was created to stderr, followed by
+/// its causes. Frames a cause has in common with the throwable it caused are
+/// summarised as `... n more`.
+pub(crate) fn print_stack_trace(
+    vm: &mut Vm,
+    throwable: Value,
+    prefix: &str,
+) -> Result<(), VmError> {
+    let mut text = String::new();
+    let mut prefix = prefix;
+    let mut enclosing: Vec<StackTraceElement> = vec![];
+    let mut printed = vec![];
+    let mut current = throwable;
+    while current != Value::Null && !printed.contains(&current) {
+        printed.push(current);
+        let description = vm.string_of(current)?;
+        let throwable = state(vm, current)?;
+        let (trace, cause) = (throwable.stack_trace.clone(), throwable.cause);
+        let common = trace
+            .iter()
+            .rev()
+            .zip(enclosing.iter().rev())
+            .take_while(|(frame, enclosing)| frame == enclosing)
+            .count();
+        text.push_str(&format!("{prefix}{description}\n"));
+        for frame in &trace[..trace.len() - common] {
+            text.push_str(&format!("\tat {frame}\n"));
+        }
+        if common > 0 {
+            text.push_str(&format!("\t... {common} more\n"));
+        }
+        enclosing = trace;
+        current = cause;
+        prefix = "Caused by: ";
+    }
+    let _ = vm.stderr.write_all(text.as_bytes());
+    Ok(())
+}
@@ -0,0 +1,367 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use super::{
+    error::VmError,
+    native::{NativeMethod, NativeRegistry},
+    value::Value,
+};
+use crate::parsers::{
+    accessflags::{
+        class_access_flags_mask, field_access_flags_mask, method_access_flags_mask, ACC_ABSTRACT,
+        ACC_INTERFACE, ACC_NATIVE, ACC_PRIVATE, ACC_STATIC, ACC_SYNCHRONIZED,
+    },
+    attributes::{Attr, ExceptionTableEntry, LineNumberTableEntry},
+    bytecode::Instruction,
+    class::JavaClassFile,
+    descriptor::{FieldType, MethodDescriptor},
+    method::Method,
+};
+
+/// Index of a class in the virtual machine's class table
+pub type ClassId = usize;
+
+/// A class loaded into the virtual machine
+#[derive(Debug)]
+pub struct RuntimeClass {
+    pub id: ClassId,
+    pub name: String,
+    pub file: Rc<JavaClassFile>,
+    pub access_flags: u16,
+    /// None for java/lang/Object
+    pub super_class: Option<ClassId>,
+    /// The interfaces the class implements, or an interface extends, directly
+    pub interfaces: Vec<ClassId>,
+    /// Every interface the class implements, including those of its superclasses
+    /// and superinterfaces
+    pub all_interfaces: HashSet<ClassId>,
+    /// The methods declared by this class
+    pub methods: MethodTable,
+    /// The method a virtual call on an instance of this class selects for each name
+    /// and descriptor: its own instance methods, those it inherits from its
+    /// superclasses and the most specific default methods of its interfaces
+    pub vtable: MethodTable,
+    /// The default methods the class inherits more than one of for the same name and
+    /// descriptor, without overriding them. None of them is in `vtable`.
+    pub default_conflicts: Vec<Rc<RuntimeMethod>>,
+    /// Values of the static fields declared by this class, by name
+    pub statics: HashMap<String, Value>,
+    /// Position in `Object::fields` of each instance field declared by this class
+    pub fields: HashMap<String, usize>,
+    /// Initial values of the instance fields of an object of this class, the fields
+    /// inherited from the superclasses first
+    pub field_defaults: Vec<Value>,
+    pub init_state: InitState,
+    /// Whether the class is left out of stack traces, like the classes of lambdas
+    pub hidden: bool,
+}
+
+/// How far the initialization of a class got, see `Vm::initialize`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InitState {
+    Uninitialized,
+    /// Its static initializer is running
+    Initializing,
+    Initialized,
+    /// Its static initializer threw, the class can't be used anymore
+    Failed,
+}
+
+impl RuntimeClass {
+    /// Links the class. Its superclass and interfaces must already be linked, they
+    /// are looked up in `linked` by id.
+    pub fn new(
+        id: ClassId,
+        file: Rc<JavaClassFile>,
+        super_class: Option<ClassId>,
+        interfaces: Vec<ClassId>,
+        linked: &[RuntimeClass],
+        natives: &NativeRegistry,
+    ) -> Result<Self, VmError> {
+        let access_flags = class_access_flags_mask(&file.access_flags);
+        let declared = file
+            .methods
+            .iter()
+            .map(|method| RuntimeMethod::new(id, &file, method, natives).map(Rc::new))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut methods = MethodTable::default();
+        for method in &declared {
+            methods.insert(Rc::clone(method));
+        }
+
+        let super_class = super_class.map(|id| &linked[id]);
+        let mut all_interfaces =
+            super_class.map_or(HashSet::new(), |class| class.all_interfaces.clone());
+        // interfaces don't inherit the methods of java/lang/Object
+        let (mut vtable, mut default_conflicts) = match super_class {
+            Some(class) if access_flags & ACC_INTERFACE == 0 => {
+                (class.vtable.clone(), class.default_conflicts.clone())
+            }
+            _ => (MethodTable::default(), vec![]),
+        };
+        for interface in &interfaces {
+            all_interfaces.insert(*interface);
+            all_interfaces.extend(&linked[*interface].all_interfaces);
+            for method in linked[*interface].vtable.iter() {
+                inherit_default(&mut vtable, &mut default_conflicts, method, linked);
+            }
+        }
+        for method in declared.iter().filter(|method| method.is_virtual()) {
+            vtable.insert(Rc::clone(method));
+            default_conflicts.retain(|conflict| !conflict.overridden_by(method));
+        }
+
+        let mut statics = HashMap::new();
+        let mut fields = HashMap::new();
+        let mut field_defaults = super_class.map_or(vec![], |class| class.field_defaults.clone());
+        for field in &file.fields {
+            let field_type = FieldType::parse(&field.descriptor)
+                .ok_or_else(|| VmError::InvalidDescriptor(field.descriptor.clone()))?;
+            let value = Value::default_for(&field_type);
+            if field_access_flags_mask(&field.access_flags) & ACC_STATIC != 0 {
+                statics.insert(field.name.clone(), value);
+            } else {
+                fields.insert(field.name.clone(), field_defaults.len());
+                field_defaults.push(value);
+            }
+        }
+        Ok(Self {
+            id,
+            name: file.this_class.clone(),
+            access_flags,
+            super_class: super_class.map(|class| class.id),
+            interfaces,
+            all_interfaces,
+            file,
+            methods,
+            vtable,
+            default_conflicts,
+            statics,
+            fields,
+            field_defaults,
+            init_state: InitState::Uninitialized,
+            hidden: false,
+        })
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self.access_flags & ACC_INTERFACE != 0
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags & ACC_ABSTRACT != 0
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.name.starts_with('[')
+    }
+
+    /// The method declared by this class, superclasses are not searched
+    pub fn method(&self, name: &str, descriptor: &str) -> Option<&Rc<RuntimeMethod>> {
+        self.methods.get(name, descriptor)
+    }
+}
+
+/// Adds a method `vtable` inherits from an interface, unless a superclass already
+/// provides one or a more specific interface does. Two unrelated default methods
+/// conflict and neither is selected.
+fn inherit_default(
+    vtable: &mut MethodTable,
+    conflicts: &mut Vec<Rc<RuntimeMethod>>,
+    method: &Rc<RuntimeMethod>,
+    linked: &[RuntimeClass],
+) {
+    let Some(existing) = vtable.get(&method.name, &method.descriptor) else {
+        // a third default conflicting with earlier ones is left out of the message,
+        // like the JDK does
+        if !conflicts
+            .iter()
+            .any(|conflict| conflict.overridden_by(method))
+        {
+            vtable.insert(Rc::clone(method));
+        }
+        return;
+    };
+    let existing_owner = &linked[existing.class];
+    if Rc::ptr_eq(existing, method) || !existing_owner.is_interface() {
+        return;
+    }
+    let more_specific = linked[method.class]
+        .all_interfaces
+        .contains(&existing.class);
+    let less_specific = existing_owner.all_interfaces.contains(&method.class);
+    if more_specific || (!less_specific && existing.is_abstract() && !method.is_abstract()) {
+        vtable.insert(Rc::clone(method));
+    } else if !less_specific && !existing.is_abstract() && !method.is_abstract() {
+        conflicts.push(Rc::clone(existing));
+        conflicts.push(Rc::clone(method));
+        vtable.remove(&method.name, &method.descriptor);
+    }
+}
+
+/// Methods by name and descriptor, a name maps to its few overloads
+#[derive(Debug, Clone, Default)]
+pub struct MethodTable {
+    methods: HashMap<String, Vec<Rc<RuntimeMethod>>>,
+}
+
+impl MethodTable {
+    pub fn get(&self, name: &str, descriptor: &str) -> Option<&Rc<RuntimeMethod>> {
+        self.methods
+            .get(name)?
+            .iter()
+            .find(|method| method.descriptor == descriptor)
+    }
+
+    /// Adds `method`, replacing the method with the same name and descriptor
+    pub fn insert(&mut self, method: Rc<RuntimeMethod>) {
+        let overloads = self.methods.entry(method.name.clone()).or_default();
+        overloads.retain(|overload| overload.descriptor != method.descriptor);
+        overloads.push(method);
+    }
+
+    pub fn remove(&mut self, name: &str, descriptor: &str) {
+        if let Some(overloads) = self.methods.get_mut(name) {
+            overloads.retain(|overload| overload.descriptor != descriptor);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Rc<RuntimeMethod>> {
+        self.methods.values().flatten()
+    }
+}
+
+/// A method ready to be invoked, with its Code attribute unpacked
+#[derive(Debug)]
+pub struct RuntimeMethod {
+    pub class: ClassId,
+    pub class_name: String,
+    /// The class file declaring the method, for its constant pool
+    pub class_file: Rc<JavaClassFile>,
+    pub name: String,
+    pub descriptor: String,
+    pub signature: MethodDescriptor,
+    pub access_flags: u16,
+    /// None for native and abstract methods
+    pub code: Option<Code>,
+    /// The implementation of a native method, if one was registered
+    pub native: Option<NativeMethod>,
+}
+
+impl RuntimeMethod {
+    fn new(
+        class: ClassId,
+        class_file: &Rc<JavaClassFile>,
+        method: &Method,
+        natives: &NativeRegistry,
+    ) -> Result<Self, VmError> {
+        let signature = MethodDescriptor::parse(&method.descriptor)
+            .ok_or_else(|| VmError::InvalidDescriptor(method.descriptor.clone()))?;
+        let code = method.attrs.iter().find_map(|attr| match attr {
+            Attr::Code {
+                max_stack,
+                max_locals,
+                code,
+                exception_table,
+                attrs,
+                ..
+            } => Some(Code {
+                max_stack: *max_stack as usize,
+                max_locals: *max_locals as usize,
+                instructions: code.clone(),
+                exception_table: exception_table.clone(),
+                line_numbers: attrs
+                    .iter()
+                    .filter_map(|attr| match attr {
+                        Attr::LineNumberTable { table, .. } => Some(table.iter().cloned()),
+                        _ => None,
+                    })
+                    .flatten()
+                    .collect(),
+            }),
+            _ => None,
+        });
+        let access_flags = method_access_flags_mask(&method.access_flags);
+        let native = match access_flags & ACC_NATIVE {
+            0 => None,
+            _ => natives.get(&class_file.this_class, &method.name, &method.descriptor),
+        };
+        Ok(Self {
+            class,
+            class_name: class_file.this_class.clone(),
+            class_file: Rc::clone(class_file),
+            name: method.name.clone(),
+            descriptor: method.descriptor.clone(),
+            signature,
+            access_flags,
+            code,
+            native,
+        })
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.access_flags & ACC_NATIVE != 0
+    }
+
+    pub fn is_abstract(&self) -> bool {
+        self.access_flags & ACC_ABSTRACT != 0
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.access_flags & ACC_PRIVATE != 0
+    }
+
+    pub fn is_synchronized(&self) -> bool {
+        self.access_flags & ACC_SYNCHRONIZED != 0
+    }
+
+    /// Whether calls to the method are dispatched on the class of the receiver,
+    /// i.e. it's an instance method that can be overridden
+    pub fn is_virtual(&self) -> bool {
+        !self.is_static() && !self.is_private() && !self.name.starts_with('<')
+    }
+
+    /// Whether `other` has the same name and descriptor
+    pub fn overridden_by(&self, other: &RuntimeMethod) -> bool {
+        self.name == other.name && self.descriptor == other.descriptor
+    }
+
+    /// Number of arguments the method is invoked with, including `this`
+    pub fn arg_count(&self) -> usize {
+        self.signature.params.len() + usize::from(!self.is_static())
+    }
+}
+
+#[derive(Debug)]
+pub struct Code {
+    pub max_stack: usize,
+    pub max_locals: usize,
+    /// Instructions with their pc, in pc order
+    pub instructions: Vec<(u32, Instruction)>,
+    /// Searched in order for the handler of an exception
+    pub exception_table: Vec<ExceptionTableEntry>,
+    /// The entries of all LineNumberTable attributes
+    pub line_numbers: Vec<LineNumberTableEntry>,
+}
+
+impl Code {
+    /// Position in `instructions` of the instruction starting at `pc`
+    pub fn index_of(&self, pc: u32) -> Option<usize> {
+        self.instructions
+            .binary_search_by_key(&pc, |(instr_pc, _)| *instr_pc)
+            .ok()
+    }
+
+    /// The source line the instruction at `pc` was compiled from
+    pub fn line_number(&self, pc: u32) -> Option<u16> {
+        self.line_numbers
+            .iter()
+            .filter(|entry| entry.start_pc as u32 <= pc)
+            .max_by_key(|entry| entry.start_pc)
+            .map(|entry| entry.line_number)
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+
+use super::heap::ObjRef;
+use crate::loader::error::LoadError;
+use crate::parsers::constantpool::ConstantPoolError;
+
+/// Errors that stop the virtual machine, as opposed to Java exceptions which running
+/// code can catch
+#[derive(Debug, Clone, PartialEq)]
+pub enum VmError {
+    ClassNotFound(String),
+    DuplicateClass(String),
+    /// A class was found on the classpath but couldn't be loaded
+    Load(LoadError),
+    MethodNotFound {
+        class: String,
+        name: String,
+        descriptor: String,
+    },
+    NoMainMethod(String),
+    /// A method without a Code attribute, i.e. a native or abstract one, was invoked
+    MissingCode {
+        class: String,
+        name: String,
+        descriptor: String,
+    },
+    /// A native method without a registered implementation was invoked
+    UnsatisfiedLink {
+        class: String,
+        name: String,
+        descriptor: String,
+    },
+    IncompatibleClassChange(String),
+    /// A Java exception, e.g. java/lang/NullPointerException. Natives and the
+    /// interpreter raise exceptions this way, and it is how an exception that escaped
+    /// is reported to the caller of `Vm::invoke`.
+    Exception {
+        class: String,
+        message: Option<String>,
+    },
+    /// A throwable object on its way to a handler
+    Throw(ObjRef),
+    InvalidDescriptor(String),
+    ArgumentCount {
+        expected: usize,
+        found: usize,
+    },
+    UnsupportedInstruction {
+        mnemonic: &'static str,
+        pc: u32,
+    },
+    InvalidBranchTarget(u32),
+    FellOffCode,
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    InvalidLocal(usize),
+    OperandStackOverflow,
+    OperandStackUnderflow,
+    /// Every live thread waits for another one
+    Deadlock,
+    /// A thread blocked on a monitor or in `wait()` where it can't let the others
+    /// run, i.e. in a static initializer or a method a native called
+    BlockedInNestedCall,
+    ConstantPool(ConstantPoolError),
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VmError::ClassNotFound(name) => write!(f, "Class {name} not found"),
+            VmError::DuplicateClass(name) => write!(f, "Class {name} is already loaded"),
+            VmError::Load(err) => write!(f, "{err}"),
+            VmError::MethodNotFound {
+                class,
+                name,
+                descriptor,
+            } => write!(f, "Method {class}.{name}:{descriptor} not found"),
+            VmError::NoMainMethod(class) => write!(
+                f,
+                "Class {class} has no public static void main(String[]) method"
+            ),
+            VmError::MissingCode {
+                class,
+                name,
+                descriptor,
+            } => write!(f, "Method {class}.{name}:{descriptor} has no code"),
+            VmError::UnsatisfiedLink {
+                class,
+                name,
+                descriptor,
+            } => write!(
+                f,
+                "No implementation of native method {class}.{name}:{descriptor}"
+            ),
+            VmError::Exception { class, message } => {
+                write!(f, "{}", class.replace('/', "."))?;
+                match message {
+                    Some(message) => write!(f, ": {message}"),
+                    None => Ok(()),
+                }
+            }
+            VmError::Throw(obj) => write!(f, "Uncaught throwable object {}", obj.index()),
+            VmError::IncompatibleClassChange(message) => {
+                write!(f, "Incompatible class change: {message}")
+            }
+            VmError::InvalidDescriptor(descriptor) => {
+                write!(f, "Invalid descriptor {descriptor}")
+            }
+            VmError::ArgumentCount { expected, found } => {
+                write!(f, "Expected {expected} arguments, found {found}")
+            }
+            VmError::UnsupportedInstruction { mnemonic, pc } => {
+                write!(f, "Unsupported instruction {mnemonic} at pc {pc}")
+            }
+            VmError::InvalidBranchTarget(target) => {
+                write!(
+                    f,
+                    "Branch target {target} is not the start of an instruction"
+                )
+            }
+            VmError::FellOffCode => write!(f, "Execution fell off the end of the code"),
+            VmError::TypeMismatch { expected, found } => {
+                write!(f, "Expected a {expected} but found a {found}")
+            }
+            VmError::InvalidLocal(index) => write!(f, "Invalid local variable {index}"),
+            VmError::OperandStackOverflow => write!(f, "Operand stack exceeds max_stack"),
+            VmError::OperandStackUnderflow => write!(f, "Pop from an empty operand stack"),
+            VmError::Deadlock => write!(f, "Deadlock: no thread can make progress"),
+            VmError::BlockedInNestedCall => write!(
+                f,
+                "Blocking in a static initializer or a method called by a native is unsupported"
+            ),
+            VmError::ConstantPool(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for VmError {}
+
+impl VmError {
+    pub fn exception(class: &str, message: Option<String>) -> Self {
+        VmError::Exception {
+            class: class.to_string(),
+            message,
+        }
+    }
+
+    pub fn null_pointer() -> Self {
+        VmError::exception("java/lang/NullPointerException", None)
+    }
+}
+
+impl From<LoadError> for VmError {
+    fn from(err: LoadError) -> Self {
+        match err {
+            LoadError::ClassNotFound(name) => VmError::ClassNotFound(name),
+            err => VmError::Load(err),
+        }
+    }
+}
+
+impl From<ConstantPoolError> for VmError {
+    fn from(err: ConstantPoolError) -> Self {
+        VmError::ConstantPool(err)
+    }
+}
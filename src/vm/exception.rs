@@ -0,0 +1,144 @@
+use std::rc::Rc;
+
+use super::{
+    builtins,
+    error::VmError,
+    heap::{ObjRef, ObjectData, StackTraceElement, Throwable},
+    value::Value,
+    vm::Vm,
+};
+use crate::parsers::{attributes::Attr, class::JavaClassFile};
+
+impl Vm {
+    /// A new instance of the throwable class `class`, with the current frames as its
+    /// stack trace. Its constructor is not run.
+    pub fn new_throwable(&mut self, class: &str, message: Option<&str>) -> Result<Value, VmError> {
+        let message = match message {
+            Some(message) => self.new_string(message)?,
+            None => Value::Null,
+        };
+        let throwable = Throwable {
+            message,
+            cause: Value::Null,
+            stack_trace: self.stack_trace(Value::Null),
+        };
+        self.new_object(class, ObjectData::Throwable(throwable))
+    }
+
+    /// The running frames, innermost first. The frames of the constructors running
+    /// on `constructing` are left out, so a throwable's trace starts where it was
+    /// created, and so are those of hidden classes.
+    pub(crate) fn stack_trace(&self, constructing: Value) -> Vec<StackTraceElement> {
+        self.frames
+            .iter()
+            .rev()
+            .skip_while(|frame| {
+                frame.method.name == "<init>" && frame.locals.first() == Some(&constructing)
+            })
+            .filter(|frame| !self.classes[frame.method.class].hidden)
+            .map(|frame| {
+                let method = &frame.method;
+                let code = method
+                    .code
+                    .as_ref()
+                    .expect("frames are only created for methods with code");
+                // pc already moved past the instruction being executed
+                let (pc, _) = code.instructions[frame.pc.saturating_sub(1)];
+                StackTraceElement {
+                    class: method.class_name.clone(),
+                    method: method.name.clone(),
+                    file: source_file(&method.class_file),
+                    line: code.line_number(pc),
+                }
+            })
+            .collect()
+    }
+
+    /// The object an error is thrown as, or the error back if it's not a Java
+    /// exception
+    pub(crate) fn throwable(&mut self, err: VmError) -> Result<ObjRef, VmError> {
+        let exception = match err {
+            VmError::Throw(exception) => return Ok(exception),
+            VmError::Exception { class, message } => {
+                self.new_throwable(&class, message.as_deref())?
+            }
+            err => return Err(err),
+        };
+        Ok(exception.as_object()?.expect("new objects aren't null"))
+    }
+
+    /// Transfers control to the handler for `exception` of the innermost frame above
+    /// the first `depth` ones that has one, discarding the frames in between. When
+    /// none has a handler the exception is returned as `VmError::Throw`.
+    pub(crate) fn unwind(&mut self, depth: usize, exception: ObjRef) -> Result<(), VmError> {
+        let class = self.heap.get(exception).class;
+        while self.frames.len() > depth {
+            let frame = self.frames.last().expect("there are frames above depth");
+            let method = Rc::clone(&frame.method);
+            let code = method
+                .code
+                .as_ref()
+                .expect("frames are only created for methods with code");
+            let (pc, _) = code.instructions[frame.pc.saturating_sub(1)];
+            for entry in &code.exception_table {
+                if pc < entry.start_pc as u32 || pc >= entry.end_pc as u32 {
+                    continue;
+                }
+                // the parser names the catch type of finally handlers "any"
+                if entry.catch_type != "any" && !self.is_assignable(class, &entry.catch_type)? {
+                    continue;
+                }
+                let handler = code
+                    .index_of(entry.handler_pc as u32)
+                    .ok_or(VmError::InvalidBranchTarget(entry.handler_pc as u32))?;
+                let frame = self
+                    .frames
+                    .last_mut()
+                    .expect("there are frames above depth");
+                frame.clear_stack();
+                frame.push(Value::Ref(exception))?;
+                frame.pc = handler;
+                return Ok(());
+            }
+            self.pop_frame();
+        }
+        Err(VmError::Throw(exception))
+    }
+
+    /// `exception` as a `VmError::Exception`, for callers outside the virtual machine
+    pub(crate) fn exception_of(&self, exception: ObjRef) -> VmError {
+        let message = match &self.heap.get(exception).data {
+            ObjectData::Throwable(throwable) if throwable.message != Value::Null => {
+                self.java_string(throwable.message).ok()
+            }
+            _ => None,
+        };
+        VmError::exception(self.class_of(exception), message)
+    }
+
+    /// Prints the stack trace of an exception that escaped main, or the `run` method
+    /// of the current thread, to stderr
+    pub(crate) fn uncaught(&mut self, exception: ObjRef) -> VmError {
+        let thread = self.thread_name();
+        // the exception is only referenced from here, so nothing may be collected
+        // while its trace is printed
+        self.native_depth += 1;
+        let printed = builtins::print_stack_trace(
+            self,
+            Value::Ref(exception),
+            &format!("Exception in thread \"{thread}\" "),
+        );
+        self.native_depth -= 1;
+        match printed {
+            Ok(()) => self.exception_of(exception),
+            Err(err) => err,
+        }
+    }
+}
+
+fn source_file(class: &JavaClassFile) -> Option<String> {
+    class.attrs.iter().find_map(|attr| match attr {
+        Attr::SourceFile { file, .. } => Some(file.clone()),
+        _ => None,
+    })
+}
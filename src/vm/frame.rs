@@ -0,0 +1,141 @@
+use std::rc::Rc;
+
+use super::{class::RuntimeMethod, error::VmError, thread::MonitorKey, value::Value};
+
+/// The activation of a method: its local variables, operand stack and the
+/// instruction to execute next
+#[derive(Debug)]
+pub struct Frame {
+    pub method: Rc<RuntimeMethod>,
+    /// Position in `Code::instructions` of the next instruction, not a byte offset
+    pub pc: usize,
+    pub locals: Vec<Value>,
+    pub stack: Vec<Value>,
+    /// The monitor of a synchronized method, released when the frame is discarded
+    pub monitor: Option<MonitorKey>,
+    max_stack: usize,
+    /// Operand stack slots in use, longs and doubles take two
+    depth: usize,
+}
+
+impl Frame {
+    /// Creates the frame of a method that has code, storing `args` in its first locals
+    pub fn new(method: Rc<RuntimeMethod>, args: Vec<Value>) -> Result<Self, VmError> {
+        let code = method.code.as_ref().ok_or_else(|| VmError::MissingCode {
+            class: method.class_name.clone(),
+            name: method.name.clone(),
+            descriptor: method.descriptor.clone(),
+        })?;
+        let mut frame = Self {
+            pc: 0,
+            locals: vec![Value::Top; code.max_locals],
+            stack: Vec::with_capacity(code.max_stack),
+            monitor: None,
+            max_stack: code.max_stack,
+            depth: 0,
+            method,
+        };
+        let mut index = 0;
+        for arg in args {
+            frame.store(index, arg)?;
+            index += arg.slots();
+        }
+        Ok(frame)
+    }
+
+    pub fn push(&mut self, value: Value) -> Result<(), VmError> {
+        if self.depth + value.slots() > self.max_stack {
+            return Err(VmError::OperandStackOverflow);
+        }
+        self.depth += value.slots();
+        self.stack.push(value);
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Result<Value, VmError> {
+        let value = self.stack.pop().ok_or(VmError::OperandStackUnderflow)?;
+        self.depth -= value.slots();
+        Ok(value)
+    }
+
+    /// Pops values taking exactly `slots` slots, returned in stack order (top last).
+    /// Used by the type agnostic stack instructions like `pop2` and `dup_x2`.
+    pub fn pop_slots(&mut self, slots: usize) -> Result<Vec<Value>, VmError> {
+        let mut values = vec![];
+        let mut popped = 0;
+        while popped < slots {
+            let value = self.pop()?;
+            popped += value.slots();
+            values.push(value);
+        }
+        if popped != slots {
+            // a long or double would have been split
+            return Err(values[values.len() - 1].mismatch("category 1 value"));
+        }
+        values.reverse();
+        Ok(values)
+    }
+
+    /// Pops the `count` arguments of an invocation, returned in declaration order
+    pub fn pop_args(&mut self, count: usize) -> Result<Vec<Value>, VmError> {
+        if count > self.stack.len() {
+            return Err(VmError::OperandStackUnderflow);
+        }
+        let args = self.stack.split_off(self.stack.len() - count);
+        self.depth -= args.iter().map(Value::slots).sum::<usize>();
+        Ok(args)
+    }
+
+    /// Empties the operand stack, as happens before a handler catches an exception
+    pub fn clear_stack(&mut self) {
+        self.stack.clear();
+        self.depth = 0;
+    }
+
+    pub fn pop_int(&mut self) -> Result<i32, VmError> {
+        self.pop()?.as_int()
+    }
+
+    pub fn pop_long(&mut self) -> Result<i64, VmError> {
+        self.pop()?.as_long()
+    }
+
+    pub fn pop_float(&mut self) -> Result<f32, VmError> {
+        self.pop()?.as_float()
+    }
+
+    pub fn pop_double(&mut self) -> Result<f64, VmError> {
+        self.pop()?.as_double()
+    }
+
+    pub fn pop_reference(&mut self) -> Result<Value, VmError> {
+        let value = self.pop()?;
+        value.as_object()?;
+        Ok(value)
+    }
+
+    /// Loads local `index`, checking it holds a value of the same kind as `expected`
+    pub fn load(&self, index: usize, expected: &'static str) -> Result<Value, VmError> {
+        let value = *self.locals.get(index).ok_or(VmError::InvalidLocal(index))?;
+        if value.type_name() != expected {
+            return Err(value.mismatch(expected));
+        }
+        Ok(value)
+    }
+
+    /// Stores `value` in local `index`, a long or double also takes `index + 1`
+    pub fn store(&mut self, index: usize, value: Value) -> Result<(), VmError> {
+        if index + value.slots() > self.locals.len() {
+            return Err(VmError::InvalidLocal(index));
+        }
+        // storing over either half of a long or double invalidates it
+        if index > 0 && self.locals[index - 1].slots() == 2 {
+            self.locals[index - 1] = Value::Top;
+        }
+        self.locals[index] = value;
+        if value.slots() == 2 {
+            self.locals[index + 1] = Value::Top;
+        }
+        Ok(())
+    }
+}
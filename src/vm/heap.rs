@@ -0,0 +1,317 @@
+use std::fmt;
+
+use super::{class::ClassId, error::VmError, thread::ThreadId, value::Value};
+use crate::parsers::bytecode::ArrayType;
+
+/// Number of allocations before the first collection, later collections happen
+/// once the heap has grown to twice what survived the previous one
+const INITIAL_COLLECTION_THRESHOLD: usize = 1024;
+
+/// A reference to an object on the heap, compared by identity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjRef(usize);
+
+impl ObjRef {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Object {
+    pub class: ClassId,
+    /// The instance fields, laid out as in `RuntimeClass::field_defaults`
+    pub fields: Vec<Value>,
+    pub data: ObjectData,
+}
+
+/// What an object holds besides its fields
+#[derive(Debug, Clone)]
+pub enum ObjectData {
+    /// An instance of a class without native state
+    Instance,
+    /// The UTF-16 code units of a java/lang/String or java/lang/StringBuilder
+    Chars(Vec<u16>),
+    /// A boxed java/lang/Integer
+    Integer(i32),
+    /// A java/io/PrintStream writing to the virtual machine's stdout or stderr
+    PrintStream(Stream),
+    Array(Array),
+    /// A java/lang/Throwable or an instance of one of its subclasses
+    Throwable(Throwable),
+    /// A java/lang/Thread or an instance of one of its subclasses
+    Thread(ThreadData),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stream {
+    Out,
+    Err,
+}
+
+/// What java/lang/Throwable keeps natively
+#[derive(Debug, Clone)]
+pub struct Throwable {
+    /// A String or null
+    pub message: Value,
+    /// A Throwable or null
+    pub cause: Value,
+    /// Where the throwable was created, the innermost frame first
+    pub stack_trace: Vec<StackTraceElement>,
+}
+
+/// What java/lang/Thread keeps natively
+#[derive(Debug, Clone)]
+pub struct ThreadData {
+    pub name: String,
+    /// The Runnable it runs, or null
+    pub target: Value,
+    /// Set once started
+    pub thread: Option<ThreadId>,
+}
+
+/// A frame of a stack trace, displayed like `Foo.bar(Foo.java:12)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackTraceElement {
+    pub class: String,
+    pub method: String,
+    /// From the SourceFile attribute
+    pub file: Option<String>,
+    /// From the LineNumberTable attribute
+    pub line: Option<u16>,
+}
+
+impl fmt::Display for StackTraceElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}(", self.class.replace('/', "."), self.method)?;
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{file}:{line})"),
+            (Some(file), None) => write!(f, "{file})"),
+            (None, _) => write!(f, "Unknown Source)"),
+        }
+    }
+}
+
+/// The elements of an array, stored with the width of their component type
+#[derive(Debug, Clone, PartialEq)]
+pub enum Array {
+    Boolean(Vec<i8>),
+    Byte(Vec<i8>),
+    Char(Vec<u16>),
+    Short(Vec<i16>),
+    Int(Vec<i32>),
+    Long(Vec<i64>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    /// Arrays of any class, interface or array type
+    Reference(Vec<Value>),
+}
+
+impl Array {
+    /// An array of `length` zeroes, falses or nulls for the component type named by
+    /// the array class `class`, e.g. `[I` or `[Ljava/lang/String;`
+    pub fn new(class: &str, length: usize) -> Array {
+        match class.as_bytes().get(1) {
+            Some(b'Z') => Array::Boolean(vec![0; length]),
+            Some(b'B') => Array::Byte(vec![0; length]),
+            Some(b'C') => Array::Char(vec![0; length]),
+            Some(b'S') => Array::Short(vec![0; length]),
+            Some(b'I') => Array::Int(vec![0; length]),
+            Some(b'J') => Array::Long(vec![0; length]),
+            Some(b'F') => Array::Float(vec![0.0; length]),
+            Some(b'D') => Array::Double(vec![0.0; length]),
+            _ => Array::Reference(vec![Value::Null; length]),
+        }
+    }
+
+    /// Name of the class of arrays created by `newarray` with `atype`
+    pub fn class_name(atype: &ArrayType) -> &'static str {
+        match atype {
+            ArrayType::Boolean => "[Z",
+            ArrayType::Char => "[C",
+            ArrayType::Float => "[F",
+            ArrayType::Double => "[D",
+            ArrayType::Byte => "[B",
+            ArrayType::Short => "[S",
+            ArrayType::Int => "[I",
+            ArrayType::Long => "[J",
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Array::Boolean(elements) | Array::Byte(elements) => elements.len(),
+            Array::Char(elements) => elements.len(),
+            Array::Short(elements) => elements.len(),
+            Array::Int(elements) => elements.len(),
+            Array::Long(elements) => elements.len(),
+            Array::Float(elements) => elements.len(),
+            Array::Double(elements) => elements.len(),
+            Array::Reference(elements) => elements.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The element at `index` widened to the computational type, None when out of
+    /// bounds
+    pub fn get(&self, index: usize) -> Option<Value> {
+        match self {
+            Array::Boolean(elements) | Array::Byte(elements) => elements
+                .get(index)
+                .map(|element| Value::Int(*element as i32)),
+            Array::Char(elements) => elements
+                .get(index)
+                .map(|element| Value::Int(*element as i32)),
+            Array::Short(elements) => elements
+                .get(index)
+                .map(|element| Value::Int(*element as i32)),
+            Array::Int(elements) => elements.get(index).map(|element| Value::Int(*element)),
+            Array::Long(elements) => elements.get(index).map(|element| Value::Long(*element)),
+            Array::Float(elements) => elements.get(index).map(|element| Value::Float(*element)),
+            Array::Double(elements) => elements.get(index).map(|element| Value::Double(*element)),
+            Array::Reference(elements) => elements.get(index).copied(),
+        }
+    }
+
+    /// Stores `value` at `index`, narrowing ints to the component type. Returns false
+    /// when `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: Value) -> Result<bool, VmError> {
+        if index >= self.len() {
+            return Ok(false);
+        }
+        match self {
+            Array::Boolean(elements) => elements[index] = (value.as_int()? & 1) as i8,
+            Array::Byte(elements) => elements[index] = value.as_int()? as i8,
+            Array::Char(elements) => elements[index] = value.as_int()? as u16,
+            Array::Short(elements) => elements[index] = value.as_int()? as i16,
+            Array::Int(elements) => elements[index] = value.as_int()?,
+            Array::Long(elements) => elements[index] = value.as_long()?,
+            Array::Float(elements) => elements[index] = value.as_float()?,
+            Array::Double(elements) => elements[index] = value.as_double()?,
+            Array::Reference(elements) => {
+                value.as_object()?;
+                elements[index] = value;
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Where objects live. Objects are never moved, a collection only frees the slots of
+/// unreachable objects for reuse.
+#[derive(Debug)]
+pub struct Heap {
+    objects: Vec<Option<Object>>,
+    /// Slots freed by the last collections
+    free: Vec<usize>,
+    live: usize,
+    /// Allocations left before `should_collect` asks for a collection
+    until_collection: usize,
+}
+
+impl Default for Heap {
+    fn default() -> Self {
+        Self {
+            objects: vec![],
+            free: vec![],
+            live: 0,
+            until_collection: INITIAL_COLLECTION_THRESHOLD,
+        }
+    }
+}
+
+impl Heap {
+    pub fn alloc(&mut self, object: Object) -> ObjRef {
+        self.live += 1;
+        self.until_collection = self.until_collection.saturating_sub(1);
+        match self.free.pop() {
+            Some(index) => {
+                self.objects[index] = Some(object);
+                ObjRef(index)
+            }
+            None => {
+                self.objects.push(Some(object));
+                ObjRef(self.objects.len() - 1)
+            }
+        }
+    }
+
+    pub fn get(&self, obj: ObjRef) -> &Object {
+        self.objects[obj.0]
+            .as_ref()
+            .expect("reachable objects are never collected")
+    }
+
+    pub fn get_mut(&mut self, obj: ObjRef) -> &mut Object {
+        self.objects[obj.0]
+            .as_mut()
+            .expect("reachable objects are never collected")
+    }
+
+    /// Whether `obj` wasn't freed by a collection
+    pub fn is_live(&self, obj: ObjRef) -> bool {
+        matches!(self.objects.get(obj.0), Some(Some(_)))
+    }
+
+    /// Number of objects on the heap
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    /// Whether enough was allocated since the last collection to collect again
+    pub fn should_collect(&self) -> bool {
+        self.until_collection == 0
+    }
+
+    /// Marks every object reachable from `roots` and frees the others, returning the
+    /// number of objects freed
+    pub fn collect(&mut self, roots: impl IntoIterator<Item = Value>) -> usize {
+        let mut marked = vec![false; self.objects.len()];
+        let mut pending: Vec<ObjRef> = roots.into_iter().filter_map(reference).collect();
+        while let Some(obj) = pending.pop() {
+            if std::mem::replace(&mut marked[obj.0], true) {
+                continue;
+            }
+            let object = self.get(obj);
+            pending.extend(object.fields.iter().copied().filter_map(reference));
+            match &object.data {
+                ObjectData::Array(Array::Reference(elements)) => {
+                    pending.extend(elements.iter().copied().filter_map(reference))
+                }
+                ObjectData::Throwable(throwable) => pending.extend(
+                    [throwable.message, throwable.cause]
+                        .into_iter()
+                        .filter_map(reference),
+                ),
+                ObjectData::Thread(thread) => pending.extend(reference(thread.target)),
+                _ => {}
+            }
+        }
+
+        let mut freed = 0;
+        for (index, object) in self.objects.iter_mut().enumerate() {
+            if object.is_some() && !marked[index] {
+                *object = None;
+                self.free.push(index);
+                freed += 1;
+            }
+        }
+        self.live -= freed;
+        self.until_collection = INITIAL_COLLECTION_THRESHOLD.max(self.live);
+        freed
+    }
+}
+
+fn reference(value: Value) -> Option<ObjRef> {
+    match value {
+        Value::Ref(obj) => Some(obj),
+        _ => None,
+    }
+}
@@ -0,0 +1,132 @@
+use std::rc::Rc;
+
+use super::{
+    class::{ClassId, InitState},
+    error::VmError,
+    heap::ObjectData,
+    value::Value,
+    vm::Vm,
+};
+use crate::parsers::{
+    accessflags::{field_access_flags_mask, ACC_STATIC},
+    attributes::Attr,
+    constantpool::Constant,
+};
+
+impl Vm {
+    /// Initializes `class` unless it already is: sets its static final fields to
+    /// their ConstantValue, initializes its superclass and the superinterfaces
+    /// declaring default methods, then runs its `<clinit>`. Happens on the first
+    /// `new`, `getstatic`, `putstatic` or `invokestatic` naming the class.
+    ///
+    /// An exception thrown by `<clinit>` is wrapped in an ExceptionInInitializerError
+    /// unless it's an Error, and every later use of the class throws
+    /// NoClassDefFoundError.
+    pub fn initialize(&mut self, class: ClassId) -> Result<(), VmError> {
+        match self.classes[class].init_state {
+            // Initializing means the static initializer itself, or something it
+            // calls, uses the class, which then sees it partially initialized
+            InitState::Initialized | InitState::Initializing => return Ok(()),
+            InitState::Failed => {
+                return Err(VmError::exception(
+                    "java/lang/NoClassDefFoundError",
+                    Some(format!(
+                        "Could not initialize class {}",
+                        self.classes[class].name.replace('/', ".")
+                    )),
+                ))
+            }
+            InitState::Uninitialized => {}
+        }
+        self.classes[class].init_state = InitState::Initializing;
+        let result = self.run_initializers(class);
+        self.classes[class].init_state = match result {
+            Ok(()) => InitState::Initialized,
+            Err(_) => InitState::Failed,
+        };
+        result
+    }
+
+    fn run_initializers(&mut self, class: ClassId) -> Result<(), VmError> {
+        self.set_constant_values(class)?;
+        if !self.classes[class].is_interface() {
+            if let Some(super_class) = self.classes[class].super_class {
+                self.initialize(super_class)?;
+            }
+            for interface in self.default_interfaces(class) {
+                self.initialize(interface)?;
+            }
+        }
+        let Some(clinit) = self.classes[class].method("<clinit>", "()V").map(Rc::clone) else {
+            return Ok(());
+        };
+        let err = match self.invoke_method(clinit, vec![]) {
+            Ok(_) => return Ok(()),
+            Err(err) => err,
+        };
+        let exception = self.throwable(err)?;
+        if self.is_assignable(self.heap.get(exception).class, "java/lang/Error")? {
+            return Err(VmError::Throw(exception));
+        }
+        let error = self.new_throwable("java/lang/ExceptionInInitializerError", None)?;
+        if let ObjectData::Throwable(throwable) = &mut self.object_mut(error)?.data {
+            throwable.cause = Value::Ref(exception);
+        }
+        Err(VmError::Throw(
+            error.as_object()?.expect("new objects aren't null"),
+        ))
+    }
+
+    fn set_constant_values(&mut self, class: ClassId) -> Result<(), VmError> {
+        let file = Rc::clone(&self.classes[class].file);
+        let cp = &file.constant_pool;
+        for field in &file.fields {
+            if field_access_flags_mask(&field.access_flags) & ACC_STATIC == 0 {
+                continue;
+            }
+            let Some(index) = field.attrs.iter().find_map(|attr| match attr {
+                Attr::ConstantValue { value_index, .. } => Some(*value_index as usize),
+                _ => None,
+            }) else {
+                continue;
+            };
+            let value = match cp.get(index)? {
+                Constant::Integer(value) => Value::Int(*value),
+                Constant::Float(value) => Value::Float(*value),
+                Constant::Long(value) => Value::Long(*value),
+                Constant::Double(value) => Value::Double(*value),
+                _ => self.interned_chars(cp.string_entry(index)?.units())?,
+            };
+            self.classes[class]
+                .statics
+                .insert(field.name.clone(), value);
+        }
+        Ok(())
+    }
+
+    /// The superinterfaces of `class` declaring a default method, which are
+    /// initialized with it, in the order they're declared in
+    fn default_interfaces(&self, class: ClassId) -> Vec<ClassId> {
+        let mut pending: Vec<ClassId> = self.classes[class]
+            .interfaces
+            .iter()
+            .rev()
+            .copied()
+            .collect();
+        let mut interfaces = vec![];
+        while let Some(interface) = pending.pop() {
+            if interfaces.contains(&interface) {
+                continue;
+            }
+            let declares_default = self.classes[interface]
+                .methods
+                .iter()
+                .any(|method| !method.is_abstract() && !method.is_static());
+            if declares_default {
+                interfaces.push(interface);
+            }
+            pending.extend(self.classes[interface].interfaces.iter().rev());
+        }
+        interfaces
+    }
+}
@@ -0,0 +1,916 @@
+use std::rc::Rc;
+
+use super::{
+    class::{Code, RuntimeMethod},
+    error::VmError,
+    frame::Frame,
+    heap::{Array, ObjRef, ObjectData},
+    thread::MonitorKey,
+    value::Value,
+    vm::Vm,
+};
+use crate::parsers::{
+    bytecode::{Instruction, WideInstruction},
+    constantpool::Constant,
+};
+
+/// How execution continues after an instruction
+enum Completion {
+    Next,
+    Return(Option<Value>),
+}
+
+impl Vm {
+    /// Executes the frames above the first `depth` ones until the one at `depth`
+    /// returns, then hands back its return value. On error the frames above `depth`
+    /// are discarded.
+    pub(crate) fn run(&mut self, depth: usize) -> Result<Option<Value>, VmError> {
+        self.threads.nesting += 1;
+        let result = self.run_frames(depth);
+        self.threads.nesting -= 1;
+        if result.is_err() {
+            while self.frames.len() > depth {
+                self.pop_frame();
+            }
+        }
+        result
+    }
+
+    /// Executes the frames of the current thread, switching to the other threads
+    /// between instructions when this is the outermost loop. Those run until they
+    /// terminate or the current thread can run again.
+    fn run_frames(&mut self, depth: usize) -> Result<Option<Value>, VmError> {
+        let origin = self.threads.current();
+        loop {
+            // between instructions every reference is in a frame or a static field
+            if self.native_depth == 0 && self.heap.should_collect() {
+                self.collect_garbage();
+            }
+            self.schedule()?;
+            let frame = self.frame();
+            let method = Rc::clone(&frame.method);
+            let code = method
+                .code
+                .as_ref()
+                .expect("frames are only created for methods with code");
+            let (pc, instr) = code
+                .instructions
+                .get(frame.pc)
+                .ok_or(VmError::FellOffCode)?;
+            frame.pc += 1;
+
+            match self.execute(&method, code, *pc, instr) {
+                Ok(Completion::Next) => {}
+                Ok(Completion::Return(value)) => {
+                    self.pop_frame();
+                    if self.threads.current() != origin && self.frames.is_empty() {
+                        self.terminate_thread()?;
+                        continue;
+                    }
+                    if self.frames.len() == depth {
+                        return Ok(value);
+                    }
+                    if let Some(value) = value {
+                        self.frame().push(value)?;
+                    }
+                }
+                Err(err) => {
+                    let exception = self.throwable(err)?;
+                    if self.threads.current() == origin {
+                        self.unwind(depth, exception)?;
+                    } else if let Err(err) = self.unwind(0, exception) {
+                        let exception = self.throwable(err)?;
+                        self.uncaught(exception);
+                        self.terminate_thread()?;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Calls `method` from the current frame. Native methods run to completion and
+    /// push their result, other methods get a frame of their own. A synchronized
+    /// method whose monitor another thread holds blocks the current thread, and is
+    /// called again when it can enter it.
+    fn call(&mut self, method: Rc<RuntimeMethod>, args: Vec<Value>) -> Result<(), VmError> {
+        if !method.is_native() {
+            let monitor = self.method_monitor(&method, &args)?;
+            if let Some(monitor) = monitor {
+                if !self.can_enter(monitor) {
+                    return self.block(monitor, args);
+                }
+            }
+            self.push_frame(method, args)?;
+            if let Some(monitor) = monitor {
+                self.enter_monitor(monitor);
+                self.frame().monitor = Some(monitor);
+            }
+            return Ok(());
+        }
+        if let Some(value) = self.invoke_native(&method, &args)? {
+            self.frame().push(value)?;
+        }
+        Ok(())
+    }
+
+    /// The value `ldc`, `ldc_w` or `ldc2_w` pushes for the constant at `index`
+    fn constant(
+        &mut self,
+        method: &RuntimeMethod,
+        index: usize,
+        instr: &Instruction,
+        pc: u32,
+    ) -> Result<Value, VmError> {
+        let cp = &method.class_file.constant_pool;
+        match cp.get(index)? {
+            Constant::Integer(value) => Ok(Value::Int(*value)),
+            Constant::Float(value) => Ok(Value::Float(*value)),
+            Constant::Long(value) => Ok(Value::Long(*value)),
+            Constant::Double(value) => Ok(Value::Double(*value)),
+            Constant::String { .. } => {
+                let chars = cp.string_entry(index)?.units();
+                self.interned_chars(chars)
+            }
+            _ => Err(VmError::UnsupportedInstruction {
+                mnemonic: instr.mnemonic(),
+                pc,
+            }),
+        }
+    }
+
+    /// `multianewarray`: an array of `lengths[0]` arrays of `lengths[1]` elements
+    /// and so on, dimensions without a length are left null
+    fn new_multi_array(&mut self, class: &str, lengths: &[i32]) -> Result<Value, VmError> {
+        if let Some(length) = lengths.iter().find(|length| **length < 0) {
+            return Err(VmError::exception(
+                "java/lang/NegativeArraySizeException",
+                Some(length.to_string()),
+            ));
+        }
+        let array = self.new_array(class, lengths[0])?;
+        if lengths.len() > 1 {
+            for index in 0..lengths[0] as usize {
+                let element = self.new_multi_array(&class[1..], &lengths[1..])?;
+                self.array_mut(array)?.set(index, element)?;
+            }
+        }
+        Ok(array)
+    }
+
+    /// `aastore` only stores objects that are instances of the component type
+    fn check_array_store(&mut self, array: Value, obj: ObjRef) -> Result<(), VmError> {
+        let array_class = self.object(array)?.class;
+        let component = match &self.classes[array_class].name[1..] {
+            component if component.starts_with('[') => component.to_string(),
+            component => component[1..component.len() - 1].to_string(),
+        };
+        let class = self.heap.get(obj).class;
+        if !self.is_assignable(class, &component)? {
+            return Err(VmError::exception(
+                "java/lang/ArrayStoreException",
+                Some(self.classes[class].name.replace('/', ".")),
+            ));
+        }
+        Ok(())
+    }
+
+    fn index_out_of_bounds(&self, array: Value, index: i32) -> VmError {
+        let length = self.array(array).map_or(0, Array::len);
+        VmError::exception(
+            "java/lang/ArrayIndexOutOfBoundsException",
+            Some(format!("Index {index} out of bounds for length {length}")),
+        )
+    }
+
+    pub(crate) fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("no frame to execute")
+    }
+
+    fn execute(
+        &mut self,
+        method: &RuntimeMethod,
+        code: &Code,
+        pc: u32,
+        instr: &Instruction,
+    ) -> Result<Completion, VmError> {
+        let cp = &method.class_file.constant_pool;
+        let frame = self.frames.last_mut().expect("no frame to execute");
+        match instr {
+            Instruction::Nop => {}
+
+            Instruction::AconstNull => frame.push(Value::Null)?,
+            Instruction::IconstM1 => frame.push(Value::Int(-1))?,
+            Instruction::Iconst0 => frame.push(Value::Int(0))?,
+            Instruction::Iconst1 => frame.push(Value::Int(1))?,
+            Instruction::Iconst2 => frame.push(Value::Int(2))?,
+            Instruction::Iconst3 => frame.push(Value::Int(3))?,
+            Instruction::Iconst4 => frame.push(Value::Int(4))?,
+            Instruction::Iconst5 => frame.push(Value::Int(5))?,
+            Instruction::Lconst0 => frame.push(Value::Long(0))?,
+            Instruction::Lconst1 => frame.push(Value::Long(1))?,
+            Instruction::Fconst0 => frame.push(Value::Float(0.0))?,
+            Instruction::Fconst1 => frame.push(Value::Float(1.0))?,
+            Instruction::Fconst2 => frame.push(Value::Float(2.0))?,
+            Instruction::Dconst0 => frame.push(Value::Double(0.0))?,
+            Instruction::Dconst1 => frame.push(Value::Double(1.0))?,
+            Instruction::Bipush(value) => frame.push(Value::Int(*value as i32))?,
+            Instruction::Sipush(value) => frame.push(Value::Int(*value as i32))?,
+            Instruction::Ldc(index) => {
+                let value = self.constant(method, *index as usize, instr, pc)?;
+                self.frame().push(value)?;
+            }
+            Instruction::LdcW(index) | Instruction::Ldc2W(index) => {
+                let value = self.constant(method, *index as usize, instr, pc)?;
+                self.frame().push(value)?;
+            }
+
+            Instruction::Iload(index) => load(frame, *index as usize, "int")?,
+            Instruction::Iload0 => load(frame, 0, "int")?,
+            Instruction::Iload1 => load(frame, 1, "int")?,
+            Instruction::Iload2 => load(frame, 2, "int")?,
+            Instruction::Iload3 => load(frame, 3, "int")?,
+            Instruction::Istore(index) => store(frame, *index as usize, "int")?,
+            Instruction::Istore0 => store(frame, 0, "int")?,
+            Instruction::Istore1 => store(frame, 1, "int")?,
+            Instruction::Istore2 => store(frame, 2, "int")?,
+            Instruction::Istore3 => store(frame, 3, "int")?,
+            Instruction::Lload(index) => load(frame, *index as usize, "long")?,
+            Instruction::Lload0 => load(frame, 0, "long")?,
+            Instruction::Lload1 => load(frame, 1, "long")?,
+            Instruction::Lload2 => load(frame, 2, "long")?,
+            Instruction::Lload3 => load(frame, 3, "long")?,
+            Instruction::Lstore(index) => store(frame, *index as usize, "long")?,
+            Instruction::Lstore0 => store(frame, 0, "long")?,
+            Instruction::Lstore1 => store(frame, 1, "long")?,
+            Instruction::Lstore2 => store(frame, 2, "long")?,
+            Instruction::Lstore3 => store(frame, 3, "long")?,
+            Instruction::Fload(index) => load(frame, *index as usize, "float")?,
+            Instruction::Fload0 => load(frame, 0, "float")?,
+            Instruction::Fload1 => load(frame, 1, "float")?,
+            Instruction::Fload2 => load(frame, 2, "float")?,
+            Instruction::Fload3 => load(frame, 3, "float")?,
+            Instruction::Fstore(index) => store(frame, *index as usize, "float")?,
+            Instruction::Fstore0 => store(frame, 0, "float")?,
+            Instruction::Fstore1 => store(frame, 1, "float")?,
+            Instruction::Fstore2 => store(frame, 2, "float")?,
+            Instruction::Fstore3 => store(frame, 3, "float")?,
+            Instruction::Dload(index) => load(frame, *index as usize, "double")?,
+            Instruction::Dload0 => load(frame, 0, "double")?,
+            Instruction::Dload1 => load(frame, 1, "double")?,
+            Instruction::Dload2 => load(frame, 2, "double")?,
+            Instruction::Dload3 => load(frame, 3, "double")?,
+            Instruction::Dstore(index) => store(frame, *index as usize, "double")?,
+            Instruction::Dstore0 => store(frame, 0, "double")?,
+            Instruction::Dstore1 => store(frame, 1, "double")?,
+            Instruction::Dstore2 => store(frame, 2, "double")?,
+            Instruction::Dstore3 => store(frame, 3, "double")?,
+            Instruction::Aload(index) => load(frame, *index as usize, "reference")?,
+            Instruction::Aload0 => load(frame, 0, "reference")?,
+            Instruction::Aload1 => load(frame, 1, "reference")?,
+            Instruction::Aload2 => load(frame, 2, "reference")?,
+            Instruction::Aload3 => load(frame, 3, "reference")?,
+            Instruction::Astore(index) => store(frame, *index as usize, "reference")?,
+            Instruction::Astore0 => store(frame, 0, "reference")?,
+            Instruction::Astore1 => store(frame, 1, "reference")?,
+            Instruction::Astore2 => store(frame, 2, "reference")?,
+            Instruction::Astore3 => store(frame, 3, "reference")?,
+            Instruction::Iaload
+            | Instruction::Laload
+            | Instruction::Faload
+            | Instruction::Daload
+            | Instruction::Aaload
+            | Instruction::Baload
+            | Instruction::Caload
+            | Instruction::Saload => {
+                let index = frame.pop_int()?;
+                let array = frame.pop_reference()?;
+                let value = self
+                    .array(array)?
+                    .get(index as usize)
+                    .filter(|_| index >= 0)
+                    .ok_or_else(|| self.index_out_of_bounds(array, index))?;
+                self.frame().push(value)?;
+            }
+            Instruction::Iastore
+            | Instruction::Lastore
+            | Instruction::Fastore
+            | Instruction::Dastore
+            | Instruction::Aastore
+            | Instruction::Bastore
+            | Instruction::Castore
+            | Instruction::Sastore => {
+                let value = frame.pop()?;
+                let index = frame.pop_int()?;
+                let array = frame.pop_reference()?;
+                if let (Instruction::Aastore, Value::Ref(obj)) = (instr, value) {
+                    self.check_array_store(array, obj)?;
+                }
+                if index < 0 || !self.array_mut(array)?.set(index as usize, value)? {
+                    return Err(self.index_out_of_bounds(array, index));
+                }
+            }
+
+            Instruction::Iinc(index, constant) => iinc(frame, *index as usize, *constant as i32)?,
+            Instruction::Wide(instr) => match instr {
+                WideInstruction::Iload(index) => load(frame, *index as usize, "int")?,
+                WideInstruction::Istore(index) => store(frame, *index as usize, "int")?,
+                WideInstruction::Lload(index) => load(frame, *index as usize, "long")?,
+                WideInstruction::Lstore(index) => store(frame, *index as usize, "long")?,
+                WideInstruction::Fload(index) => load(frame, *index as usize, "float")?,
+                WideInstruction::Fstore(index) => store(frame, *index as usize, "float")?,
+                WideInstruction::Dload(index) => load(frame, *index as usize, "double")?,
+                WideInstruction::Dstore(index) => store(frame, *index as usize, "double")?,
+                WideInstruction::Aload(index) => load(frame, *index as usize, "reference")?,
+                WideInstruction::Astore(index) => store(frame, *index as usize, "reference")?,
+                WideInstruction::Iinc(index, constant) => {
+                    iinc(frame, *index as usize, *constant as i32)?
+                }
+                WideInstruction::Ret(_) => {
+                    return Err(VmError::UnsupportedInstruction {
+                        mnemonic: "wide ret",
+                        pc,
+                    })
+                }
+            },
+
+            Instruction::Pop => {
+                frame.pop_slots(1)?;
+            }
+            Instruction::Pop2 => {
+                frame.pop_slots(2)?;
+            }
+            Instruction::Dup => dup(frame, 1, 0)?,
+            Instruction::DupX1 => dup(frame, 1, 1)?,
+            Instruction::DupX2 => dup(frame, 1, 2)?,
+            Instruction::Dup2 => dup(frame, 2, 0)?,
+            Instruction::Dup2X1 => dup(frame, 2, 1)?,
+            Instruction::Dup2X2 => dup(frame, 2, 2)?,
+            Instruction::Swap => {
+                let value1 = frame.pop_slots(1)?[0];
+                let value2 = frame.pop_slots(1)?[0];
+                frame.push(value1)?;
+                frame.push(value2)?;
+            }
+
+            Instruction::Iadd => int_op(frame, i32::wrapping_add)?,
+            Instruction::Isub => int_op(frame, i32::wrapping_sub)?,
+            Instruction::Imul => int_op(frame, i32::wrapping_mul)?,
+            Instruction::Idiv | Instruction::Irem => {
+                let value2 = frame.pop_int()?;
+                let value1 = frame.pop_int()?;
+                if value2 == 0 {
+                    return Err(division_by_zero());
+                }
+                let result = match instr {
+                    Instruction::Idiv => value1.wrapping_div(value2),
+                    _ => value1.wrapping_rem(value2),
+                };
+                frame.push(Value::Int(result))?;
+            }
+            Instruction::Ineg => {
+                let value = frame.pop_int()?;
+                frame.push(Value::Int(value.wrapping_neg()))?;
+            }
+            Instruction::Ishl => int_op(frame, |value, shift| value.wrapping_shl(shift as u32))?,
+            Instruction::Ishr => int_op(frame, |value, shift| value.wrapping_shr(shift as u32))?,
+            Instruction::Iushr => int_op(frame, |value, shift| {
+                (value as u32).wrapping_shr(shift as u32) as i32
+            })?,
+            Instruction::Iand => int_op(frame, |value1, value2| value1 & value2)?,
+            Instruction::Ior => int_op(frame, |value1, value2| value1 | value2)?,
+            Instruction::Ixor => int_op(frame, |value1, value2| value1 ^ value2)?,
+
+            Instruction::Ladd => long_op(frame, i64::wrapping_add)?,
+            Instruction::Lsub => long_op(frame, i64::wrapping_sub)?,
+            Instruction::Lmul => long_op(frame, i64::wrapping_mul)?,
+            Instruction::Ldiv | Instruction::Lrem => {
+                let value2 = frame.pop_long()?;
+                let value1 = frame.pop_long()?;
+                if value2 == 0 {
+                    return Err(division_by_zero());
+                }
+                let result = match instr {
+                    Instruction::Ldiv => value1.wrapping_div(value2),
+                    _ => value1.wrapping_rem(value2),
+                };
+                frame.push(Value::Long(result))?;
+            }
+            Instruction::Lneg => {
+                let value = frame.pop_long()?;
+                frame.push(Value::Long(value.wrapping_neg()))?;
+            }
+            Instruction::Lshl | Instruction::Lshr | Instruction::Lushr => {
+                let shift = frame.pop_int()? as u32;
+                let value = frame.pop_long()?;
+                let result = match instr {
+                    Instruction::Lshl => value.wrapping_shl(shift),
+                    Instruction::Lshr => value.wrapping_shr(shift),
+                    _ => (value as u64).wrapping_shr(shift) as i64,
+                };
+                frame.push(Value::Long(result))?;
+            }
+            Instruction::Land => long_op(frame, |value1, value2| value1 & value2)?,
+            Instruction::Lor => long_op(frame, |value1, value2| value1 | value2)?,
+            Instruction::Lxor => long_op(frame, |value1, value2| value1 ^ value2)?,
+
+            Instruction::Fadd => float_op(frame, |value1, value2| value1 + value2)?,
+            Instruction::Fsub => float_op(frame, |value1, value2| value1 - value2)?,
+            Instruction::Fmul => float_op(frame, |value1, value2| value1 * value2)?,
+            Instruction::Fdiv => float_op(frame, |value1, value2| value1 / value2)?,
+            Instruction::Frem => float_op(frame, |value1, value2| value1 % value2)?,
+            Instruction::Fneg => {
+                let value = frame.pop_float()?;
+                frame.push(Value::Float(-value))?;
+            }
+
+            Instruction::Dadd => double_op(frame, |value1, value2| value1 + value2)?,
+            Instruction::Dsub => double_op(frame, |value1, value2| value1 - value2)?,
+            Instruction::Dmul => double_op(frame, |value1, value2| value1 * value2)?,
+            Instruction::Ddiv => double_op(frame, |value1, value2| value1 / value2)?,
+            Instruction::Drem => double_op(frame, |value1, value2| value1 % value2)?,
+            Instruction::Dneg => {
+                let value = frame.pop_double()?;
+                frame.push(Value::Double(-value))?;
+            }
+
+            // Rust's float to integer casts saturate and map NaN to 0, like Java's
+            Instruction::I2l => {
+                let value = frame.pop_int()?;
+                frame.push(Value::Long(value as i64))?;
+            }
+            Instruction::I2f => {
+                let value = frame.pop_int()?;
+                frame.push(Value::Float(value as f32))?;
+            }
+            Instruction::I2d => {
+                let value = frame.pop_int()?;
+                frame.push(Value::Double(value as f64))?;
+            }
+            Instruction::L2i => {
+                let value = frame.pop_long()?;
+                frame.push(Value::Int(value as i32))?;
+            }
+            Instruction::L2f => {
+                let value = frame.pop_long()?;
+                frame.push(Value::Float(value as f32))?;
+            }
+            Instruction::L2d => {
+                let value = frame.pop_long()?;
+                frame.push(Value::Double(value as f64))?;
+            }
+            Instruction::F2i => {
+                let value = frame.pop_float()?;
+                frame.push(Value::Int(value as i32))?;
+            }
+            Instruction::F2l => {
+                let value = frame.pop_float()?;
+                frame.push(Value::Long(value as i64))?;
+            }
+            Instruction::F2d => {
+                let value = frame.pop_float()?;
+                frame.push(Value::Double(value as f64))?;
+            }
+            Instruction::D2i => {
+                let value = frame.pop_double()?;
+                frame.push(Value::Int(value as i32))?;
+            }
+            Instruction::D2l => {
+                let value = frame.pop_double()?;
+                frame.push(Value::Long(value as i64))?;
+            }
+            Instruction::D2f => {
+                let value = frame.pop_double()?;
+                frame.push(Value::Float(value as f32))?;
+            }
+            Instruction::I2b => {
+                let value = frame.pop_int()?;
+                frame.push(Value::Int(value as i8 as i32))?;
+            }
+            Instruction::I2c => {
+                let value = frame.pop_int()?;
+                frame.push(Value::Int(value as u16 as i32))?;
+            }
+            Instruction::I2s => {
+                let value = frame.pop_int()?;
+                frame.push(Value::Int(value as i16 as i32))?;
+            }
+
+            Instruction::Lcmp => {
+                let value2 = frame.pop_long()?;
+                let value1 = frame.pop_long()?;
+                frame.push(Value::Int(value1.cmp(&value2) as i32))?;
+            }
+            Instruction::Fcmpl | Instruction::Fcmpg => {
+                let value2 = frame.pop_float()?;
+                let value1 = frame.pop_float()?;
+                let nan = if matches!(instr, Instruction::Fcmpl) {
+                    -1
+                } else {
+                    1
+                };
+                let result = value1.partial_cmp(&value2).map_or(nan, |ord| ord as i32);
+                frame.push(Value::Int(result))?;
+            }
+            Instruction::Dcmpl | Instruction::Dcmpg => {
+                let value2 = frame.pop_double()?;
+                let value1 = frame.pop_double()?;
+                let nan = if matches!(instr, Instruction::Dcmpl) {
+                    -1
+                } else {
+                    1
+                };
+                let result = value1.partial_cmp(&value2).map_or(nan, |ord| ord as i32);
+                frame.push(Value::Int(result))?;
+            }
+
+            Instruction::Ifeq(target)
+            | Instruction::Ifne(target)
+            | Instruction::Iflt(target)
+            | Instruction::Ifge(target)
+            | Instruction::Ifgt(target)
+            | Instruction::Ifle(target) => {
+                let value = frame.pop_int()?;
+                let taken = match instr {
+                    Instruction::Ifeq(_) => value == 0,
+                    Instruction::Ifne(_) => value != 0,
+                    Instruction::Iflt(_) => value < 0,
+                    Instruction::Ifge(_) => value >= 0,
+                    Instruction::Ifgt(_) => value > 0,
+                    _ => value <= 0,
+                };
+                if taken {
+                    jump(frame, code, *target)?;
+                }
+            }
+            Instruction::IfIcmpeq(target)
+            | Instruction::IfIcmpne(target)
+            | Instruction::IfIcmplt(target)
+            | Instruction::IfIcmpge(target)
+            | Instruction::IfIcmpgt(target)
+            | Instruction::IfIcmple(target) => {
+                let value2 = frame.pop_int()?;
+                let value1 = frame.pop_int()?;
+                let taken = match instr {
+                    Instruction::IfIcmpeq(_) => value1 == value2,
+                    Instruction::IfIcmpne(_) => value1 != value2,
+                    Instruction::IfIcmplt(_) => value1 < value2,
+                    Instruction::IfIcmpge(_) => value1 >= value2,
+                    Instruction::IfIcmpgt(_) => value1 > value2,
+                    _ => value1 <= value2,
+                };
+                if taken {
+                    jump(frame, code, *target)?;
+                }
+            }
+            Instruction::IfAcmpeq(target) | Instruction::IfAcmpne(target) => {
+                let value2 = frame.pop_reference()?;
+                let value1 = frame.pop_reference()?;
+                if (value1 == value2) == matches!(instr, Instruction::IfAcmpeq(_)) {
+                    jump(frame, code, *target)?;
+                }
+            }
+            Instruction::Ifnull(target) | Instruction::Ifnonnull(target) => {
+                let value = frame.pop_reference()?;
+                if (value == Value::Null) == matches!(instr, Instruction::Ifnull(_)) {
+                    jump(frame, code, *target)?;
+                }
+            }
+            Instruction::Goto(target) | Instruction::GotoW(target) => jump(frame, code, *target)?,
+            Instruction::Tableswitch {
+                default,
+                low,
+                high,
+                targets,
+            } => {
+                let index = frame.pop_int()?;
+                let target = if (*low..=*high).contains(&index) {
+                    targets[(index as i64 - *low as i64) as usize]
+                } else {
+                    *default
+                };
+                jump(frame, code, target)?;
+            }
+            Instruction::Lookupswitch { default, pairs } => {
+                let key = frame.pop_int()?;
+                let target = pairs
+                    .iter()
+                    .find(|(match_, _)| *match_ == key)
+                    .map_or(*default, |(_, target)| *target);
+                jump(frame, code, target)?;
+            }
+
+            Instruction::Ireturn => {
+                return Ok(Completion::Return(Some(Value::Int(frame.pop_int()?))))
+            }
+            Instruction::Lreturn => {
+                return Ok(Completion::Return(Some(Value::Long(frame.pop_long()?))))
+            }
+            Instruction::Freturn => {
+                return Ok(Completion::Return(Some(Value::Float(frame.pop_float()?))))
+            }
+            Instruction::Dreturn => {
+                return Ok(Completion::Return(Some(Value::Double(frame.pop_double()?))))
+            }
+            Instruction::Areturn => return Ok(Completion::Return(Some(frame.pop_reference()?))),
+            Instruction::Return => return Ok(Completion::Return(None)),
+
+            Instruction::Invokestatic(index) => {
+                let (class, name, descriptor) = cp.member_ref(*index as usize)?;
+                let target = self.find_method(class, name, descriptor)?;
+                if !target.is_static() {
+                    return Err(VmError::IncompatibleClassChange(format!(
+                        "{class}.{name}:{descriptor} is not static"
+                    )));
+                }
+                self.initialize(target.class)?;
+                let args = self.frame().pop_args(target.arg_count())?;
+                self.call(target, args)?;
+            }
+            Instruction::Invokespecial(index) => {
+                let (class, name, descriptor) = cp.member_ref(*index as usize)?;
+                let resolved = self.find_method(class, name, descriptor)?;
+                let class = self.resolve_class(class)?;
+                // super.m() runs the method the superclass of the caller selects,
+                // constructors, private methods and interface methods run as resolved
+                let current = self.classes[method.class].super_class;
+                let target = match current {
+                    Some(super_class)
+                        if name != "<init>"
+                            && !resolved.is_private()
+                            && !self.classes[class].is_interface()
+                            && self.is_subclass(super_class, class) =>
+                    {
+                        self.select_method(super_class, &resolved)?
+                    }
+                    _ if resolved.is_abstract() => {
+                        return Err(self.abstract_method_error(method.class, &resolved))
+                    }
+                    _ => resolved,
+                };
+                let args = self.frame().pop_args(target.arg_count())?;
+                if args[0].as_object()?.is_none() {
+                    return Err(VmError::null_pointer());
+                }
+                self.call(target, args)?;
+            }
+            Instruction::Invokevirtual(index) => {
+                let (class, name, descriptor) = cp.member_ref(*index as usize)?;
+                let resolved = self.find_method(class, name, descriptor)?;
+                if resolved.is_static() {
+                    return Err(VmError::IncompatibleClassChange(format!(
+                        "{class}.{name}:{descriptor} is static"
+                    )));
+                }
+                let args = self.frame().pop_args(resolved.arg_count())?;
+                let receiver = args[0].as_object()?.ok_or_else(VmError::null_pointer)?;
+                let receiver = self.heap.get(receiver).class;
+                let target = self.select_method(receiver, &resolved)?;
+                self.call(target, args)?;
+            }
+            Instruction::Invokeinterface(index, _) => {
+                let (interface, name, descriptor) = cp.member_ref(*index as usize)?;
+                let resolved = self.find_method(interface, name, descriptor)?;
+                if resolved.is_static() {
+                    return Err(VmError::IncompatibleClassChange(format!(
+                        "{interface}.{name}:{descriptor} is static"
+                    )));
+                }
+                let interface = self.resolve_class(interface)?;
+                let args = self.frame().pop_args(resolved.arg_count())?;
+                let receiver = args[0].as_object()?.ok_or_else(VmError::null_pointer)?;
+                let receiver = self.heap.get(receiver).class;
+                // methods of java/lang/Object can be invoked through any interface
+                if self.classes[interface].is_interface()
+                    && !self.classes[receiver].all_interfaces.contains(&interface)
+                {
+                    return Err(VmError::exception(
+                        "java/lang/IncompatibleClassChangeError",
+                        Some(format!(
+                            "Class {} does not implement the requested interface {}",
+                            self.classes[receiver].name.replace('/', "."),
+                            self.classes[interface].name.replace('/', "."),
+                        )),
+                    ));
+                }
+                let target = self.select_method(receiver, &resolved)?;
+                self.call(target, args)?;
+            }
+            Instruction::Invokedynamic(index) => {
+                let call_site = self.call_site(method, *index)?;
+                let args = self.frame().pop_args(call_site.params.len())?;
+                let result = self.invoke_call_site(&call_site, args)?;
+                self.frame().push(result)?;
+            }
+
+            Instruction::Getstatic(index) => {
+                let (class, name, _) = cp.member_ref(*index as usize)?;
+                let owner = self.find_static_field(class, name)?;
+                self.initialize(owner)?;
+                let value = self.classes[owner].statics[name];
+                self.frame().push(value)?;
+            }
+            Instruction::Putstatic(index) => {
+                let (class, name, descriptor) = cp.member_ref(*index as usize)?;
+                let owner = self.find_static_field(class, name)?;
+                self.initialize(owner)?;
+                let value = narrow(descriptor, self.frame().pop()?)?;
+                let name = name.to_string();
+                self.classes[owner].statics.insert(name, value);
+            }
+            Instruction::Getfield(index) => {
+                let (class, name, _) = cp.member_ref(*index as usize)?;
+                let object = frame.pop_reference()?;
+                let slot = self.find_field(class, name)?;
+                let value = self.object(object)?.fields[slot];
+                self.frame().push(value)?;
+            }
+            Instruction::Putfield(index) => {
+                let (class, name, descriptor) = cp.member_ref(*index as usize)?;
+                let value = narrow(descriptor, frame.pop()?)?;
+                let object = frame.pop_reference()?;
+                let slot = self.find_field(class, name)?;
+                self.object_mut(object)?.fields[slot] = value;
+            }
+
+            Instruction::New(index) => {
+                let class = cp.class_name(*index as usize)?.to_string();
+                let id = self.resolve_class(&class)?;
+                if self.classes[id].is_abstract() || self.classes[id].is_interface() {
+                    return Err(VmError::exception(
+                        "java/lang/InstantiationError",
+                        Some(class.replace('/', ".")),
+                    ));
+                }
+                self.initialize(id)?;
+                let value = self.new_object(&class, ObjectData::Instance)?;
+                self.frame().push(value)?;
+            }
+            Instruction::Newarray(atype) => {
+                let length = frame.pop_int()?;
+                let array = self.new_array(Array::class_name(atype), length)?;
+                self.frame().push(array)?;
+            }
+            Instruction::Anewarray(index) => {
+                let component = cp.class_name(*index as usize)?;
+                let class = match component.starts_with('[') {
+                    true => format!("[{component}"),
+                    false => format!("[L{component};"),
+                };
+                let length = frame.pop_int()?;
+                let array = self.new_array(&class, length)?;
+                self.frame().push(array)?;
+            }
+            Instruction::Multianewarray(index, dimensions) => {
+                let class = cp.class_name(*index as usize)?.to_string();
+                let lengths = frame
+                    .pop_args(*dimensions as usize)?
+                    .iter()
+                    .map(Value::as_int)
+                    .collect::<Result<Vec<_>, _>>()?;
+                let array = self.new_multi_array(&class, &lengths)?;
+                self.frame().push(array)?;
+            }
+            Instruction::Athrow => {
+                let exception = frame.pop_reference()?;
+                let exception = exception.as_object()?.ok_or_else(VmError::null_pointer)?;
+                return Err(VmError::Throw(exception));
+            }
+            Instruction::Checkcast(index) => {
+                let target = cp.class_name(*index as usize)?.to_string();
+                let value = frame.pop_reference()?;
+                if let Value::Ref(obj) = value {
+                    let class = self.heap.get(obj).class;
+                    if !self.is_assignable(class, &target)? {
+                        return Err(VmError::exception(
+                            "java/lang/ClassCastException",
+                            Some(format!(
+                                "class {} cannot be cast to class {}",
+                                self.classes[class].name.replace('/', "."),
+                                target.replace('/', "."),
+                            )),
+                        ));
+                    }
+                }
+                self.frame().push(value)?;
+            }
+            Instruction::Instanceof(index) => {
+                let target = cp.class_name(*index as usize)?.to_string();
+                let is_instance = match frame.pop_reference()? {
+                    Value::Ref(obj) => {
+                        let class = self.heap.get(obj).class;
+                        self.is_assignable(class, &target)?
+                    }
+                    _ => false,
+                };
+                self.frame().push(Value::Int(is_instance as i32))?;
+            }
+            Instruction::Monitorenter => {
+                let obj = frame.pop_reference()?;
+                let monitor = obj.as_object()?.ok_or_else(VmError::null_pointer)?;
+                let monitor = MonitorKey::Object(monitor);
+                match self.can_enter(monitor) {
+                    true => self.enter_monitor(monitor),
+                    false => self.block(monitor, vec![obj])?,
+                }
+            }
+            Instruction::Monitorexit => {
+                let obj = frame.pop_reference()?;
+                let monitor = obj.as_object()?.ok_or_else(VmError::null_pointer)?;
+                self.exit_monitor(MonitorKey::Object(monitor))?;
+            }
+            Instruction::Arraylength => {
+                let array = frame.pop_reference()?;
+                let length = self.array(array)?.len();
+                self.frame().push(Value::Int(length as i32))?;
+            }
+
+            _ => {
+                return Err(VmError::UnsupportedInstruction {
+                    mnemonic: instr.mnemonic(),
+                    pc,
+                })
+            }
+        }
+        Ok(Completion::Next)
+    }
+}
+
+fn division_by_zero() -> VmError {
+    VmError::exception(
+        "java/lang/ArithmeticException",
+        Some("/ by zero".to_string()),
+    )
+}
+
+/// Narrows an int stored to a boolean, byte, char or short field, like `bastore`,
+/// `castore` and `sastore` do for array elements
+fn narrow(descriptor: &str, value: Value) -> Result<Value, VmError> {
+    let narrowed = match descriptor {
+        "Z" => value.as_int()? & 1,
+        "B" => value.as_int()? as i8 as i32,
+        "C" => value.as_int()? as u16 as i32,
+        "S" => value.as_int()? as i16 as i32,
+        _ => return Ok(value),
+    };
+    Ok(Value::Int(narrowed))
+}
+
+fn load(frame: &mut Frame, index: usize, kind: &'static str) -> Result<(), VmError> {
+    let value = frame.load(index, kind)?;
+    frame.push(value)
+}
+
+fn store(frame: &mut Frame, index: usize, kind: &'static str) -> Result<(), VmError> {
+    let value = frame.pop()?;
+    if value.type_name() != kind {
+        return Err(value.mismatch(kind));
+    }
+    frame.store(index, value)
+}
+
+fn iinc(frame: &mut Frame, index: usize, constant: i32) -> Result<(), VmError> {
+    let value = frame.load(index, "int")?.as_int()?;
+    frame.store(index, Value::Int(value.wrapping_add(constant)))
+}
+
+/// Duplicates the values in the top `slots` slots and inserts the copy below the
+/// `under` slots beneath them, which covers all six forms of `dup`
+fn dup(frame: &mut Frame, slots: usize, under: usize) -> Result<(), VmError> {
+    let top = frame.pop_slots(slots)?;
+    let below = frame.pop_slots(under)?;
+    for value in top.iter().chain(&below).chain(&top) {
+        frame.push(*value)?;
+    }
+    Ok(())
+}
+
+fn jump(frame: &mut Frame, code: &Code, target: u32) -> Result<(), VmError> {
+    frame.pc = code
+        .index_of(target)
+        .ok_or(VmError::InvalidBranchTarget(target))?;
+    Ok(())
+}
+
+fn int_op(frame: &mut Frame, op: impl Fn(i32, i32) -> i32) -> Result<(), VmError> {
+    let value2 = frame.pop_int()?;
+    let value1 = frame.pop_int()?;
+    frame.push(Value::Int(op(value1, value2)))
+}
+
+fn long_op(frame: &mut Frame, op: impl Fn(i64, i64) -> i64) -> Result<(), VmError> {
+    let value2 = frame.pop_long()?;
+    let value1 = frame.pop_long()?;
+    frame.push(Value::Long(op(value1, value2)))
+}
+
+fn float_op(frame: &mut Frame, op: impl Fn(f32, f32) -> f32) -> Result<(), VmError> {
+    let value2 = frame.pop_float()?;
+    let value1 = frame.pop_float()?;
+    frame.push(Value::Float(op(value1, value2)))
+}
+
+fn double_op(frame: &mut Frame, op: impl Fn(f64, f64) -> f64) -> Result<(), VmError> {
+    let value2 = frame.pop_double()?;
+    let value1 = frame.pop_double()?;
+    frame.push(Value::Double(op(value1, value2)))
+}
@@ -0,0 +1,353 @@
+use std::rc::Rc;
+
+use super::{
+    builtins,
+    class::{ClassId, RuntimeMethod},
+    error::VmError,
+    heap::ObjectData,
+    lambda,
+    value::Value,
+    vm::Vm,
+};
+use crate::parsers::{
+    attributes::Attr,
+    constantpool::{Constant, ConstantPool},
+    descriptor::{FieldType, MethodDescriptor},
+};
+
+/// Reference kinds of MethodHandle entries
+pub const REF_GET_FIELD: u8 = 1;
+pub const REF_GET_STATIC: u8 = 2;
+pub const REF_PUT_FIELD: u8 = 3;
+pub const REF_PUT_STATIC: u8 = 4;
+pub const REF_INVOKE_VIRTUAL: u8 = 5;
+pub const REF_INVOKE_STATIC: u8 = 6;
+pub const REF_INVOKE_SPECIAL: u8 = 7;
+pub const REF_NEW_INVOKE_SPECIAL: u8 = 8;
+pub const REF_INVOKE_INTERFACE: u8 = 9;
+
+/// Flags of `LambdaMetafactory.altMetafactory`
+const FLAG_MARKERS: i32 = 1 << 1;
+const FLAG_BRIDGES: i32 = 1 << 2;
+
+/// Recipe tags of `StringConcatFactory.makeConcatWithConstants`
+const TAG_ARGUMENT: u16 = 1;
+const TAG_CONSTANT: u16 = 2;
+
+/// A resolved MethodHandle entry: the field or method it reads, writes or invokes
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodHandle {
+    pub kind: u8,
+    pub class: String,
+    pub name: String,
+    /// A field descriptor for the field kinds, a method descriptor for the others
+    pub descriptor: String,
+}
+
+/// An `invokedynamic` instruction linked by one of the built-in bootstrap methods
+#[derive(Debug)]
+pub(crate) struct CallSite {
+    /// Types of the arguments the instruction pops
+    pub params: Vec<FieldType>,
+    pub target: Target,
+}
+
+/// What a call site does with its arguments
+#[derive(Debug)]
+pub(crate) enum Target {
+    /// `LambdaMetafactory`: creates an instance of the lambda class, which keeps the
+    /// arguments in its fields
+    Lambda(ClassId),
+    /// `StringConcatFactory`: concatenates the parts into a new String
+    Concat(Vec<ConcatPart>),
+}
+
+#[derive(Debug)]
+pub(crate) enum ConcatPart {
+    Text(Vec<u16>),
+    /// The argument at this index, converted like `String.valueOf` does
+    Argument(usize),
+}
+
+/// A static argument of a bootstrap method
+#[derive(Debug, Clone)]
+pub(crate) enum BootstrapArgument {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    /// The UTF-16 code units of a String entry, unpaired surrogates included
+    String(Vec<u16>),
+    Class(String),
+    MethodType(String),
+    MethodHandle(MethodHandle),
+}
+
+impl Vm {
+    /// The call site of the `invokedynamic` of `method` naming the InvokeDynamic
+    /// entry `index`, linked the first time it's executed. Instructions naming the
+    /// same entry share their call site, which behaves the same for all of them.
+    pub(crate) fn call_site(
+        &mut self,
+        method: &RuntimeMethod,
+        index: u16,
+    ) -> Result<Rc<CallSite>, VmError> {
+        if let Some(call_site) = self.call_sites.get(&(method.class, index)) {
+            return Ok(Rc::clone(call_site));
+        }
+        let call_site = Rc::new(self.link_call_site(method, index)?);
+        self.call_sites
+            .insert((method.class, index), Rc::clone(&call_site));
+        Ok(call_site)
+    }
+
+    fn link_call_site(&mut self, method: &RuntimeMethod, index: u16) -> Result<CallSite, VmError> {
+        let file = Rc::clone(&method.class_file);
+        let cp = &file.constant_pool;
+        let (bootstrap_index, name, descriptor) = cp.invoke_dynamic(index as usize)?;
+        let signature = MethodDescriptor::parse(descriptor)
+            .ok_or_else(|| VmError::InvalidDescriptor(descriptor.to_string()))?;
+        let bootstrap = file
+            .attrs
+            .iter()
+            .find_map(|attr| match attr {
+                Attr::BootstrapMethods { methods, .. } => methods.get(bootstrap_index as usize),
+                _ => None,
+            })
+            .ok_or_else(|| {
+                bootstrap_method_error(format!(
+                    "{} has no bootstrap method {bootstrap_index}",
+                    method.class_name.replace('/', ".")
+                ))
+            })?;
+        let (_, class, bootstrap_name, _) = cp.method_handle(bootstrap.method_ref as usize)?;
+        let args = bootstrap
+            .arguments
+            .iter()
+            .map(|arg| bootstrap_argument(cp, *arg as usize))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let target = match (class, bootstrap_name) {
+            ("java/lang/invoke/LambdaMetafactory", "metafactory" | "altMetafactory") => {
+                Target::Lambda(self.spin_lambda(method, name, &signature, &args)?)
+            }
+            ("java/lang/invoke/StringConcatFactory", "makeConcatWithConstants") => {
+                Target::Concat(self.concat_recipe(&args)?)
+            }
+            ("java/lang/invoke/StringConcatFactory", "makeConcat") => Target::Concat(
+                (0..signature.params.len())
+                    .map(ConcatPart::Argument)
+                    .collect(),
+            ),
+            _ => {
+                return Err(bootstrap_method_error(format!(
+                    "Bootstrap method {}.{bootstrap_name} is not supported",
+                    class.replace('/', ".")
+                )))
+            }
+        };
+        Ok(CallSite {
+            params: signature.params,
+            target,
+        })
+    }
+
+    /// Invokes the target of `call_site` with the arguments popped by `invokedynamic`
+    pub(crate) fn invoke_call_site(
+        &mut self,
+        call_site: &CallSite,
+        args: Vec<Value>,
+    ) -> Result<Value, VmError> {
+        match &call_site.target {
+            Target::Lambda(class) => {
+                let name = self.classes[*class].name.clone();
+                let lambda = self.new_object(&name, ObjectData::Instance)?;
+                // the captured arguments are the only fields of lambda classes
+                self.object_mut(lambda)?.fields = args;
+                Ok(lambda)
+            }
+            Target::Concat(parts) => {
+                // the arguments are off the operand stack, so nothing may be collected
+                // while toString() runs on them
+                self.native_depth += 1;
+                let units = self.concat(parts, &call_site.params, &args);
+                self.native_depth -= 1;
+                self.new_object("java/lang/String", ObjectData::Chars(units?))
+            }
+        }
+    }
+
+    fn concat(
+        &mut self,
+        parts: &[ConcatPart],
+        params: &[FieldType],
+        args: &[Value],
+    ) -> Result<Vec<u16>, VmError> {
+        let mut units = vec![];
+        for part in parts {
+            match part {
+                ConcatPart::Text(text) => units.extend_from_slice(text),
+                ConcatPart::Argument(index) => {
+                    units.extend(builtins::value_of(self, args[*index], &params[*index])?)
+                }
+            }
+        }
+        Ok(units)
+    }
+
+    /// Splits the recipe of `makeConcatWithConstants` into its parts, the constants
+    /// following the recipe are inlined
+    fn concat_recipe(&mut self, args: &[BootstrapArgument]) -> Result<Vec<ConcatPart>, VmError> {
+        let Some(BootstrapArgument::String(recipe)) = args.first() else {
+            return Err(bootstrap_method_error(
+                "makeConcatWithConstants expects a String recipe".to_string(),
+            ));
+        };
+        let mut constants = args[1..].iter();
+        let mut parts = vec![];
+        let mut text = vec![];
+        let mut argument = 0;
+        for &unit in recipe {
+            match unit {
+                TAG_ARGUMENT => {
+                    if !text.is_empty() {
+                        parts.push(ConcatPart::Text(std::mem::take(&mut text)));
+                    }
+                    parts.push(ConcatPart::Argument(argument));
+                    argument += 1;
+                }
+                TAG_CONSTANT => {
+                    let (value, field_type) = match constants.next() {
+                        Some(BootstrapArgument::String(constant)) => {
+                            text.extend(constant);
+                            continue;
+                        }
+                        Some(BootstrapArgument::Int(value)) => (Value::Int(*value), FieldType::Int),
+                        Some(BootstrapArgument::Long(value)) => {
+                            (Value::Long(*value), FieldType::Long)
+                        }
+                        Some(BootstrapArgument::Float(value)) => {
+                            (Value::Float(*value), FieldType::Float)
+                        }
+                        Some(BootstrapArgument::Double(value)) => {
+                            (Value::Double(*value), FieldType::Double)
+                        }
+                        _ => {
+                            return Err(bootstrap_method_error(format!(
+                                "Missing or unsupported constant for recipe {:?}",
+                                String::from_utf16_lossy(recipe)
+                            )))
+                        }
+                    };
+                    text.extend(builtins::value_of(self, value, &field_type)?);
+                }
+                unit => text.push(unit),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(ConcatPart::Text(text));
+        }
+        Ok(parts)
+    }
+
+    /// Defines the class of the lambdas created by a `LambdaMetafactory` call site
+    /// named `name` with type `factory`, returning its id
+    fn spin_lambda(
+        &mut self,
+        caller: &RuntimeMethod,
+        name: &str,
+        factory: &MethodDescriptor,
+        args: &[BootstrapArgument],
+    ) -> Result<ClassId, VmError> {
+        let (
+            Some(BootstrapArgument::MethodType(erased)),
+            Some(BootstrapArgument::MethodHandle(implementation)),
+            Some(BootstrapArgument::MethodType(_)),
+        ) = (args.first(), args.get(1), args.get(2))
+        else {
+            return Err(bootstrap_method_error(
+                "LambdaMetafactory expects a MethodType, a MethodHandle and a MethodType"
+                    .to_string(),
+            ));
+        };
+        let Some(FieldType::Object(interface)) = &factory.ret else {
+            return Err(bootstrap_method_error(format!(
+                "Lambdas implement an interface, not {:?}",
+                factory.ret
+            )));
+        };
+        let mut interfaces = vec![interface.clone()];
+        let mut descriptors = vec![erased.clone()];
+        // altMetafactory: flags, then the marker interfaces and bridge descriptors
+        if let Some(BootstrapArgument::Int(flags)) = args.get(3) {
+            let mut rest = args[4..].iter();
+            let mut counted = |flag: i32| -> Vec<BootstrapArgument> {
+                match (flags & flag != 0, rest.next()) {
+                    (true, Some(BootstrapArgument::Int(count))) => {
+                        rest.by_ref().take(*count as usize).cloned().collect()
+                    }
+                    _ => vec![],
+                }
+            };
+            for marker in counted(FLAG_MARKERS) {
+                if let BootstrapArgument::Class(marker) = marker {
+                    interfaces.push(marker);
+                }
+            }
+            for bridge in counted(FLAG_BRIDGES) {
+                if let BootstrapArgument::MethodType(bridge) = bridge {
+                    descriptors.push(bridge);
+                }
+            }
+        }
+
+        let class_name = format!("{}$$Lambda${}", caller.class_name, self.classes.len());
+        let file = lambda::lambda_class_file(
+            &class_name,
+            interfaces,
+            &factory.params,
+            name,
+            &descriptors,
+            implementation,
+        )?;
+        let id = self.add_class(file)?;
+        self.classes[id].hidden = true;
+        Ok(id)
+    }
+}
+
+fn bootstrap_argument(cp: &ConstantPool, index: usize) -> Result<BootstrapArgument, VmError> {
+    Ok(match cp.get(index)? {
+        Constant::Integer(value) => BootstrapArgument::Int(*value),
+        Constant::Long(value) => BootstrapArgument::Long(*value),
+        Constant::Float(value) => BootstrapArgument::Float(*value),
+        Constant::Double(value) => BootstrapArgument::Double(*value),
+        Constant::String { .. } => BootstrapArgument::String(cp.string_entry(index)?.units()),
+        Constant::Class { .. } => BootstrapArgument::Class(cp.class_name(index)?.to_string()),
+        Constant::MethodType { .. } => {
+            BootstrapArgument::MethodType(cp.method_type(index)?.to_string())
+        }
+        Constant::MethodHandle { .. } => BootstrapArgument::MethodHandle(method_handle(cp, index)?),
+        constant => {
+            return Err(bootstrap_method_error(format!(
+                "Unsupported bootstrap argument {}",
+                constant.tag_name()
+            )))
+        }
+    })
+}
+
+/// The MethodHandle entry at `index`
+fn method_handle(cp: &ConstantPool, index: usize) -> Result<MethodHandle, VmError> {
+    let (kind, class, name, descriptor) = cp.method_handle(index)?;
+    Ok(MethodHandle {
+        kind,
+        class: class.to_string(),
+        name: name.to_string(),
+        descriptor: descriptor.to_string(),
+    })
+}
+
+fn bootstrap_method_error(message: String) -> VmError {
+    VmError::exception("java/lang/BootstrapMethodError", Some(message))
+}
@@ -0,0 +1,378 @@
+//! The classes `LambdaMetafactory` call sites create instances of. Each one is a
+//! final class implementing the functional interface, keeping the captured
+//! arguments in its fields and forwarding calls of the interface method to the
+//! implementation method with the arguments converted to its parameter types.
+
+use super::{
+    error::VmError,
+    invokedynamic::{
+        MethodHandle, REF_INVOKE_INTERFACE, REF_INVOKE_SPECIAL, REF_INVOKE_STATIC,
+        REF_INVOKE_VIRTUAL, REF_NEW_INVOKE_SPECIAL,
+    },
+};
+use crate::parsers::{
+    attributes::Attr,
+    bytecode::Instruction,
+    class::JavaClassFile,
+    constantpool::{Constant, ConstantPool, Utf8},
+    descriptor::{FieldType, MethodDescriptor},
+    field::Field,
+    method::Method,
+    version::MAGIC,
+};
+use crate::writer::classwriter::write_byte_code;
+
+/// The class file of a lambda class named `name` implementing `interfaces`, the
+/// functional interface first. Its constructor-less instances are created with the
+/// `captured` arguments as fields, and it implements `method` with each of the
+/// `descriptors` by invoking `implementation`.
+pub(crate) fn lambda_class_file(
+    name: &str,
+    interfaces: Vec<String>,
+    captured: &[FieldType],
+    method: &str,
+    descriptors: &[String],
+    implementation: &MethodHandle,
+) -> Result<JavaClassFile, VmError> {
+    let mut pool = PoolBuilder::default();
+    let fields = captured
+        .iter()
+        .enumerate()
+        .map(|(index, field_type)| Field {
+            access_flags: flags(&["ACC_PRIVATE", "ACC_FINAL"]),
+            name: field_name(index),
+            descriptor: descriptor_of(field_type),
+            attrs: vec![],
+            ..Default::default()
+        })
+        .collect();
+    let methods = descriptors
+        .iter()
+        .map(|descriptor| {
+            let code = forward(&mut pool, name, captured, descriptor, implementation)?;
+            Ok(Method {
+                access_flags: flags(&["ACC_PUBLIC"]),
+                name: method.to_string(),
+                descriptor: descriptor.clone(),
+                attrs: vec![code],
+                ..Default::default()
+            })
+        })
+        .collect::<Result<_, VmError>>()?;
+    Ok(JavaClassFile {
+        magic: MAGIC,
+        constant_pool: pool.finish(),
+        access_flags: flags(&["ACC_FINAL", "ACC_SUPER", "ACC_SYNTHETIC"]),
+        this_class: name.to_string(),
+        super_class: "java/lang/Object".to_string(),
+        interfaces,
+        fields,
+        methods,
+        ..Default::default()
+    })
+}
+
+/// The Code attribute of an implementation of the interface method with type
+/// `descriptor`: loads the captured fields and the arguments, invokes the
+/// implementation method and returns its result
+fn forward(
+    pool: &mut PoolBuilder,
+    class: &str,
+    captured: &[FieldType],
+    descriptor: &str,
+    implementation: &MethodHandle,
+) -> Result<Attr, VmError> {
+    let sam = parse(descriptor)?;
+    let target = parse(&implementation.descriptor)?;
+    let mut params = target.params.clone();
+    if !matches!(
+        implementation.kind,
+        REF_INVOKE_STATIC | REF_NEW_INVOKE_SPECIAL
+    ) {
+        params.insert(0, FieldType::Object(implementation.class.clone()));
+    }
+    if captured.len() + sam.params.len() != params.len() {
+        return Err(VmError::exception(
+            "java/lang/BootstrapMethodError",
+            Some(format!(
+                "Incorrect number of parameters for {} method {}.{}{}",
+                kind_name(implementation.kind),
+                implementation.class,
+                implementation.name,
+                implementation.descriptor
+            )),
+        ));
+    }
+
+    let mut code = vec![];
+    if implementation.kind == REF_NEW_INVOKE_SPECIAL {
+        code.push(Instruction::New(pool.class(&implementation.class)));
+        code.push(Instruction::Dup);
+    }
+    for (index, field_type) in captured.iter().enumerate() {
+        code.push(Instruction::Aload0);
+        let field = pool.member(
+            MemberKind::Field,
+            class,
+            &field_name(index),
+            &descriptor_of(field_type),
+        );
+        code.push(Instruction::Getfield(field));
+    }
+    let mut local = 1;
+    for (param, target) in sam.params.iter().zip(&params[captured.len()..]) {
+        code.push(load(param, local));
+        local += param.slots();
+        convert(pool, &mut code, param, target);
+    }
+
+    let kind = match implementation.kind {
+        REF_INVOKE_INTERFACE => MemberKind::InterfaceMethod,
+        _ => MemberKind::Method,
+    };
+    let index = pool.member(
+        kind,
+        &implementation.class,
+        &implementation.name,
+        &implementation.descriptor,
+    );
+    code.push(match implementation.kind {
+        REF_INVOKE_STATIC => Instruction::Invokestatic(index),
+        REF_INVOKE_VIRTUAL => Instruction::Invokevirtual(index),
+        REF_INVOKE_SPECIAL | REF_NEW_INVOKE_SPECIAL => Instruction::Invokespecial(index),
+        REF_INVOKE_INTERFACE => {
+            Instruction::Invokeinterface(index, (1 + target.param_slots()) as u8)
+        }
+        kind => {
+            return Err(VmError::exception(
+                "java/lang/BootstrapMethodError",
+                Some(format!(
+                    "Unsupported MethodHandle kind: {}",
+                    kind_name(kind)
+                )),
+            ))
+        }
+    });
+
+    let result = match implementation.kind {
+        REF_NEW_INVOKE_SPECIAL => Some(FieldType::Object(implementation.class.clone())),
+        _ => target.ret.clone(),
+    };
+    match (&result, &sam.ret) {
+        (Some(result), Some(ret)) => convert(pool, &mut code, result, ret),
+        (Some(FieldType::Long | FieldType::Double), None) => code.push(Instruction::Pop2),
+        (Some(_), None) => code.push(Instruction::Pop),
+        (None, _) => {}
+    }
+    code.push(match &sam.ret {
+        None => Instruction::Return,
+        Some(FieldType::Long) => Instruction::Lreturn,
+        Some(FieldType::Float) => Instruction::Freturn,
+        Some(FieldType::Double) => Instruction::Dreturn,
+        Some(FieldType::Object(_) | FieldType::Array(_)) => Instruction::Areturn,
+        Some(_) => Instruction::Ireturn,
+    });
+
+    let mut pc = 0;
+    let code = code
+        .into_iter()
+        .map(|instr| {
+            let start = pc;
+            pc += write_byte_code(std::slice::from_ref(&(start, instr.clone())))
+                .expect("lambda classes have no branches and small operands")
+                .len() as u32;
+            (start, instr)
+        })
+        .collect();
+    let params_slots: usize = params.iter().map(FieldType::slots).sum();
+    Ok(Attr::Code {
+        name_index: 0,
+        // the implementation's arguments, the new object and its copy, and a
+        // wide value being converted
+        max_stack: (params_slots + 4) as u16,
+        max_locals: local as u16,
+        code_length: pc,
+        code,
+        exception_table: vec![],
+        attrs: vec![],
+    })
+}
+
+/// Appends the instructions converting a value of type `from` on top of the stack
+/// to `to`: a cast for references, boxing or unboxing, or primitive widening
+fn convert(pool: &mut PoolBuilder, code: &mut Vec<Instruction>, from: &FieldType, to: &FieldType) {
+    if from == to {
+        return;
+    }
+    match (wrapper(from), wrapper(to)) {
+        // reference to reference
+        (None, None) => {
+            if *to != FieldType::Object("java/lang/Object".to_string()) {
+                code.push(Instruction::Checkcast(pool.class(&class_of(to))));
+            }
+        }
+        // unboxing
+        (None, Some((wrapper, method))) => {
+            code.push(Instruction::Checkcast(pool.class(wrapper)));
+            let descriptor = format!("(){}", descriptor_of(to));
+            let method = pool.member(MemberKind::Method, wrapper, method, &descriptor);
+            code.push(Instruction::Invokevirtual(method));
+        }
+        // boxing, the box is then assignable to Object, Number or the wrapper
+        (Some((wrapper, _)), None) => {
+            let descriptor = format!("({})L{wrapper};", descriptor_of(from));
+            let method = pool.member(MemberKind::Method, wrapper, "valueOf", &descriptor);
+            code.push(Instruction::Invokestatic(method));
+        }
+        // widening, byte, short and char already are ints on the stack
+        (Some(_), Some(_)) => code.extend(match (from, to) {
+            (FieldType::Long, FieldType::Float) => Some(Instruction::L2f),
+            (FieldType::Long, FieldType::Double) => Some(Instruction::L2d),
+            (FieldType::Float, FieldType::Double) => Some(Instruction::F2d),
+            (FieldType::Long | FieldType::Float | FieldType::Double, _) => None,
+            (_, FieldType::Long) => Some(Instruction::I2l),
+            (_, FieldType::Float) => Some(Instruction::I2f),
+            (_, FieldType::Double) => Some(Instruction::I2d),
+            _ => None,
+        }),
+    }
+}
+
+/// The wrapper class of a primitive type and the method unboxing it
+fn wrapper(field_type: &FieldType) -> Option<(&'static str, &'static str)> {
+    match field_type {
+        FieldType::Boolean => Some(("java/lang/Boolean", "booleanValue")),
+        FieldType::Byte => Some(("java/lang/Byte", "byteValue")),
+        FieldType::Char => Some(("java/lang/Character", "charValue")),
+        FieldType::Short => Some(("java/lang/Short", "shortValue")),
+        FieldType::Int => Some(("java/lang/Integer", "intValue")),
+        FieldType::Long => Some(("java/lang/Long", "longValue")),
+        FieldType::Float => Some(("java/lang/Float", "floatValue")),
+        FieldType::Double => Some(("java/lang/Double", "doubleValue")),
+        FieldType::Object(_) | FieldType::Array(_) => None,
+    }
+}
+
+fn load(field_type: &FieldType, local: usize) -> Instruction {
+    let local = local as u8;
+    match field_type {
+        FieldType::Long => Instruction::Lload(local),
+        FieldType::Float => Instruction::Fload(local),
+        FieldType::Double => Instruction::Dload(local),
+        FieldType::Object(_) | FieldType::Array(_) => Instruction::Aload(local),
+        _ => Instruction::Iload(local),
+    }
+}
+
+fn parse(descriptor: &str) -> Result<MethodDescriptor, VmError> {
+    MethodDescriptor::parse(descriptor)
+        .ok_or_else(|| VmError::InvalidDescriptor(descriptor.to_string()))
+}
+
+fn field_name(index: usize) -> String {
+    format!("arg${}", index + 1)
+}
+
+fn flags(flags: &[&str]) -> Vec<String> {
+    flags.iter().map(|flag| flag.to_string()).collect()
+}
+
+/// The descriptor of a field type, e.g. `I` or `Ljava/lang/String;`
+fn descriptor_of(field_type: &FieldType) -> String {
+    match field_type {
+        FieldType::Byte => "B".to_string(),
+        FieldType::Char => "C".to_string(),
+        FieldType::Double => "D".to_string(),
+        FieldType::Float => "F".to_string(),
+        FieldType::Int => "I".to_string(),
+        FieldType::Long => "J".to_string(),
+        FieldType::Short => "S".to_string(),
+        FieldType::Boolean => "Z".to_string(),
+        FieldType::Object(name) => format!("L{name};"),
+        FieldType::Array(component) => format!("[{}", descriptor_of(component)),
+    }
+}
+
+/// The name a Class entry uses for a reference type, its descriptor for arrays
+fn class_of(field_type: &FieldType) -> String {
+    match field_type {
+        FieldType::Object(name) => name.clone(),
+        field_type => descriptor_of(field_type),
+    }
+}
+
+fn kind_name(kind: u8) -> &'static str {
+    match kind {
+        REF_INVOKE_VIRTUAL => "invokeVirtual",
+        REF_INVOKE_STATIC => "invokeStatic",
+        REF_INVOKE_SPECIAL => "invokeSpecial",
+        REF_NEW_INVOKE_SPECIAL => "newInvokeSpecial",
+        REF_INVOKE_INTERFACE => "invokeInterface",
+        _ => "field access",
+    }
+}
+
+#[derive(Clone, Copy)]
+enum MemberKind {
+    Field,
+    Method,
+    InterfaceMethod,
+}
+
+/// Builds a constant pool, adding each entry once
+#[derive(Default)]
+struct PoolBuilder {
+    constants: Vec<Constant>,
+}
+
+impl PoolBuilder {
+    fn add(&mut self, constant: Constant) -> u16 {
+        let index = match self.constants.iter().position(|c| *c == constant) {
+            Some(index) => index,
+            None => {
+                self.constants.push(constant);
+                self.constants.len() - 1
+            }
+        };
+        (index + 1) as u16
+    }
+
+    fn utf8(&mut self, value: &str) -> u16 {
+        self.add(Constant::Utf8(Utf8::new(value)))
+    }
+
+    fn class(&mut self, name: &str) -> u16 {
+        let name_index = self.utf8(name);
+        self.add(Constant::Class { name_index })
+    }
+
+    fn member(&mut self, kind: MemberKind, class: &str, name: &str, descriptor: &str) -> u16 {
+        let class_index = self.class(class);
+        let name_index = self.utf8(name);
+        let descriptor_index = self.utf8(descriptor);
+        let name_and_type_index = self.add(Constant::NameAndType {
+            name_index,
+            descriptor_index,
+        });
+        self.add(match kind {
+            MemberKind::Field => Constant::Fieldref {
+                class_index,
+                name_and_type_index,
+            },
+            MemberKind::Method => Constant::Methodref {
+                class_index,
+                name_and_type_index,
+            },
+            MemberKind::InterfaceMethod => Constant::InterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            },
+        })
+    }
+
+    fn finish(self) -> ConstantPool {
+        ConstantPool {
+            constants: self.constants,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+mod builtins;
+pub mod class;
+pub mod error;
+pub mod exception;
+pub mod frame;
+pub mod heap;
+pub mod initialization;
+pub mod interpreter;
+pub mod invokedynamic;
+mod lambda;
+pub mod native;
+pub mod thread;
+pub mod value;
+#[allow(clippy::module_inception)]
+pub mod vm;
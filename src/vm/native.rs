@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use super::{error::VmError, value::Value, vm::Vm};
+
+/// A Rust implementation of a native method. Instance methods get `this` as their
+/// first argument.
+pub type NativeMethod = fn(&mut Vm, &[Value]) -> Result<Option<Value>, VmError>;
+
+/// Native method implementations by class, name and descriptor. Methods are bound
+/// when their class is added to the virtual machine.
+#[derive(Default)]
+pub struct NativeRegistry {
+    methods: HashMap<(String, String, String), NativeMethod>,
+}
+
+impl NativeRegistry {
+    pub fn register(&mut self, class: &str, name: &str, descriptor: &str, method: NativeMethod) {
+        self.methods.insert(
+            (class.to_string(), name.to_string(), descriptor.to_string()),
+            method,
+        );
+    }
+
+    pub fn get(&self, class: &str, name: &str, descriptor: &str) -> Option<NativeMethod> {
+        self.methods
+            .get(&(class.to_string(), name.to_string(), descriptor.to_string()))
+            .copied()
+    }
+}
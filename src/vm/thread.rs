@@ -0,0 +1,617 @@
+//! Java threads as green threads: each one has its own frames, and the outermost
+//! interpreter loop switches between them when the running one blocks or has used
+//! up its time slice. Which thread runs when only depends on the program, so
+//! multithreaded programs behave the same on every run.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::Duration;
+
+use super::{
+    builtins,
+    class::{ClassId, RuntimeMethod},
+    error::VmError,
+    frame::Frame,
+    heap::{ObjRef, ObjectData},
+    value::Value,
+    vm::Vm,
+};
+
+/// Index of a thread in the thread table, the main thread is 0
+pub type ThreadId = usize;
+
+const MAIN: ThreadId = 0;
+
+/// Number of instructions a thread executes before the others get a turn. Every
+/// time slice also advances the clock `Thread.sleep` is measured with by a
+/// millisecond, so busy threads can't keep sleeping ones from waking up.
+const TIME_SLICE: usize = 1000;
+
+/// What threads synchronize on: an object, or a class for static synchronized
+/// methods
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MonitorKey {
+    Object(ObjRef),
+    Class(ClassId),
+}
+
+/// A monitor some thread holds
+#[derive(Debug, Clone, Copy)]
+struct Monitor {
+    owner: ThreadId,
+    /// How many times the owner entered it
+    count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThreadState {
+    Runnable,
+    /// Waits to enter a monitor another thread holds, then retries the
+    /// `monitorenter` or invocation of a synchronized method it stopped at
+    Blocked(MonitorKey),
+    /// In the wait set of a monitor until notified, or until `deadline` for timed
+    /// waits
+    Waiting {
+        monitor: MonitorKey,
+        count: usize,
+        deadline: Option<u64>,
+        /// Order of the call to `wait`, notify wakes the longest waiting thread
+        sequence: u64,
+    },
+    /// Notified, takes the monitor back with the count it had before waiting
+    Reacquiring {
+        monitor: MonitorKey,
+        count: usize,
+    },
+    /// In `Thread.join` until the thread terminates, or until `deadline`
+    Joining {
+        thread: ThreadId,
+        deadline: Option<u64>,
+    },
+    /// In `Thread.sleep` until the clock reaches the deadline
+    Sleeping(u64),
+    Terminated,
+}
+
+/// A Java thread
+#[derive(Debug)]
+pub struct JavaThread {
+    /// The java/lang/Thread, null for the main thread until it is asked for
+    pub object: Value,
+    pub state: ThreadState,
+    /// The frames of the thread while another one runs, those of the running
+    /// thread are `Vm::frames`
+    frames: Vec<Frame>,
+}
+
+/// The thread table and the monitors
+#[derive(Debug)]
+pub(crate) struct Threads {
+    threads: Vec<JavaThread>,
+    current: ThreadId,
+    monitors: HashMap<MonitorKey, Monitor>,
+    /// Milliseconds since the virtual machine started, by the clock of `Thread.sleep`
+    clock: u64,
+    /// Instructions left in the time slice of the running thread
+    slice: usize,
+    /// Number of calls to `wait` so far
+    waits: u64,
+    /// Number of threads created without a name, which are named `Thread-N`
+    unnamed: usize,
+    /// Number of interpreter loops running, only the outermost one switches threads
+    pub(crate) nesting: usize,
+}
+
+impl Default for Threads {
+    fn default() -> Self {
+        let main = JavaThread {
+            object: Value::Null,
+            state: ThreadState::Runnable,
+            frames: vec![],
+        };
+        Self {
+            threads: vec![main],
+            current: MAIN,
+            monitors: HashMap::new(),
+            clock: 0,
+            slice: TIME_SLICE,
+            waits: 0,
+            unnamed: 0,
+            nesting: 0,
+        }
+    }
+}
+
+impl Threads {
+    pub(crate) fn current(&self) -> ThreadId {
+        self.current
+    }
+
+    /// The next `Thread-N` name
+    pub(crate) fn next_name(&mut self) -> String {
+        self.unnamed += 1;
+        format!("Thread-{}", self.unnamed - 1)
+    }
+
+    /// The values the threads that aren't running keep alive
+    pub(crate) fn roots(&self) -> impl Iterator<Item = &Value> {
+        self.threads.iter().flat_map(|thread| {
+            let frames = thread
+                .frames
+                .iter()
+                .flat_map(|frame| frame.locals.iter().chain(&frame.stack));
+            std::iter::once(&thread.object).chain(frames)
+        })
+    }
+
+    /// Forgets the monitors of objects that were collected
+    pub(crate) fn retain_monitors(&mut self, is_live: impl Fn(ObjRef) -> bool) {
+        self.monitors.retain(|monitor, _| match monitor {
+            MonitorKey::Object(obj) => is_live(*obj),
+            MonitorKey::Class(_) => true,
+        });
+    }
+
+    fn is_alive(&self, thread: ThreadId) -> bool {
+        self.threads[thread].state != ThreadState::Terminated
+    }
+
+    fn state(&mut self) -> &mut ThreadState {
+        &mut self.threads[self.current].state
+    }
+
+    /// Whether the running thread can enter `monitor` without blocking
+    fn can_enter(&self, monitor: MonitorKey) -> bool {
+        self.monitors
+            .get(&monitor)
+            .is_none_or(|held| held.owner == self.current)
+    }
+
+    fn enter(&mut self, monitor: MonitorKey, count: usize) {
+        let current = self.current;
+        self.monitors
+            .entry(monitor)
+            .or_insert(Monitor {
+                owner: current,
+                count: 0,
+            })
+            .count += count;
+    }
+
+    /// Leaves `monitor` once, false if the running thread doesn't hold it
+    fn exit(&mut self, monitor: MonitorKey) -> bool {
+        match self.monitors.get_mut(&monitor) {
+            Some(held) if held.owner == self.current => {
+                held.count -= 1;
+                if held.count == 0 {
+                    self.monitors.remove(&monitor);
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Makes `thread` runnable if what it waits for happened
+    fn wake(&mut self, thread: ThreadId) -> bool {
+        let clock = self.clock;
+        let expired = |deadline: Option<u64>| deadline.is_some_and(|deadline| clock >= deadline);
+        let state = match self.threads[thread].state {
+            ThreadState::Runnable => return true,
+            ThreadState::Blocked(monitor) => match self.monitors.get(&monitor) {
+                Some(held) if held.owner != thread => return false,
+                _ => ThreadState::Runnable,
+            },
+            ThreadState::Waiting {
+                monitor,
+                count,
+                deadline,
+                ..
+            } if expired(deadline) => {
+                self.threads[thread].state = ThreadState::Reacquiring { monitor, count };
+                return self.wake(thread);
+            }
+            ThreadState::Reacquiring { monitor, count } => {
+                if self.monitors.contains_key(&monitor) {
+                    return false;
+                }
+                self.monitors.insert(
+                    monitor,
+                    Monitor {
+                        owner: thread,
+                        count,
+                    },
+                );
+                ThreadState::Runnable
+            }
+            ThreadState::Joining {
+                thread: joined,
+                deadline,
+            } if !self.is_alive(joined) || expired(deadline) => ThreadState::Runnable,
+            ThreadState::Sleeping(deadline) if clock >= deadline => ThreadState::Runnable,
+            _ => return false,
+        };
+        self.threads[thread].state = state;
+        true
+    }
+
+    /// The earliest time a sleeping thread or a timed wait or join wakes up at
+    fn next_deadline(&self) -> Option<u64> {
+        self.threads
+            .iter()
+            .filter_map(|thread| match thread.state {
+                ThreadState::Sleeping(deadline) => Some(deadline),
+                ThreadState::Waiting { deadline, .. } | ThreadState::Joining { deadline, .. } => {
+                    deadline
+                }
+                _ => None,
+            })
+            .min()
+    }
+}
+
+impl Vm {
+    /// Called by the interpreter loop between instructions, switches to another
+    /// thread when the running one blocked or used up its time slice
+    pub(crate) fn schedule(&mut self) -> Result<(), VmError> {
+        let threads = &mut self.threads;
+        threads.slice = threads.slice.saturating_sub(1);
+        let runnable = *threads.state() == ThreadState::Runnable;
+        if runnable && threads.slice > 0 {
+            return Ok(());
+        }
+        if threads.slice == 0 {
+            threads.clock += 1;
+            threads.slice = TIME_SLICE;
+        }
+        // a loop run by a native or a static initializer can't switch threads, as
+        // the others would then have to be resumed from inside it
+        if threads.nesting > 1 || self.native_depth > 0 {
+            return match *threads.state() {
+                ThreadState::Runnable => Ok(()),
+                ThreadState::Sleeping(deadline) => {
+                    self.advance_clock(deadline);
+                    *self.threads.state() = ThreadState::Runnable;
+                    Ok(())
+                }
+                _ => Err(VmError::BlockedInNestedCall),
+            };
+        }
+        match self.switch_thread()? {
+            true => Ok(()),
+            false => Err(VmError::Deadlock),
+        }
+    }
+
+    /// Saves the frames of the running thread and resumes the next thread that can
+    /// run, in thread order after the running one, waiting for sleeping threads if
+    /// none can. False if every thread has terminated, an error if the live ones
+    /// wait for each other.
+    pub(crate) fn switch_thread(&mut self) -> Result<bool, VmError> {
+        let count = self.threads.threads.len();
+        let current = self.threads.current;
+        loop {
+            let next = (1..=count)
+                .map(|offset| (current + offset) % count)
+                .find(|thread| self.threads.wake(*thread));
+            if let Some(next) = next {
+                if next != current {
+                    let frames = std::mem::take(&mut self.frames);
+                    self.threads.threads[current].frames = frames;
+                    self.frames = std::mem::take(&mut self.threads.threads[next].frames);
+                    self.threads.current = next;
+                }
+                self.threads.slice = TIME_SLICE;
+                return Ok(true);
+            }
+            match self.threads.next_deadline() {
+                Some(deadline) => self.advance_clock(deadline),
+                None if (0..count).any(|thread| self.threads.is_alive(thread)) => {
+                    return Err(VmError::Deadlock)
+                }
+                None => return Ok(false),
+            }
+        }
+    }
+
+    /// Sleeps until the clock reaches `deadline`, when no thread has anything to do
+    fn advance_clock(&mut self, deadline: u64) {
+        if deadline > self.threads.clock {
+            std::thread::sleep(Duration::from_millis(deadline - self.threads.clock));
+            self.threads.clock = deadline;
+        }
+    }
+
+    /// Ends the running thread, which returned from its `run` method or let an
+    /// exception escape it, and switches to another one
+    pub(crate) fn terminate_thread(&mut self) -> Result<bool, VmError> {
+        *self.threads.state() = ThreadState::Terminated;
+        self.switch_thread()
+    }
+
+    /// Runs the threads started by main until they have all terminated, like the
+    /// JVM does before exiting
+    pub(crate) fn run_threads(&mut self) -> Result<(), VmError> {
+        while self.terminate_thread()? {
+            match self.run(0) {
+                Ok(_) => {}
+                Err(VmError::Throw(exception)) => {
+                    self.uncaught(exception);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    /// The monitor a synchronized method invoked with `args` enters, None for other
+    /// methods
+    pub(crate) fn method_monitor(
+        &self,
+        method: &RuntimeMethod,
+        args: &[Value],
+    ) -> Result<Option<MonitorKey>, VmError> {
+        if !method.is_synchronized() {
+            return Ok(None);
+        }
+        if method.is_static() {
+            return Ok(Some(MonitorKey::Class(method.class)));
+        }
+        let this = args[0].as_object()?.ok_or_else(VmError::null_pointer)?;
+        Ok(Some(MonitorKey::Object(this)))
+    }
+
+    pub(crate) fn can_enter(&self, monitor: MonitorKey) -> bool {
+        self.threads.can_enter(monitor)
+    }
+
+    pub(crate) fn enter_monitor(&mut self, monitor: MonitorKey) {
+        self.threads.enter(monitor, 1);
+    }
+
+    /// `monitorexit`, an IllegalMonitorStateException if the running thread doesn't
+    /// hold the monitor
+    pub(crate) fn exit_monitor(&mut self, monitor: MonitorKey) -> Result<(), VmError> {
+        match self.threads.exit(monitor) {
+            true => Ok(()),
+            false => Err(VmError::exception(
+                "java/lang/IllegalMonitorStateException",
+                None,
+            )),
+        }
+    }
+
+    /// Blocks the running thread until it can enter `monitor`. The instruction
+    /// being executed is retried then, so the values it popped are pushed back.
+    pub(crate) fn block(&mut self, monitor: MonitorKey, popped: Vec<Value>) -> Result<(), VmError> {
+        let frame = self.frame();
+        for value in popped {
+            frame.push(value)?;
+        }
+        frame.pc -= 1;
+        *self.threads.state() = ThreadState::Blocked(monitor);
+        Ok(())
+    }
+
+    /// Releases a monitor entered by a frame that is being discarded
+    pub(crate) fn release(&mut self, monitor: MonitorKey) {
+        self.threads.exit(monitor);
+    }
+
+    /// `Object.wait`: releases the monitor of `obj` and waits until notified, or
+    /// for `timeout` milliseconds unless it's 0
+    pub(crate) fn wait(&mut self, obj: Value, timeout: i64) -> Result<(), VmError> {
+        let monitor = self.owned_monitor(obj)?;
+        if timeout < 0 {
+            return Err(VmError::exception(
+                "java/lang/IllegalArgumentException",
+                Some("timeout value is negative".to_string()),
+            ));
+        }
+        let threads = &mut self.threads;
+        let count = threads
+            .monitors
+            .remove(&monitor)
+            .map_or(0, |held| held.count);
+        threads.waits += 1;
+        let state = ThreadState::Waiting {
+            monitor,
+            count,
+            deadline: (timeout > 0).then(|| threads.clock + timeout as u64),
+            sequence: threads.waits,
+        };
+        *threads.state() = state;
+        Ok(())
+    }
+
+    /// `Object.notify` and `Object.notifyAll`: the longest waiting thread, or all of
+    /// them, will take the monitor of `obj` back once the running thread releases it
+    pub(crate) fn notify(&mut self, obj: Value, all: bool) -> Result<(), VmError> {
+        let monitor = self.owned_monitor(obj)?;
+        let mut waiting: Vec<(u64, ThreadId, usize)> = self
+            .threads
+            .threads
+            .iter()
+            .enumerate()
+            .filter_map(|(id, thread)| match thread.state {
+                ThreadState::Waiting {
+                    monitor: waited,
+                    count,
+                    sequence,
+                    ..
+                } if waited == monitor => Some((sequence, id, count)),
+                _ => None,
+            })
+            .collect();
+        waiting.sort_unstable();
+        let notified = match all {
+            true => &waiting[..],
+            false => &waiting[..waiting.len().min(1)],
+        };
+        for (_, thread, count) in notified {
+            self.threads.threads[*thread].state = ThreadState::Reacquiring {
+                monitor,
+                count: *count,
+            };
+        }
+        Ok(())
+    }
+
+    /// The monitor of `obj`, an IllegalMonitorStateException if the running thread
+    /// doesn't hold it
+    fn owned_monitor(&self, obj: Value) -> Result<MonitorKey, VmError> {
+        let obj = obj.as_object()?.ok_or_else(VmError::null_pointer)?;
+        let monitor = MonitorKey::Object(obj);
+        match self.threads.monitors.get(&monitor) {
+            Some(held) if held.owner == self.threads.current => Ok(monitor),
+            _ => Err(VmError::exception(
+                "java/lang/IllegalMonitorStateException",
+                Some("current thread is not owner".to_string()),
+            )),
+        }
+    }
+
+    /// `Thread.start`: a new thread running `run()` of `thread`, or of its target
+    /// when it's a plain java/lang/Thread
+    pub(crate) fn start_thread(&mut self, thread: Value) -> Result<(), VmError> {
+        let obj = thread.as_object()?.ok_or_else(VmError::null_pointer)?;
+        let target = match &self.heap.get(obj).data {
+            ObjectData::Thread(data) if data.thread.is_none() => data.target,
+            ObjectData::Thread(_) => {
+                return Err(VmError::exception(
+                    "java/lang/IllegalThreadStateException",
+                    None,
+                ))
+            }
+            _ => {
+                return Err(VmError::TypeMismatch {
+                    expected: "java/lang/Thread",
+                    found: "reference",
+                })
+            }
+        };
+        let class = self.heap.get(obj).class;
+        let run = self.find_method("java/lang/Thread", "run", "()V")?;
+        let run = self.select_method(class, &run)?;
+        // Thread.run calls run() of the target, which the new thread does directly
+        let (run, receiver) = match (run.is_native(), target) {
+            (true, Value::Ref(target)) => {
+                let resolved = self.find_method("java/lang/Runnable", "run", "()V")?;
+                let class = self.heap.get(target).class;
+                (
+                    Some(self.select_method(class, &resolved)?),
+                    Value::Ref(target),
+                )
+            }
+            (true, _) => (None, thread),
+            (false, _) => (Some(run), thread),
+        };
+
+        let id = self.threads.threads.len();
+        let mut java_thread = JavaThread {
+            object: thread,
+            state: ThreadState::Runnable,
+            frames: vec![],
+        };
+        match run {
+            Some(run) if !run.is_native() => {
+                let args = vec![receiver];
+                let monitor = self.method_monitor(&run, &args)?;
+                let mut frame = Frame::new(Rc::clone(&run), args)?;
+                frame.monitor = monitor;
+                java_thread.frames.push(frame);
+                // a synchronized run() enters its monitor before its first instruction
+                if let Some(monitor) = monitor {
+                    java_thread.state = ThreadState::Reacquiring { monitor, count: 1 };
+                }
+            }
+            Some(run) => {
+                // a native run() finishes as soon as it's called, so it's called now
+                self.invoke_native(&run, &[receiver])?;
+                java_thread.state = ThreadState::Terminated;
+            }
+            None => java_thread.state = ThreadState::Terminated,
+        }
+        self.threads.threads.push(java_thread);
+        if let ObjectData::Thread(data) = &mut self.heap.get_mut(obj).data {
+            data.thread = Some(id);
+        }
+        Ok(())
+    }
+
+    /// `Thread.join`: waits until `thread` terminates, or for `timeout`
+    /// milliseconds unless it's 0. Returns at once if it wasn't started.
+    pub(crate) fn join(&mut self, thread: Value, timeout: i64) -> Result<(), VmError> {
+        if timeout < 0 {
+            return Err(VmError::exception(
+                "java/lang/IllegalArgumentException",
+                Some("timeout value is negative".to_string()),
+            ));
+        }
+        let Some(thread) = self.thread_id(thread)? else {
+            return Ok(());
+        };
+        if self.threads.is_alive(thread) {
+            let deadline = (timeout > 0).then(|| self.threads.clock + timeout as u64);
+            *self.threads.state() = ThreadState::Joining { thread, deadline };
+        }
+        Ok(())
+    }
+
+    /// `Thread.sleep`, a sleep of 0 lets the other threads run first
+    pub(crate) fn sleep(&mut self, millis: i64) -> Result<(), VmError> {
+        if millis < 0 {
+            return Err(VmError::exception(
+                "java/lang/IllegalArgumentException",
+                Some("timeout value is negative".to_string()),
+            ));
+        }
+        match millis {
+            0 => self.yield_thread(),
+            millis => {
+                let deadline = self.threads.clock + millis as u64;
+                *self.threads.state() = ThreadState::Sleeping(deadline);
+            }
+        }
+        Ok(())
+    }
+
+    /// `Thread.yield`: ends the time slice of the running thread
+    pub(crate) fn yield_thread(&mut self) {
+        self.threads.slice = 1;
+    }
+
+    /// The id of a started thread, None if it wasn't started
+    pub(crate) fn thread_id(&self, thread: Value) -> Result<Option<ThreadId>, VmError> {
+        match &self.object(thread)?.data {
+            ObjectData::Thread(data) => Ok(data.thread),
+            _ => Ok(None),
+        }
+    }
+
+    pub(crate) fn is_alive(&self, thread: Value) -> Result<bool, VmError> {
+        Ok(self
+            .thread_id(thread)?
+            .is_some_and(|thread| self.threads.is_alive(thread)))
+    }
+
+    /// `Thread.currentThread()`, the Thread of the main thread is created the first
+    /// time it's asked for
+    pub(crate) fn current_thread(&mut self) -> Result<Value, VmError> {
+        let current = self.threads.current;
+        if self.threads.threads[current].object == Value::Null {
+            let thread = builtins::new_thread(self, "main".to_string(), Some(current))?;
+            self.threads.threads[current].object = thread;
+        }
+        Ok(self.threads.threads[current].object)
+    }
+
+    /// The name of the running thread
+    pub(crate) fn thread_name(&self) -> String {
+        let thread = self.threads.threads[self.threads.current].object;
+        match self.object(thread).map(|object| &object.data) {
+            Ok(ObjectData::Thread(data)) => data.name.clone(),
+            _ => "main".to_string(),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use super::{error::VmError, heap::ObjRef};
+use crate::parsers::descriptor::FieldType;
+
+/// A value of one of the JVM computational types, as held in local variables and on
+/// the operand stack. boolean, byte, char and short are all represented as `Int`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value {
+    /// An unusable slot, either a local that was never stored to or the second slot
+    /// taken by a long or double
+    Top,
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    Null,
+    Ref(ObjRef),
+}
+
+impl Value {
+    /// The initial value of a field of type `field_type`
+    pub fn default_for(field_type: &FieldType) -> Value {
+        match field_type {
+            FieldType::Long => Value::Long(0),
+            FieldType::Float => Value::Float(0.0),
+            FieldType::Double => Value::Double(0.0),
+            FieldType::Object(_) | FieldType::Array(_) => Value::Null,
+            _ => Value::Int(0),
+        }
+    }
+
+    /// Number of local variable or operand stack slots the value takes
+    pub fn slots(&self) -> usize {
+        match self {
+            Value::Long(_) | Value::Double(_) => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Top => "top",
+            Value::Int(_) => "int",
+            Value::Long(_) => "long",
+            Value::Float(_) => "float",
+            Value::Double(_) => "double",
+            Value::Null | Value::Ref(_) => "reference",
+        }
+    }
+
+    pub fn as_int(&self) -> Result<i32, VmError> {
+        match self {
+            Value::Int(value) => Ok(*value),
+            _ => Err(self.mismatch("int")),
+        }
+    }
+
+    pub fn as_long(&self) -> Result<i64, VmError> {
+        match self {
+            Value::Long(value) => Ok(*value),
+            _ => Err(self.mismatch("long")),
+        }
+    }
+
+    pub fn as_float(&self) -> Result<f32, VmError> {
+        match self {
+            Value::Float(value) => Ok(*value),
+            _ => Err(self.mismatch("float")),
+        }
+    }
+
+    pub fn as_double(&self) -> Result<f64, VmError> {
+        match self {
+            Value::Double(value) => Ok(*value),
+            _ => Err(self.mismatch("double")),
+        }
+    }
+
+    /// The referenced object, None for null
+    pub fn as_object(&self) -> Result<Option<ObjRef>, VmError> {
+        match self {
+            Value::Null => Ok(None),
+            Value::Ref(obj) => Ok(Some(*obj)),
+            _ => Err(self.mismatch("reference")),
+        }
+    }
+
+    pub(crate) fn mismatch(&self, expected: &'static str) -> VmError {
+        VmError::TypeMismatch {
+            expected,
+            found: self.type_name(),
+        }
+    }
+}
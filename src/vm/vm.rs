@@ -0,0 +1,750 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use super::{
+    builtins,
+    class::{ClassId, RuntimeClass, RuntimeMethod},
+    error::VmError,
+    frame::Frame,
+    heap::{Array, Heap, ObjRef, Object, ObjectData},
+    invokedynamic::CallSite,
+    native::{NativeMethod, NativeRegistry},
+    thread::Threads,
+    value::Value,
+};
+use crate::loader::classloader::ClassLoader;
+use crate::parsers::{
+    accessflags::{ACC_PUBLIC, ACC_STATIC},
+    class::JavaClassFile,
+    version::MAGIC,
+};
+
+/// Maximum number of frames on the stack before invocations throw
+/// `java.lang.StackOverflowError`
+const MAX_FRAMES: usize = 4096;
+
+/// The virtual machine: the loaded classes, the heap and the frames of the running
+/// code. A new virtual machine already has the built-in classes of `java.lang` loaded.
+pub struct Vm {
+    pub(crate) classes: Vec<RuntimeClass>,
+    class_ids: HashMap<String, ClassId>,
+    /// The frames of the running thread
+    pub(crate) frames: Vec<Frame>,
+    pub(crate) threads: Threads,
+    pub(crate) heap: Heap,
+    /// The interned strings by content. They don't keep their strings alive, an
+    /// interned string nothing references can't be compared to anymore.
+    strings: HashMap<Vec<u16>, ObjRef>,
+    /// The boxed Integers of -128..=127 by `value + 128`, created on first use. Boxing
+    /// one of those values always gives the same object (JLS 5.1.7).
+    pub(crate) integers: Vec<Option<ObjRef>>,
+    /// The linked `invokedynamic` call sites, by class and InvokeDynamic entry
+    pub(crate) call_sites: HashMap<(ClassId, u16), Rc<CallSite>>,
+    natives: NativeRegistry,
+    pub(crate) stdout: Box<dyn Write>,
+    pub(crate) stderr: Box<dyn Write>,
+    loader: ClassLoader,
+    /// Classes whose superclasses are being resolved, to detect circularity
+    linking: Vec<String>,
+    /// Number of native methods running, the heap isn't collected while one is as
+    /// the references it holds are not visible to the collector
+    pub(crate) native_depth: usize,
+    /// Values the virtual machine holds while it runs other code, like the
+    /// arguments of `invoke_static` while the class of the method is initialized
+    pinned: Vec<Value>,
+}
+
+impl Vm {
+    pub fn new() -> Self {
+        let mut vm = Self {
+            classes: vec![],
+            class_ids: HashMap::new(),
+            frames: vec![],
+            threads: Threads::default(),
+            heap: Heap::default(),
+            strings: HashMap::new(),
+            integers: vec![None; 256],
+            call_sites: HashMap::new(),
+            natives: NativeRegistry::default(),
+            stdout: Box::new(io::stdout()),
+            stderr: Box::new(io::stderr()),
+            loader: ClassLoader::default(),
+            linking: vec![],
+            native_depth: 0,
+            pinned: vec![],
+        };
+        builtins::register(&mut vm);
+        vm
+    }
+
+    /// Where System.out writes to, the process stdout by default
+    pub fn set_stdout(&mut self, stdout: Box<dyn Write>) {
+        self.stdout = stdout;
+    }
+
+    /// Where System.err writes to, the process stderr by default
+    pub fn set_stderr(&mut self, stderr: Box<dyn Write>) {
+        self.stderr = stderr;
+    }
+
+    /// Where classes that were not added are loaded from when first used. Built-in
+    /// classes take precedence over classes of the same name on the classpath.
+    pub fn set_class_loader(&mut self, loader: ClassLoader) {
+        self.loader = loader;
+    }
+
+    pub fn class_loader(&self) -> &ClassLoader {
+        &self.loader
+    }
+
+    /// Registers the implementation of a native method, which must happen before
+    /// its class is added
+    pub fn register_native(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+        method: NativeMethod,
+    ) {
+        self.natives.register(class, name, descriptor, method);
+    }
+
+    /// Links and adds a class. Its superclass and interfaces are resolved first, so
+    /// they must have been added already or be found by the class loader. Interfaces
+    /// that can't be found are left out, so classes can implement JDK interfaces
+    /// that aren't built in.
+    pub fn add_class(&mut self, file: impl Into<Rc<JavaClassFile>>) -> Result<ClassId, VmError> {
+        let file = file.into();
+        if self.class_ids.contains_key(&file.this_class) {
+            return Err(VmError::DuplicateClass(file.this_class.clone()));
+        }
+        if self.linking.contains(&file.this_class) {
+            return Err(VmError::exception(
+                "java/lang/ClassCircularityError",
+                Some(file.this_class.replace('/', ".")),
+            ));
+        }
+        self.linking.push(file.this_class.clone());
+        let supertypes = self.resolve_supertypes(&file);
+        self.linking.pop();
+        let (super_class, interfaces) = supertypes?;
+        // resolving the supertypes may have loaded this class too
+        if self.class_ids.contains_key(&file.this_class) {
+            return Err(VmError::DuplicateClass(file.this_class.clone()));
+        }
+        let id = self.classes.len();
+        let class = RuntimeClass::new(
+            id,
+            file,
+            super_class,
+            interfaces,
+            &self.classes,
+            &self.natives,
+        )?;
+        self.class_ids.insert(class.name.clone(), id);
+        self.classes.push(class);
+        Ok(id)
+    }
+
+    fn resolve_supertypes(
+        &mut self,
+        file: &JavaClassFile,
+    ) -> Result<(Option<ClassId>, Vec<ClassId>), VmError> {
+        let super_class = match file.super_class.as_str() {
+            "" => None,
+            super_class => Some(self.resolve_class(super_class)?),
+        };
+        let mut interfaces = vec![];
+        for interface in &file.interfaces {
+            match self.resolve_class(interface) {
+                Ok(interface) => interfaces.push(interface),
+                Err(VmError::ClassNotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok((super_class, interfaces))
+    }
+
+    pub fn heap(&self) -> &Heap {
+        &self.heap
+    }
+
+    pub fn class(&self, id: ClassId) -> &RuntimeClass {
+        &self.classes[id]
+    }
+
+    pub fn class_id(&self, name: &str) -> Result<ClassId, VmError> {
+        self.class_ids
+            .get(name)
+            .copied()
+            .ok_or_else(|| VmError::ClassNotFound(name.to_string()))
+    }
+
+    /// The class named `name`, loading it with the class loader if it wasn't yet
+    pub fn resolve_class(&mut self, name: &str) -> Result<ClassId, VmError> {
+        if let Some(id) = self.class_ids.get(name) {
+            return Ok(*id);
+        }
+        if name.starts_with('[') {
+            return self.add_class(array_class_file(name));
+        }
+        let file = self.loader.load(name)?;
+        self.add_class(file)
+    }
+
+    /// Resolves a method reference: looks `name` and `descriptor` up in `class` and
+    /// its superclasses, then in the methods it inherits from its interfaces
+    pub fn find_method(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+    ) -> Result<Rc<RuntimeMethod>, VmError> {
+        let id = self.resolve_class(class)?;
+        let mut current = Some(id);
+        while let Some(class) = current {
+            if let Some(method) = self.classes[class].method(name, descriptor) {
+                return Ok(Rc::clone(method));
+            }
+            current = self.classes[class].super_class;
+        }
+        if let Some(method) = self.classes[id].vtable.get(name, descriptor) {
+            return Ok(Rc::clone(method));
+        }
+        // the superinterfaces of an interface may declare the method abstract more
+        // than once, any of them will do
+        for interface in self.classes[id].all_interfaces.clone() {
+            if let Some(method) = self.classes[interface].method(name, descriptor) {
+                if !method.is_static() && !method.is_private() {
+                    return Ok(Rc::clone(method));
+                }
+            }
+        }
+        Err(VmError::MethodNotFound {
+            class: class.to_string(),
+            name: name.to_string(),
+            descriptor: descriptor.to_string(),
+        })
+    }
+
+    /// The method a virtual or interface call to the resolved method `method` runs
+    /// when the receiver is an instance of `class`
+    pub fn select_method(
+        &self,
+        class: ClassId,
+        method: &Rc<RuntimeMethod>,
+    ) -> Result<Rc<RuntimeMethod>, VmError> {
+        if method.is_private() {
+            return Ok(Rc::clone(method));
+        }
+        let receiver = &self.classes[class];
+        if let Some(selected) = receiver.vtable.get(&method.name, &method.descriptor) {
+            if !selected.is_abstract() {
+                return Ok(Rc::clone(selected));
+            }
+        }
+        let conflicts: Vec<String> = receiver
+            .default_conflicts
+            .iter()
+            .filter(|conflict| conflict.overridden_by(method))
+            .map(|conflict| {
+                format!(
+                    "{}.{}",
+                    conflict.class_name.replace('/', "."),
+                    conflict.name
+                )
+            })
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(VmError::exception(
+                "java/lang/IncompatibleClassChangeError",
+                Some(format!(
+                    "Conflicting default methods: {}",
+                    conflicts.join(" ")
+                )),
+            ));
+        }
+        Err(self.abstract_method_error(class, method))
+    }
+
+    /// The AbstractMethodError of invoking the abstract `method` on an instance of
+    /// `class`
+    pub(crate) fn abstract_method_error(&self, class: ClassId, method: &RuntimeMethod) -> VmError {
+        let kind = match self.classes[method.class].is_interface() {
+            true => "interface",
+            false => "abstract class",
+        };
+        let signature = &method.signature;
+        let ret = signature
+            .ret
+            .as_ref()
+            .map_or("void".to_string(), ToString::to_string);
+        let params: Vec<String> = signature.params.iter().map(ToString::to_string).collect();
+        VmError::exception(
+            "java/lang/AbstractMethodError",
+            Some(format!(
+                "Receiver class {} does not define or inherit an implementation of the \
+                 resolved method 'abstract {ret} {}({})' of {kind} {}.",
+                self.classes[class].name.replace('/', "."),
+                method.name,
+                params.join(", "),
+                method.class_name.replace('/', "."),
+            )),
+        )
+    }
+
+    /// Whether `class` is `ancestor` or one of its subclasses
+    pub(crate) fn is_subclass(&self, class: ClassId, ancestor: ClassId) -> bool {
+        let mut current = Some(class);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.classes[id].super_class;
+        }
+        false
+    }
+
+    /// The class or interface declaring static field `name`, searching `class`, its
+    /// superinterfaces and then its superclasses
+    pub fn find_static_field(&mut self, class: &str, name: &str) -> Result<ClassId, VmError> {
+        let id = self.resolve_class(class)?;
+        self.static_field_owner(id, name)
+            .ok_or_else(|| VmError::exception("java/lang/NoSuchFieldError", Some(name.to_string())))
+    }
+
+    /// Field lookup of JVMS 5.4.3.2: `class` itself, then each of its direct
+    /// superinterfaces recursively in the order they're declared in, then its
+    /// superclass recursively
+    fn static_field_owner(&self, class: ClassId, name: &str) -> Option<ClassId> {
+        let runtime = &self.classes[class];
+        if runtime.statics.contains_key(name) {
+            return Some(class);
+        }
+        runtime
+            .interfaces
+            .iter()
+            .find_map(|interface| self.static_field_owner(*interface, name))
+            .or_else(|| {
+                let super_class = runtime.super_class?;
+                self.static_field_owner(super_class, name)
+            })
+    }
+
+    /// Position in `Object::fields` of the instance field `name`, searching `class`
+    /// and then its superclasses
+    pub fn find_field(&mut self, class: &str, name: &str) -> Result<usize, VmError> {
+        let mut current = Some(self.resolve_class(class)?);
+        while let Some(id) = current {
+            if let Some(slot) = self.classes[id].fields.get(name) {
+                return Ok(*slot);
+            }
+            current = self.classes[id].super_class;
+        }
+        Err(VmError::exception(
+            "java/lang/NoSuchFieldError",
+            Some(name.to_string()),
+        ))
+    }
+
+    /// Invokes a static method and runs it to completion, returning its result
+    pub fn invoke_static(
+        &mut self,
+        class: &str,
+        name: &str,
+        descriptor: &str,
+        args: Vec<Value>,
+    ) -> Result<Option<Value>, VmError> {
+        let method = self.find_method(class, name, descriptor)?;
+        if !method.is_static() {
+            return Err(VmError::IncompatibleClassChange(format!(
+                "{class}.{name}:{descriptor} is not static"
+            )));
+        }
+        // the static initializer may collect the heap before the arguments are in a frame
+        let pinned = self.pinned.len();
+        self.pinned.extend(&args);
+        let initialized = self.initialize(method.class);
+        self.pinned.truncate(pinned);
+        match initialized {
+            Err(VmError::Throw(exception)) if self.native_depth == 0 => {
+                return Err(self.exception_of(exception))
+            }
+            result => result?,
+        }
+        self.invoke(method, args)
+    }
+
+    /// Invokes an instance method on `args[0]`, selected by the class of that object
+    pub fn invoke_virtual(
+        &mut self,
+        name: &str,
+        descriptor: &str,
+        args: Vec<Value>,
+    ) -> Result<Option<Value>, VmError> {
+        let receiver = args
+            .first()
+            .ok_or(VmError::ArgumentCount {
+                expected: 1,
+                found: 0,
+            })?
+            .as_object()?
+            .ok_or_else(VmError::null_pointer)?;
+        let class = self.heap.get(receiver).class;
+        let name_of_class = self.classes[class].name.clone();
+        let method = self.find_method(&name_of_class, name, descriptor)?;
+        let method = self.select_method(class, &method)?;
+        self.invoke(method, args)
+    }
+
+    /// Invokes `method` and runs it to completion, returning its result. An
+    /// exception the method throws is returned as `VmError::Exception`, or as
+    /// `VmError::Throw` to natives so they can pass it on to their caller.
+    pub fn invoke(
+        &mut self,
+        method: Rc<RuntimeMethod>,
+        args: Vec<Value>,
+    ) -> Result<Option<Value>, VmError> {
+        match self.invoke_method(method, args) {
+            Err(VmError::Throw(exception)) if self.native_depth == 0 => {
+                Err(self.exception_of(exception))
+            }
+            result => result,
+        }
+    }
+
+    pub(crate) fn invoke_method(
+        &mut self,
+        method: Rc<RuntimeMethod>,
+        args: Vec<Value>,
+    ) -> Result<Option<Value>, VmError> {
+        if args.len() != method.arg_count() {
+            return Err(VmError::ArgumentCount {
+                expected: method.arg_count(),
+                found: args.len(),
+            });
+        }
+        if method.is_native() {
+            return self.invoke_native(&method, &args);
+        }
+        // blocking here would need the caller's frames to be switched out too
+        let monitor = self.method_monitor(&method, &args)?;
+        if monitor.is_some_and(|monitor| !self.can_enter(monitor)) {
+            return Err(VmError::BlockedInNestedCall);
+        }
+        let depth = self.frames.len();
+        self.push_frame(method, args)?;
+        if let Some(monitor) = monitor {
+            self.enter_monitor(monitor);
+            self.frame().monitor = Some(monitor);
+        }
+        self.run(depth)
+    }
+
+    pub(crate) fn invoke_native(
+        &mut self,
+        method: &RuntimeMethod,
+        args: &[Value],
+    ) -> Result<Option<Value>, VmError> {
+        let native = method.native.ok_or_else(|| VmError::UnsatisfiedLink {
+            class: method.class_name.clone(),
+            name: method.name.clone(),
+            descriptor: method.descriptor.clone(),
+        })?;
+        self.native_depth += 1;
+        let result = native(self, args);
+        self.native_depth -= 1;
+        result
+    }
+
+    /// Runs `public static void main(String[])` of `class` without arguments
+    pub fn run_main(&mut self, class: &str) -> Result<(), VmError> {
+        self.run_main_with_args(class, &[])
+    }
+
+    /// Runs `public static void main(String[])` of `class`, passing `args` to it. An
+    /// exception escaping main has its stack trace printed to stderr, like the JVM
+    /// does, and is returned as `VmError::Exception`.
+    pub fn run_main_with_args(&mut self, class: &str, args: &[&str]) -> Result<(), VmError> {
+        let id = self.resolve_class(class)?;
+        let main = self
+            .class(id)
+            .method("main", "([Ljava/lang/String;)V")
+            .filter(|main| main.access_flags & (ACC_PUBLIC | ACC_STATIC) == ACC_PUBLIC | ACC_STATIC)
+            .map(Rc::clone)
+            .ok_or_else(|| VmError::NoMainMethod(class.to_string()))?;
+        // the args are built after the static initializer ran, which may collect the heap
+        let result = self.initialize(id).and_then(|()| {
+            let array = self.new_array("[Ljava/lang/String;", args.len() as i32)?;
+            for (index, arg) in args.iter().enumerate() {
+                let arg = self.new_string(arg)?;
+                self.array_mut(array)?.set(index, arg)?;
+            }
+            self.invoke_method(main, vec![array])
+        });
+        let result = match result {
+            Err(VmError::Throw(exception)) => Err(self.uncaught(exception)),
+            Err(err @ VmError::Exception { .. }) => {
+                let _ = writeln!(self.stderr, "Exception in thread \"main\" {err}");
+                Err(err)
+            }
+            result => result.map(|_| ()),
+        };
+        // the JVM exits once the threads main started have terminated too
+        let result = match result {
+            Ok(()) | Err(VmError::Exception { .. }) => self.run_threads().and(result),
+            err => err,
+        };
+        let _ = self.stdout.flush();
+        let _ = self.stderr.flush();
+        result
+    }
+
+    pub(crate) fn push_frame(
+        &mut self,
+        method: Rc<RuntimeMethod>,
+        args: Vec<Value>,
+    ) -> Result<(), VmError> {
+        if self.frames.len() >= MAX_FRAMES {
+            return Err(VmError::exception("java/lang/StackOverflowError", None));
+        }
+        let frame = Frame::new(method, args)?;
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    /// Discards the current frame, releasing the monitor of a synchronized method
+    pub(crate) fn pop_frame(&mut self) {
+        if let Some(frame) = self.frames.pop() {
+            if let Some(monitor) = frame.monitor {
+                self.release(monitor);
+            }
+        }
+    }
+
+    pub fn new_object(&mut self, class: &str, data: ObjectData) -> Result<Value, VmError> {
+        let class = self.resolve_class(class)?;
+        let fields = self.classes[class].field_defaults.clone();
+        let object = Object {
+            class,
+            fields,
+            data,
+        };
+        Ok(Value::Ref(self.heap.alloc(object)))
+    }
+
+    /// A new array of the array class `class`, e.g. `[I` or `[[Ljava/lang/String;`
+    pub fn new_array(&mut self, class: &str, length: i32) -> Result<Value, VmError> {
+        if length < 0 {
+            return Err(VmError::exception(
+                "java/lang/NegativeArraySizeException",
+                Some(length.to_string()),
+            ));
+        }
+        let array = Array::new(class, length as usize);
+        self.new_object(class, ObjectData::Array(array))
+    }
+
+    /// A new String, never identical to another one, see `interned_string`
+    pub fn new_string(&mut self, value: &str) -> Result<Value, VmError> {
+        let chars = value.encode_utf16().collect();
+        self.new_object("java/lang/String", ObjectData::Chars(chars))
+    }
+
+    /// The interned String with content `value`, the same object for equal
+    /// contents as long as it's reachable. `ldc` of a string constant pushes these.
+    pub fn interned_string(&mut self, value: &str) -> Result<Value, VmError> {
+        self.interned_chars(value.encode_utf16().collect())
+    }
+
+    /// The interned String with the UTF-16 code units `chars`, which unlike a Rust
+    /// string may contain unpaired surrogates
+    pub fn interned_chars(&mut self, chars: Vec<u16>) -> Result<Value, VmError> {
+        if let Some(string) = self.strings.get(&chars) {
+            return Ok(Value::Ref(*string));
+        }
+        let string = self.new_object("java/lang/String", ObjectData::Chars(chars.clone()))?;
+        self.strings
+            .insert(chars, string.as_object()?.expect("new objects aren't null"));
+        Ok(string)
+    }
+
+    /// `String.intern()`: the interned String equal to `string`, which becomes the
+    /// interned one if there is none yet
+    pub fn intern(&mut self, string: Value) -> Result<Value, VmError> {
+        let chars = self.chars(string)?.to_vec();
+        let obj = string.as_object()?.ok_or_else(VmError::null_pointer)?;
+        Ok(Value::Ref(*self.strings.entry(chars).or_insert(obj)))
+    }
+
+    /// The object `value` references, a NullPointerException for null
+    pub fn object(&self, value: Value) -> Result<&Object, VmError> {
+        let obj = value.as_object()?.ok_or_else(VmError::null_pointer)?;
+        Ok(self.heap.get(obj))
+    }
+
+    pub fn object_mut(&mut self, value: Value) -> Result<&mut Object, VmError> {
+        let obj = value.as_object()?.ok_or_else(VmError::null_pointer)?;
+        Ok(self.heap.get_mut(obj))
+    }
+
+    /// The elements of an array, a NullPointerException for null
+    pub fn array(&self, value: Value) -> Result<&Array, VmError> {
+        match &self.object(value)?.data {
+            ObjectData::Array(array) => Ok(array),
+            _ => Err(VmError::TypeMismatch {
+                expected: "array",
+                found: "reference",
+            }),
+        }
+    }
+
+    pub fn array_mut(&mut self, value: Value) -> Result<&mut Array, VmError> {
+        match &mut self.object_mut(value)?.data {
+            ObjectData::Array(array) => Ok(array),
+            _ => Err(VmError::TypeMismatch {
+                expected: "array",
+                found: "reference",
+            }),
+        }
+    }
+
+    /// Whether an object of class `class` is an instance of `target`, a class,
+    /// interface or array class
+    pub fn is_assignable(&mut self, class: ClassId, target: &str) -> Result<bool, VmError> {
+        let name = self.classes[class].name.clone();
+        if name == target || target == "java/lang/Object" {
+            return Ok(true);
+        }
+        if let (Some(component), Some(target)) = (name.strip_prefix('['), target.strip_prefix('['))
+        {
+            // arrays of references are covariant, arrays of primitives only match
+            // arrays of the same primitive which was checked above
+            return match (reference_class(component), reference_class(target)) {
+                (Some(component), Some(target)) => {
+                    let target = target.to_string();
+                    let component = self.resolve_class(component)?;
+                    self.is_assignable(component, &target)
+                }
+                _ => Ok(false),
+            };
+        }
+        let mut current = Some(class);
+        while let Some(id) = current {
+            if self.classes[id].name == target {
+                return Ok(true);
+            }
+            let file = Rc::clone(&self.classes[id].file);
+            for interface in &file.interfaces {
+                if interface == target {
+                    return Ok(true);
+                }
+                match self.resolve_class(interface) {
+                    Ok(interface) => {
+                        if self.is_assignable(interface, target)? {
+                            return Ok(true);
+                        }
+                    }
+                    // JDK interfaces that aren't built in, e.g. java/io/Serializable,
+                    // are only matched by name
+                    Err(VmError::ClassNotFound(_)) => {}
+                    Err(err) => return Err(err),
+                }
+            }
+            current = self.classes[id].super_class;
+        }
+        Ok(false)
+    }
+
+    /// Frees the objects that can't be reached from the frames of the threads, the
+    /// Thread objects, static fields or the values the virtual machine is holding on
+    /// to, returning how many were freed. References held outside of the virtual
+    /// machine, like the result of `new_string`, must be stored in a static field or
+    /// passed to the code being run to survive a collection.
+    pub fn collect_garbage(&mut self) -> usize {
+        let frames = self
+            .frames
+            .iter()
+            .flat_map(|frame| frame.locals.iter().chain(&frame.stack));
+        let statics = self.classes.iter().flat_map(|class| class.statics.values());
+        let integers = self.integers.iter().flatten().map(|obj| Value::Ref(*obj));
+        let roots: Vec<Value> = frames
+            .chain(statics)
+            .chain(self.threads.roots())
+            .chain(&self.pinned)
+            .copied()
+            .chain(integers)
+            .collect();
+        let freed = self.heap.collect(roots);
+        let heap = &self.heap;
+        self.strings.retain(|_, string| heap.is_live(*string));
+        self.threads.retain_monitors(|obj| heap.is_live(obj));
+        freed
+    }
+
+    /// Name of the class of the object `obj`
+    pub fn class_of(&self, obj: ObjRef) -> &str {
+        &self.classes[self.heap.get(obj).class].name
+    }
+
+    /// The UTF-16 code units of a String or StringBuilder
+    pub fn chars(&self, value: Value) -> Result<&[u16], VmError> {
+        match &self.object(value)?.data {
+            ObjectData::Chars(chars) => Ok(chars),
+            _ => Err(VmError::TypeMismatch {
+                expected: "java/lang/String",
+                found: "reference",
+            }),
+        }
+    }
+
+    /// The content of a String or StringBuilder, lone surrogates are replaced
+    pub fn java_string(&self, value: Value) -> Result<String, VmError> {
+        Ok(String::from_utf16_lossy(self.chars(value)?))
+    }
+
+    /// `String.valueOf(Object)`: "null", or the result of calling `toString()`
+    pub fn string_of(&mut self, value: Value) -> Result<String, VmError> {
+        if value == Value::Null {
+            return Ok("null".to_string());
+        }
+        let string = self
+            .invoke_virtual("toString", "()Ljava/lang/String;", vec![value])?
+            .unwrap_or(Value::Null);
+        match string {
+            Value::Null => Ok("null".to_string()),
+            string => self.java_string(string),
+        }
+    }
+}
+
+/// The class named by the component type of an array class, None for primitives
+fn reference_class(component: &str) -> Option<&str> {
+    match component.strip_prefix('L') {
+        Some(class) => class.strip_suffix(';'),
+        None => component.starts_with('[').then_some(component),
+    }
+}
+
+/// Array classes have no class file, this one has what `RuntimeClass` needs of them
+fn array_class_file(name: &str) -> JavaClassFile {
+    let flags = ["ACC_PUBLIC", "ACC_FINAL", "ACC_ABSTRACT"];
+    JavaClassFile {
+        magic: MAGIC,
+        access_flags: flags.iter().map(|flag| flag.to_string()).collect(),
+        this_class: name.to_string(),
+        super_class: "java/lang/Object".to_string(),
+        interfaces: vec![
+            "java/lang/Cloneable".to_string(),
+            "java/io/Serializable".to_string(),
+        ],
+        ..Default::default()
+    }
+}
+
+impl Default for Vm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,527 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::parsers::{
+    accessflags::{
+        class_access_flags_mask, field_access_flags_mask, method_access_flags_mask,
+        unnamed_class_access_flags, unnamed_field_access_flags, unnamed_method_access_flags,
+    },
+    attributes::Attr,
+    bytecode::{Instruction, WideInstruction},
+    class::JavaClassFile,
+    constantpool::{Constant, ConstantPool, Utf8},
+};
+use crate::utils::bytewriter::ByteWriter;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WriteError {
+    /// A branch target that cannot be reached with the offset size of its instruction
+    BranchOutOfRange { pc: u32, target: u32 },
+    /// A branch target, exception handler or line number pc that no instruction was
+    /// parsed from
+    UnknownPc(u32),
+    /// More entries than the u2 counts of the class file format allow
+    TooLarge(&'static str),
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteError::BranchOutOfRange { pc, target } => {
+                write!(f, "Branch target {target} out of range at pc {pc}")
+            }
+            WriteError::UnknownPc(pc) => write!(f, "No instruction is stored with pc {pc}"),
+            WriteError::TooLarge(what) => write!(f, "Too many {what} for a class file"),
+        }
+    }
+}
+
+impl std::error::Error for WriteError {}
+
+/// Serialises a [`JavaClassFile`] back into class file bytes.
+///
+/// Names are written with the constant pool index they were read from, or when the
+/// class was modified, the first entry of the class constant pool holding them.
+/// Entries that are missing are appended to it. Access flag bits without an `ACC_*`
+/// name are kept. An unmodified class is written back byte for byte.
+///
+/// Code may be edited, see [`write_byte_code`]. The pcs of exception tables and line
+/// number tables are moved along with the instructions, those inside attributes that
+/// are not parsed, like StackMapTable, are written as they were read.
+pub struct ClassWriter {
+    cp: ConstantPool,
+}
+
+impl ClassWriter {
+    pub fn new() -> Self {
+        Self {
+            cp: ConstantPool::default(),
+        }
+    }
+
+    pub fn write(mut self, class: &JavaClassFile) -> Result<Vec<u8>, WriteError> {
+        self.cp = class.constant_pool.clone();
+
+        // the constant pool is written last since writing the rest may add entries to it
+        let mut body = ByteWriter::default();
+        body.write_u2(
+            class_access_flags_mask(&class.access_flags)
+                | unnamed_class_access_flags(class.access_mask),
+        );
+        let this_class = self.class_at(class.this_class_index, &class.this_class)?;
+        body.write_u2(this_class);
+        let super_class = match class.super_class.as_str() {
+            "" => 0,
+            name => self.class_at(class.super_class_index, name)?,
+        };
+        body.write_u2(super_class);
+
+        body.write_u2(count(class.interfaces.len(), "interfaces")?);
+        for (i, interface) in class.interfaces.iter().enumerate() {
+            let read_from = class.interface_indices.get(i).copied().unwrap_or(0);
+            let index = self.class_at(read_from, interface)?;
+            body.write_u2(index);
+        }
+
+        body.write_u2(count(class.fields.len(), "fields")?);
+        for field in &class.fields {
+            body.write_u2(
+                field_access_flags_mask(&field.access_flags)
+                    | unnamed_field_access_flags(field.access_mask),
+            );
+            let name_index = self.utf8_at(field.name_index, &field.name)?;
+            body.write_u2(name_index);
+            let descriptor_index = self.utf8_at(field.descriptor_index, &field.descriptor)?;
+            body.write_u2(descriptor_index);
+            self.write_attrs(&mut body, &field.attrs, None)?;
+        }
+
+        body.write_u2(count(class.methods.len(), "methods")?);
+        for method in &class.methods {
+            body.write_u2(
+                method_access_flags_mask(&method.access_flags)
+                    | unnamed_method_access_flags(method.access_mask),
+            );
+            let name_index = self.utf8_at(method.name_index, &method.name)?;
+            body.write_u2(name_index);
+            let descriptor_index = self.utf8_at(method.descriptor_index, &method.descriptor)?;
+            body.write_u2(descriptor_index);
+            self.write_attrs(&mut body, &method.attrs, None)?;
+        }
+
+        self.write_attrs(&mut body, &class.attrs, None)?;
+
+        let mut out = ByteWriter::default();
+        out.write_u4(class.magic);
+        out.write_u2(class.minor);
+        out.write_u2(class.major);
+        write_constant_pool(&mut out, &self.cp)?;
+        out.write_n(&body.xs);
+        Ok(out.xs)
+    }
+
+    /// `index` if it still is the Utf8 entry of `value`, so that a pool holding
+    /// duplicates keeps them, otherwise the first entry for `value`
+    fn utf8_at(&mut self, index: u16, value: &str) -> Result<u16, WriteError> {
+        match self.cp.utf8(index as usize) {
+            Ok(found) if found == value => Ok(index),
+            _ => self.utf8_index(value),
+        }
+    }
+
+    /// `index` if it still is the Class entry of `name`, otherwise the first entry
+    /// for `name`
+    fn class_at(&mut self, index: u16, name: &str) -> Result<u16, WriteError> {
+        match self.cp.class_name(index as usize) {
+            Ok(found) if found == name => Ok(index),
+            _ => self.class_index(name),
+        }
+    }
+
+    fn utf8_index(&mut self, value: &str) -> Result<u16, WriteError> {
+        let found = self
+            .cp
+            .constants
+            .iter()
+            .position(|constant| matches!(constant, Constant::Utf8(s) if s.as_str() == value));
+        match found {
+            Some(i) => Ok(i as u16 + 1),
+            None => self.push(Constant::Utf8(Utf8::new(value))),
+        }
+    }
+
+    fn class_index(&mut self, name: &str) -> Result<u16, WriteError> {
+        let found = (1..=self.cp.constants.len()).find(|&i| self.cp.class_name(i) == Ok(name));
+        match found {
+            Some(i) => Ok(i as u16),
+            None => {
+                let name_index = self.utf8_index(name)?;
+                self.push(Constant::Class { name_index })
+            }
+        }
+    }
+
+    fn push(&mut self, constant: Constant) -> Result<u16, WriteError> {
+        self.cp.constants.push(constant);
+        // constant_pool_count is a u2 and counts the unused entry 0
+        if self.cp.constants.len() >= u16::MAX as usize {
+            return Err(WriteError::TooLarge("constants"));
+        }
+        Ok(self.cp.constants.len() as u16)
+    }
+
+    /// Writes `attrs`, those of a Code attribute with the pcs its instructions were
+    /// moved to
+    fn write_attrs(
+        &mut self,
+        out: &mut ByteWriter,
+        attrs: &[Attr],
+        pcs: Option<&PcMap>,
+    ) -> Result<(), WriteError> {
+        out.write_u2(count(attrs.len(), "attributes")?);
+        for attr in attrs {
+            let mut info = ByteWriter::default();
+            match attr {
+                Attr::Code {
+                    max_stack,
+                    max_locals,
+                    code_length,
+                    code,
+                    exception_table,
+                    attrs,
+                    ..
+                } => {
+                    info.write_u2(*max_stack);
+                    info.write_u2(*max_locals);
+                    let (code, pcs) = encode_code(code, Some(*code_length))?;
+                    info.write_u4(code.len() as u32);
+                    info.write_n(&code);
+                    info.write_u2(count(exception_table.len(), "exception handlers")?);
+                    for entry in exception_table {
+                        info.write_u2(pcs.get(entry.start_pc as u32)? as u16);
+                        info.write_u2(pcs.get(entry.end_pc as u32)? as u16);
+                        info.write_u2(pcs.get(entry.handler_pc as u32)? as u16);
+                        let catch_type = match entry.catch_type.as_str() {
+                            "any" => 0,
+                            name => self.class_at(entry.catch_type_index, name)?,
+                        };
+                        info.write_u2(catch_type);
+                    }
+                    self.write_attrs(&mut info, attrs, Some(&pcs))?;
+                }
+                Attr::LineNumberTable { table, .. } => {
+                    info.write_u2(count(table.len(), "line numbers")?);
+                    for entry in table {
+                        let start_pc = match pcs {
+                            Some(pcs) => pcs.get(entry.start_pc as u32)? as u16,
+                            None => entry.start_pc,
+                        };
+                        info.write_u2(start_pc);
+                        info.write_u2(entry.line_number);
+                    }
+                }
+                Attr::SourceFile {
+                    file, file_index, ..
+                } => {
+                    let file_index = self.utf8_at(*file_index, file)?;
+                    info.write_u2(file_index);
+                }
+                Attr::ConstantValue { value_index, .. } => info.write_u2(*value_index),
+                Attr::BootstrapMethods { methods, .. } => {
+                    info.write_u2(count(methods.len(), "bootstrap methods")?);
+                    for method in methods {
+                        info.write_u2(method.method_ref);
+                        info.write_u2(count(method.arguments.len(), "bootstrap arguments")?);
+                        for argument in &method.arguments {
+                            info.write_u2(*argument);
+                        }
+                    }
+                }
+                Attr::Unknown { bytes, .. } => info.write_n(bytes),
+            }
+            let name_index = self.utf8_at(attr.name_index(), attr.name())?;
+            out.write_u2(name_index);
+            out.write_u4(info.len() as u32);
+            out.write_n(&info.xs);
+        }
+        Ok(())
+    }
+}
+
+impl Default for ClassWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn count(len: usize, what: &'static str) -> Result<u16, WriteError> {
+    u16::try_from(len).map_err(|_| WriteError::TooLarge(what))
+}
+
+fn write_constant_pool(out: &mut ByteWriter, cp: &ConstantPool) -> Result<(), WriteError> {
+    out.write_u2(count(cp.constants.len() + 1, "constants")?);
+    for constant in &cp.constants {
+        if *constant != Constant::Unusable {
+            out.write_u1(constant.tag());
+        }
+        match constant {
+            Constant::Utf8(value) => {
+                let bytes = value.bytes();
+                out.write_u2(count(bytes.len(), "bytes in a Utf8 constant")?);
+                out.write_n(bytes);
+            }
+            Constant::Integer(value) => out.write_u4(*value as u32),
+            Constant::Float(value) => out.write_u4(value.to_bits()),
+            Constant::Long(value) => out.write_n(&value.to_be_bytes()),
+            Constant::Double(value) => out.write_n(&value.to_bits().to_be_bytes()),
+            Constant::Class { name_index } => out.write_u2(*name_index),
+            Constant::String { string_index } => out.write_u2(*string_index),
+            Constant::Fieldref {
+                class_index,
+                name_and_type_index,
+            }
+            | Constant::Methodref {
+                class_index,
+                name_and_type_index,
+            }
+            | Constant::InterfaceMethodref {
+                class_index,
+                name_and_type_index,
+            } => {
+                out.write_u2(*class_index);
+                out.write_u2(*name_and_type_index);
+            }
+            Constant::NameAndType {
+                name_index,
+                descriptor_index,
+            } => {
+                out.write_u2(*name_index);
+                out.write_u2(*descriptor_index);
+            }
+            Constant::MethodHandle {
+                reference_kind,
+                reference_index,
+            } => {
+                out.write_u1(*reference_kind);
+                out.write_u2(*reference_index);
+            }
+            Constant::MethodType { descriptor_index } => out.write_u2(*descriptor_index),
+            Constant::Dynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            }
+            | Constant::InvokeDynamic {
+                bootstrap_method_attr_index,
+                name_and_type_index,
+            } => {
+                out.write_u2(*bootstrap_method_attr_index);
+                out.write_u2(*name_and_type_index);
+            }
+            Constant::Module { name_index } | Constant::Package { name_index } => {
+                out.write_u2(*name_index)
+            }
+            // the second slot of a Long or Double has no bytes of its own
+            Constant::Unusable => {}
+        }
+    }
+    Ok(())
+}
+
+/// Encodes instructions into a code array.
+///
+/// Branch and switch targets name the pcs stored with the instructions, the ones they
+/// were parsed from, and are moved to where those instructions end up. Instructions
+/// can so be inserted or removed: an inserted instruction given the pc of the one
+/// after it is the one branches to that pc land on, as the first instruction stored
+/// with a pc is.
+pub fn write_byte_code(code: &[(u32, Instruction)]) -> Result<Vec<u8>, WriteError> {
+    Ok(encode_code(code, None)?.0)
+}
+
+/// Where the instructions of a code array were moved to, by the pc they were parsed
+/// from
+struct PcMap {
+    pcs: HashMap<u32, u32>,
+    /// The length of the code array when it was parsed, if known, and now, the end
+    /// pc of exception handlers covering the last instruction
+    end: (Option<u32>, u32),
+}
+
+impl PcMap {
+    fn get(&self, pc: u32) -> Result<u32, WriteError> {
+        match self.pcs.get(&pc) {
+            Some(pc) => Ok(*pc),
+            None if Some(pc) == self.end.0 => Ok(self.end.1),
+            None => Err(WriteError::UnknownPc(pc)),
+        }
+    }
+}
+
+/// Encodes `code`, whose code array was `end` bytes long when it was parsed
+fn encode_code(
+    code: &[(u32, Instruction)],
+    end: Option<u32>,
+) -> Result<(Vec<u8>, PcMap), WriteError> {
+    // the length of an instruction only depends on its pc, so laying the code out
+    // with every branch jumping to itself gives the new pcs
+    let mut layout = ByteWriter::default();
+    let mut pcs = HashMap::new();
+    for (pc, instr) in code {
+        pcs.entry(*pc).or_insert(layout.len() as u32);
+        write_instruction(&mut layout, instr, &|_, pc| Ok(pc))?;
+    }
+    if layout.len() > u16::MAX as usize {
+        return Err(WriteError::TooLarge("bytes of code"));
+    }
+    let pcs = PcMap {
+        pcs,
+        end: (end, layout.len() as u32),
+    };
+
+    let mut out = ByteWriter::default();
+    for (_, instr) in code {
+        write_instruction(&mut out, instr, &|target, _| pcs.get(target))?;
+    }
+    Ok((out.xs, pcs))
+}
+
+/// Encodes `instr` at the end of `out`, with `moved` giving the new pc of a branch
+/// target from the target and the pc of the branch
+fn write_instruction(
+    out: &mut ByteWriter,
+    instr: &Instruction,
+    moved: &dyn Fn(u32, u32) -> Result<u32, WriteError>,
+) -> Result<(), WriteError> {
+    let pc = out.len() as u32;
+    out.write_u1(instr.opcode());
+    match instr {
+        Instruction::Bipush(value) => out.write_u1(*value as u8),
+        Instruction::Sipush(value) => out.write_u2(*value as u16),
+        Instruction::Ldc(index) => out.write_u1(*index),
+        Instruction::LdcW(index)
+        | Instruction::Ldc2W(index)
+        | Instruction::Getstatic(index)
+        | Instruction::Putstatic(index)
+        | Instruction::Getfield(index)
+        | Instruction::Putfield(index)
+        | Instruction::Invokevirtual(index)
+        | Instruction::Invokespecial(index)
+        | Instruction::Invokestatic(index)
+        | Instruction::New(index)
+        | Instruction::Anewarray(index)
+        | Instruction::Checkcast(index)
+        | Instruction::Instanceof(index) => out.write_u2(*index),
+        Instruction::Iload(index)
+        | Instruction::Lload(index)
+        | Instruction::Fload(index)
+        | Instruction::Dload(index)
+        | Instruction::Aload(index)
+        | Instruction::Istore(index)
+        | Instruction::Lstore(index)
+        | Instruction::Fstore(index)
+        | Instruction::Dstore(index)
+        | Instruction::Astore(index)
+        | Instruction::Ret(index) => out.write_u1(*index),
+        Instruction::Iinc(index, constant) => {
+            out.write_u1(*index);
+            out.write_u1(*constant as u8);
+        }
+        Instruction::Ifeq(target)
+        | Instruction::Ifne(target)
+        | Instruction::Iflt(target)
+        | Instruction::Ifge(target)
+        | Instruction::Ifgt(target)
+        | Instruction::Ifle(target)
+        | Instruction::IfIcmpeq(target)
+        | Instruction::IfIcmpne(target)
+        | Instruction::IfIcmplt(target)
+        | Instruction::IfIcmpge(target)
+        | Instruction::IfIcmpgt(target)
+        | Instruction::IfIcmple(target)
+        | Instruction::IfAcmpeq(target)
+        | Instruction::IfAcmpne(target)
+        | Instruction::Goto(target)
+        | Instruction::Jsr(target)
+        | Instruction::Ifnull(target)
+        | Instruction::Ifnonnull(target) => {
+            let target = moved(*target, pc)?;
+            let offset = i16::try_from(target as i64 - pc as i64)
+                .map_err(|_| WriteError::BranchOutOfRange { pc, target })?;
+            out.write_u2(offset as u16);
+        }
+        Instruction::GotoW(target) | Instruction::JsrW(target) => {
+            out.write_u4(branch_offset(pc, moved(*target, pc)?))
+        }
+        Instruction::Tableswitch {
+            default,
+            low,
+            high,
+            targets,
+        } => {
+            write_switch_padding(out, pc);
+            out.write_u4(branch_offset(pc, moved(*default, pc)?));
+            out.write_u4(*low as u32);
+            out.write_u4(*high as u32);
+            for target in targets {
+                out.write_u4(branch_offset(pc, moved(*target, pc)?));
+            }
+        }
+        Instruction::Lookupswitch { default, pairs } => {
+            write_switch_padding(out, pc);
+            out.write_u4(branch_offset(pc, moved(*default, pc)?));
+            out.write_u4(pairs.len() as u32);
+            for (key, target) in pairs {
+                out.write_u4(*key as u32);
+                out.write_u4(branch_offset(pc, moved(*target, pc)?));
+            }
+        }
+        Instruction::Invokeinterface(index, count) => {
+            out.write_u2(*index);
+            out.write_u1(*count);
+            out.write_u1(0);
+        }
+        Instruction::Invokedynamic(index) => {
+            out.write_u2(*index);
+            out.write_u2(0);
+        }
+        Instruction::Newarray(atype) => out.write_u1(*atype as u8),
+        Instruction::Multianewarray(index, dimensions) => {
+            out.write_u2(*index);
+            out.write_u1(*dimensions);
+        }
+        Instruction::Wide(instr) => {
+            out.write_u1(instr.opcode());
+            match instr {
+                WideInstruction::Iinc(index, constant) => {
+                    out.write_u2(*index);
+                    out.write_u2(*constant as u16);
+                }
+                WideInstruction::Iload(index)
+                | WideInstruction::Lload(index)
+                | WideInstruction::Fload(index)
+                | WideInstruction::Dload(index)
+                | WideInstruction::Aload(index)
+                | WideInstruction::Istore(index)
+                | WideInstruction::Lstore(index)
+                | WideInstruction::Fstore(index)
+                | WideInstruction::Dstore(index)
+                | WideInstruction::Astore(index)
+                | WideInstruction::Ret(index) => out.write_u2(*index),
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn branch_offset(pc: u32, target: u32) -> u32 {
+    (target as i64 - pc as i64) as i32 as u32
+}
+
+/// tableswitch and lookupswitch operands start at an address that is a multiple of four
+/// bytes from the start of the code array
+fn write_switch_padding(out: &mut ByteWriter, pc: u32) {
+    for _ in 0..(4 - (pc + 1) % 4) % 4 {
+        out.write_u1(0);
+    }
+}
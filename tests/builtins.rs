@@ -0,0 +1,91 @@
+mod common;
+
+use common::{run_main, vm_with};
+use rusvm::{Value, Vm, VmError};
+
+#[test]
+fn runs_hello_world() {
+    assert_eq!(
+        run_main("Main"),
+        ("Hello, World!\n".to_string(), String::new())
+    );
+}
+
+#[test]
+fn matches_the_jdk_output() {
+    let (stdout, stderr) = run_main("Builtins");
+    let expected = [
+        "Hello from rusvm",
+        "42",
+        "-7",
+        "1.5",
+        "1.0E10",
+        "0.001",
+        "33.333333333333336",
+        "true",
+        "x",
+        "no newline",
+        "sum=5 2.5false",
+        "14",
+        "desserts",
+        "5",
+        "e",
+        "hello world",
+        "true",
+        "99162322",
+        "-122",
+        "ff",
+        "7",
+        "true",
+        "false",
+        "5",
+        "1.4142135623730951",
+        "3.0",
+    ];
+    assert_eq!(stdout.lines().collect::<Vec<_>>(), expected);
+    assert_eq!(stderr, "to stderr\n");
+}
+
+#[test]
+fn natives_throw_java_exceptions() {
+    let mut vm = vm_with(&[]);
+    let string = vm.new_string("12a").unwrap();
+    let result = vm.invoke_static(
+        "java/lang/Integer",
+        "parseInt",
+        "(Ljava/lang/String;)I",
+        vec![string],
+    );
+    assert_eq!(
+        result,
+        Err(VmError::Exception {
+            class: "java/lang/NumberFormatException".to_string(),
+            message: Some("For input string: \"12a\"".to_string()),
+        })
+    );
+    let result = vm.invoke_virtual("length", "()I", vec![Value::Null]);
+    assert_eq!(result, Err(VmError::null_pointer()));
+}
+
+#[test]
+fn small_integers_box_to_the_same_object_across_collections() {
+    let mut vm = vm_with(&[]);
+    let box_int = |vm: &mut Vm, value| {
+        vm.invoke_static(
+            "java/lang/Integer",
+            "valueOf",
+            "(I)Ljava/lang/Integer;",
+            vec![Value::Int(value)],
+        )
+        .unwrap()
+        .unwrap()
+    };
+    let large = box_int(&mut vm, 128);
+    assert_ne!(box_int(&mut vm, 128), large);
+    let small = box_int(&mut vm, -128);
+    assert_ne!(box_int(&mut vm, 127), small);
+    // the cached Integers are kept even though nothing else references them, the two
+    // boxed 128s are not
+    assert_eq!(vm.collect_garbage(), 2);
+    assert_eq!(box_int(&mut vm, -128), small);
+}
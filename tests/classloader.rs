@@ -0,0 +1,129 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::Output;
+use rusvm::{ClassLoader, ClassPathEntry, LoadError, Vm, VmError};
+
+const CLASSPATH: &str = "samples/classpath";
+const JAR: &str = "samples/classpath/util.jar";
+
+fn loader() -> ClassLoader {
+    ClassLoader::new(vec![
+        ClassPathEntry::open(CLASSPATH).unwrap(),
+        ClassPathEntry::open(JAR).unwrap(),
+    ])
+}
+
+#[test]
+fn loads_classes_from_directories_and_jars() {
+    let mut loader = loader();
+    let app = loader.load("shapes/App").unwrap();
+    assert_eq!(app.super_class, "shapes/Base");
+    let strings = loader.load("util/Strings").unwrap();
+    assert!(strings.methods.iter().any(|method| method.name == "shout"));
+}
+
+#[test]
+fn loads_each_class_once() {
+    let mut loader = loader();
+    let first = loader.load("shapes/Base").unwrap();
+    let second = loader.load("shapes/Base").unwrap();
+    assert!(Rc::ptr_eq(&first, &second));
+    assert!(!loader.is_loaded("shapes/App"));
+}
+
+#[test]
+fn reports_missing_and_misplaced_classes() {
+    let mut loader = loader();
+    assert_eq!(
+        loader.load("shapes/Circle").unwrap_err(),
+        LoadError::ClassNotFound("shapes/Circle".to_string())
+    );
+
+    // shapes/App.class found as if it were the top level class App
+    let mut loader = ClassLoader::new(vec![
+        ClassPathEntry::open("samples/classpath/shapes").unwrap()
+    ]);
+    assert_eq!(
+        loader.load("App").unwrap_err(),
+        LoadError::WrongName {
+            expected: "App".to_string(),
+            found: "shapes/App".to_string(),
+        }
+    );
+}
+
+#[test]
+fn rejects_names_leading_out_of_the_classpath() {
+    // samples/Main.class exists, but outside of the classpath directory
+    let entry = ClassPathEntry::open("samples/classpath/shapes").unwrap();
+    for name in [
+        "../../Main",
+        "/etc/passwd",
+        "shapes//App",
+        "./App",
+        "shapes/",
+    ] {
+        assert_eq!(
+            entry.read_class(name),
+            Err(LoadError::InvalidName(name.to_string()))
+        );
+    }
+    assert_eq!(
+        loader().load("../Main").unwrap_err(),
+        LoadError::InvalidName("../Main".to_string())
+    );
+}
+
+#[test]
+fn rejects_archives_that_are_not_zip_files() {
+    let err = ClassPathEntry::open("samples/Main.class").unwrap_err();
+    assert!(matches!(err, LoadError::Zip { .. }), "{err}");
+}
+
+#[test]
+fn classpath_strings_use_the_platform_separator() {
+    let classpath = std::env::join_paths([CLASSPATH, JAR]).unwrap();
+    let mut loader = ClassLoader::from_classpath(classpath.to_str().unwrap()).unwrap();
+    assert!(loader.load("util/Strings").is_ok());
+    assert!(ClassLoader::from_classpath("samples/missing.jar").is_err());
+}
+
+#[test]
+fn vm_loads_superclasses_and_referenced_classes_on_first_use() {
+    let mut vm = Vm::new();
+    let stdout = Output::default();
+    vm.set_stdout(Box::new(stdout.clone()));
+    vm.set_class_loader(loader());
+    vm.run_main("shapes/App").unwrap();
+    assert_eq!(
+        stdout.text(),
+        "a shape with 4 sides!\na shape with 0 sides!\n"
+    );
+    assert!(vm.class_loader().is_loaded("shapes/Base"));
+    assert!(vm.class_loader().is_loaded("util/Strings"));
+    assert!(!vm.class_loader().is_loaded("shapes/Unused"));
+}
+
+#[test]
+fn vm_without_the_class_on_its_classpath() {
+    let mut vm = Vm::new();
+    vm.set_class_loader(ClassLoader::new(vec![
+        ClassPathEntry::open(CLASSPATH).unwrap()
+    ]));
+    assert_eq!(
+        vm.run_main("shapes/App"),
+        Err(VmError::ClassNotFound("util/Strings".to_string()))
+    );
+}
+
+#[test]
+fn reads_stored_and_deflated_entries() {
+    // util.jar deflates its entries, stored.jar has the same class uncompressed
+    let deflated = ClassPathEntry::open(JAR).unwrap();
+    let stored = ClassPathEntry::open("samples/classpath/stored.jar").unwrap();
+    let class = deflated.read_class("util/Strings").unwrap().unwrap();
+    assert_eq!(stored.read_class("util/Strings").unwrap(), Some(class));
+    assert_eq!(stored.read_class("util/Missing").unwrap(), None);
+}
@@ -0,0 +1,62 @@
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use rusvm::{ClassLoader, JavaClassFileParser, Vm};
+
+/// A writer whose content can still be read after handing it to the vm
+#[derive(Clone, Default)]
+pub struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Output {
+    pub fn text(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A vm with the given classes from samples/ added
+pub fn vm_with(classes: &[&str]) -> Vm {
+    let mut vm = Vm::new();
+    for class in classes {
+        let bytes = fs::read(format!("samples/{class}.class")).unwrap();
+        let class = JavaClassFileParser::new(&bytes).parse().unwrap();
+        vm.add_class(class).unwrap();
+    }
+    vm
+}
+
+/// A vm loading the classes of samples/ on demand, with its stdout and stderr
+/// captured
+pub fn vm() -> (Vm, Output, Output) {
+    let mut vm = Vm::new();
+    let (stdout, stderr) = (Output::default(), Output::default());
+    vm.set_stdout(Box::new(stdout.clone()));
+    vm.set_stderr(Box::new(stderr.clone()));
+    vm.set_class_loader(ClassLoader::from_classpath("samples").unwrap());
+    (vm, stdout, stderr)
+}
+
+/// Runs the main method of a class from samples/, returning what it wrote to stdout
+/// and stderr
+pub fn run_main(class: &str) -> (String, String) {
+    let mut vm = vm_with(&[class]);
+    let (stdout, stderr) = (Output::default(), Output::default());
+    vm.set_stdout(Box::new(stdout.clone()));
+    vm.set_stderr(Box::new(stderr.clone()));
+    vm.run_main(class).unwrap();
+    (stdout.text(), stderr.text())
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::vm;
+use rusvm::vm::heap::ObjectData;
+use rusvm::{Value, VmError};
+
+#[test]
+fn matches_the_jdk_output() {
+    let (mut vm, stdout, _) = vm();
+    vm.run_main("Dispatch").unwrap();
+    let expected = [
+        "square<shape> 9",
+        "cube:square<shape> 24",
+        "hello ada",
+        "dear hello diamond",
+        "left+right",
+        "true",
+        "base derived",
+        "42",
+        "legacy",
+        "Receiver class Legacy does not define or inherit an implementation of the \
+         resolved method 'abstract int version()' of interface Plugin.",
+    ];
+    assert_eq!(
+        stdout.text(),
+        expected.map(|line| format!("{line}\n")).concat()
+    );
+}
+
+#[test]
+fn method_tables_hold_inherited_and_default_methods() {
+    let (mut vm, _, _) = vm();
+    let diamond = vm.resolve_class("Dispatch$Diamond").unwrap();
+    let vtable = &vm.class(diamond).vtable;
+    let owner = |name, descriptor| vtable.get(name, descriptor).unwrap().class_name.as_str();
+    assert_eq!(owner("greet", "()Ljava/lang/String;"), "Dispatch$Polite");
+    assert_eq!(owner("side", "()Ljava/lang/String;"), "Dispatch$Diamond");
+    assert_eq!(owner("hashCode", "()I"), "java/lang/Object");
+    assert!(vm
+        .class(diamond)
+        .method("greet", "()Ljava/lang/String;")
+        .is_none());
+}
+
+#[test]
+fn invoke_virtual_selects_the_override() {
+    let (mut vm, _, _) = vm();
+    let cube = vm
+        .new_object("Dispatch$Cube", ObjectData::Instance)
+        .unwrap();
+    let init = vm.find_method("Dispatch$Cube", "<init>", "(I)V").unwrap();
+    vm.invoke(init, vec![cube, Value::Int(3)]).unwrap();
+    let name = vm
+        .invoke_virtual("name", "()Ljava/lang/String;", vec![cube])
+        .unwrap()
+        .unwrap();
+    assert_eq!(vm.java_string(name).unwrap(), "cube:square<shape>");
+}
+
+#[test]
+fn abstract_methods_without_implementation_throw() {
+    let (mut vm, _, _) = vm();
+    let legacy = vm.new_object("Legacy", ObjectData::Instance).unwrap();
+    assert_eq!(
+        vm.invoke_virtual("version", "()I", vec![legacy]),
+        Err(VmError::exception(
+            "java/lang/AbstractMethodError",
+            Some(
+                "Receiver class Legacy does not define or inherit an implementation of the \
+                 resolved method 'abstract int version()' of interface Plugin."
+                    .to_string()
+            )
+        ))
+    );
+}
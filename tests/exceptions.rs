@@ -0,0 +1,131 @@
+mod common;
+
+use common::vm;
+use rusvm::{Value, VmError};
+
+#[test]
+fn matches_the_jdk_output() {
+    let (mut vm, stdout, stderr) = vm();
+    vm.run_main("Exceptions").unwrap();
+    let expected = [
+        "7",
+        "missing 20 20",
+        "finally 7",
+        "3",
+        "finally 1",
+        "java.lang.ArithmeticException: / by zero",
+        "Index 2 out of bounds for length 2",
+        "npe caught",
+        "integer 4",
+        "not an integer",
+        "true false true",
+        "wrapped <- missing 4",
+        "java.lang.ArrayStoreException: java.lang.Integer",
+        "java.lang.Error null",
+        "java.lang.StackOverflowError true",
+    ];
+    assert_eq!(
+        stdout.text(),
+        expected.map(|line| format!("{line}\n")).concat()
+    );
+    assert_eq!(stderr.text(), "");
+}
+
+#[test]
+fn prints_the_stack_trace_of_exceptions_escaping_main() {
+    let (mut vm, stdout, stderr) = vm();
+    assert_eq!(
+        vm.run_main("Uncaught"),
+        Err(VmError::exception(
+            "java/lang/IllegalStateException",
+            Some("could not load".to_string())
+        ))
+    );
+    assert_eq!(stdout.text(), "loading\n");
+    let expected = [
+        "Exception in thread \"main\" java.lang.IllegalStateException: could not load",
+        "\tat Uncaught.load(Uncaught.java:13)",
+        "\tat Uncaught.main(Uncaught.java:19)",
+        "Caused by: java.lang.ArithmeticException: / by zero",
+        "\tat Uncaught.depth(Uncaught.java:4)",
+        "\tat Uncaught.depth(Uncaught.java:6)",
+        "\tat Uncaught.depth(Uncaught.java:6)",
+        "\tat Uncaught.load(Uncaught.java:11)",
+        "\t... 1 more",
+    ];
+    assert_eq!(
+        stderr.text(),
+        expected.map(|line| format!("{line}\n")).concat()
+    );
+}
+
+#[test]
+fn reports_exceptions_thrown_by_invoked_methods() {
+    let (mut vm, _, _) = vm();
+    assert_eq!(
+        vm.invoke_static(
+            "Exceptions",
+            "withdraw",
+            "(II)I",
+            vec![Value::Int(1), Value::Int(5)]
+        ),
+        Err(VmError::exception(
+            "Exceptions$InsufficientFunds",
+            Some("missing 4".to_string())
+        ))
+    );
+    assert_eq!(
+        vm.invoke_static(
+            "Exceptions",
+            "withdraw",
+            "(II)I",
+            vec![Value::Int(5), Value::Int(1)]
+        ),
+        Ok(Some(Value::Int(4)))
+    );
+    // the vm is left without frames and can still be used
+    assert_eq!(
+        vm.invoke_static("Uncaught", "depth", "(I)I", vec![Value::Int(0)]),
+        Err(VmError::exception(
+            "java/lang/ArithmeticException",
+            Some("/ by zero".to_string())
+        ))
+    );
+}
+
+#[test]
+fn deep_recursion_throws_stack_overflow_error() {
+    let (mut vm, _, _) = vm();
+    assert_eq!(
+        vm.invoke_static("Exceptions", "recurse", "()V", vec![]),
+        Err(VmError::exception("java/lang/StackOverflowError", None))
+    );
+    let exceptions = vm.resolve_class("Exceptions").unwrap();
+    assert_eq!(vm.class(exceptions).statics["depth"], Value::Int(4096));
+    assert_eq!(
+        vm.invoke_static(
+            "Exceptions",
+            "divide",
+            "(II)I",
+            vec![Value::Int(6), Value::Int(3)]
+        ),
+        Ok(Some(Value::Int(2)))
+    );
+}
+
+#[test]
+fn checks_catch_types_against_superclasses() {
+    let (mut vm, _, _) = vm();
+    let npe = vm.resolve_class("java/lang/NullPointerException").unwrap();
+    assert!(vm.is_assignable(npe, "java/lang/RuntimeException").unwrap());
+    assert!(vm.is_assignable(npe, "java/lang/Throwable").unwrap());
+    assert!(!vm.is_assignable(npe, "java/lang/Error").unwrap());
+
+    let strings = vm.resolve_class("[[Ljava/lang/String;").unwrap();
+    assert!(vm.is_assignable(strings, "[[Ljava/lang/Object;").unwrap());
+    assert!(vm.is_assignable(strings, "[Ljava/lang/Object;").unwrap());
+    assert!(vm.is_assignable(strings, "java/lang/Cloneable").unwrap());
+    assert!(!vm.is_assignable(strings, "[Ljava/lang/String;").unwrap());
+    let ints = vm.resolve_class("[I").unwrap();
+    assert!(!vm.is_assignable(ints, "[Ljava/lang/Object;").unwrap());
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use common::vm;
+use rusvm::vm::heap::ObjectData;
+use rusvm::{Value, Vm, VmError};
+
+#[test]
+fn matches_the_jdk_output() {
+    let (mut vm, stdout, _) = vm();
+    vm.run_main_with_args("Objects", &["a", "b"]).unwrap();
+    let expected = [
+        "2",
+        "16",
+        "point3d point 2",
+        "30",
+        "-56 0 t 4464",
+        "false true 1099511627776 1.5",
+        "3 4 7",
+        "null b",
+        "rrus",
+        "499500",
+    ];
+    assert_eq!(
+        stdout.text(),
+        expected.map(|line| format!("{line}\n")).concat()
+    );
+}
+
+#[test]
+fn lays_out_inherited_fields_first() {
+    let (mut vm, _, _) = vm();
+    let point = vm.resolve_class("Objects$Point").unwrap();
+    let point3d = vm.resolve_class("Objects$Point3D").unwrap();
+    assert_eq!(vm.class(point).field_defaults.len(), 3);
+    assert_eq!(vm.class(point3d).super_class, Some(point));
+    assert_eq!(vm.class(point3d).field_defaults.len(), 5);
+    assert_eq!(vm.class(point3d).fields["z"], 3);
+    // the shadowing label gets a slot of its own
+    assert_eq!(vm.class(point).fields["label"], 2);
+    assert_eq!(vm.class(point3d).fields["label"], 4);
+    assert_eq!(vm.find_field("Objects$Point3D", "x"), Ok(0));
+}
+
+#[test]
+fn collects_unreachable_objects() {
+    let (mut vm, _, _) = vm();
+    vm.run_main("Objects").unwrap();
+    // 50 lists of 1000 nodes were allocated, collections ran along the way
+    assert!(vm.heap().len() < 10_000, "{}", vm.heap().len());
+
+    vm.collect_garbage();
+    let live = vm.heap().len();
+    let objects = vm.resolve_class("Objects").unwrap();
+    let kept = vm.class(objects).statics["kept"];
+    assert!((1000..1100).contains(&live), "{live}");
+
+    // the list in the static field survived with its contents
+    let mut node = kept;
+    let mut length = 0;
+    while node != Value::Null {
+        length += 1;
+        node = vm.object(node).unwrap().fields[1];
+    }
+    assert_eq!(length, 1000);
+    assert_eq!(vm.collect_garbage(), 0);
+}
+
+#[test]
+fn reports_bad_array_accesses() {
+    let (mut vm, _, _) = vm();
+    let array = vm.new_array("[I", 3).unwrap();
+    let at = |vm: &mut Vm, index| {
+        vm.invoke_static("Objects", "at", "([II)I", vec![array, Value::Int(index)])
+    };
+    assert_eq!(at(&mut vm, 2), Ok(Some(Value::Int(0))));
+    for index in [3, -1] {
+        assert_eq!(
+            at(&mut vm, index),
+            Err(VmError::exception(
+                "java/lang/ArrayIndexOutOfBoundsException",
+                Some(format!("Index {index} out of bounds for length 3")),
+            ))
+        );
+    }
+    assert_eq!(
+        vm.invoke_static("Objects", "x", "(LObjects$Point;)I", vec![Value::Null]),
+        Err(VmError::null_pointer())
+    );
+    assert_eq!(
+        vm.new_array("[J", -2),
+        Err(VmError::exception(
+            "java/lang/NegativeArraySizeException",
+            Some("-2".to_string()),
+        ))
+    );
+}
+
+#[test]
+fn clones_arrays_but_not_other_objects() {
+    let (mut vm, _, _) = vm();
+    let array = vm.new_array("[J", 2).unwrap();
+    vm.array_mut(array).unwrap().set(1, Value::Long(7)).unwrap();
+    let copy = vm
+        .invoke_virtual("clone", "()Ljava/lang/Object;", vec![array])
+        .unwrap()
+        .unwrap();
+    assert_ne!(copy, array);
+    assert_eq!(vm.array(copy), vm.array(array));
+    assert_eq!(vm.class_of(copy.as_object().unwrap().unwrap()), "[J");
+
+    let point = vm
+        .new_object("Objects$Point", ObjectData::Instance)
+        .unwrap();
+    assert_eq!(
+        vm.invoke_virtual("clone", "()Ljava/lang/Object;", vec![point]),
+        Err(VmError::exception(
+            "java/lang/CloneNotSupportedException",
+            Some("Objects$Point".to_string()),
+        ))
+    );
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use common::vm;
+use rusvm::vm::class::InitState;
+use rusvm::{Value, VmError};
+
+#[test]
+fn matches_the_jdk_output() {
+    let (mut vm, stdout, _) = vm();
+    vm.run_main("Init").unwrap();
+    let expected = [
+        "2",
+        "main parent child ",
+        "2 main parent child ",
+        "7 main parent child lazy ",
+        "main parent child lazy greeting greeter ",
+        "null caused by java.lang.ArithmeticException: / by zero",
+        "java.lang.NoClassDefFoundError: Could not initialize class Init$Broken",
+        "java.lang.StackOverflowError: too deep",
+    ];
+    assert_eq!(
+        stdout.text(),
+        expected.map(|line| format!("{line}\n")).concat()
+    );
+}
+
+#[test]
+fn classes_are_initialized_on_first_active_use() {
+    let (mut vm, _, _) = vm();
+    let child = vm.resolve_class("Init$Child").unwrap();
+    let parent = vm.class(child).super_class.unwrap();
+    assert_eq!(vm.class(child).init_state, InitState::Uninitialized);
+    assert_eq!(vm.class(child).statics["LIMIT"], Value::Int(0));
+
+    let value = vm.invoke_static("Init$Child", "read", "()I", vec![]);
+    assert_eq!(value, Ok(Some(Value::Int(2))));
+    assert_eq!(vm.class(parent).init_state, InitState::Initialized);
+    assert_eq!(vm.class(child).init_state, InitState::Initialized);
+    let statics = &vm.class(child).statics;
+    assert_eq!(statics["LIMIT"], Value::Int(100));
+    assert_eq!(statics["BIG"], Value::Long(1 << 40));
+    let name = statics["NAME"];
+    assert_eq!(vm.java_string(name).unwrap(), "child");
+}
+
+#[test]
+fn failed_initialization_is_not_retried() {
+    let (mut vm, _, _) = vm();
+    assert_eq!(
+        vm.invoke_static("Init$Broken", "get", "()I", vec![]),
+        Err(VmError::exception(
+            "java/lang/ExceptionInInitializerError",
+            None
+        ))
+    );
+    let broken = vm.class_id("Init$Broken").unwrap();
+    assert_eq!(vm.class(broken).init_state, InitState::Failed);
+    assert_eq!(
+        vm.invoke_static("Init$Broken", "get", "()I", vec![]),
+        Err(VmError::exception(
+            "java/lang/NoClassDefFoundError",
+            Some("Could not initialize class Init$Broken".to_string())
+        ))
+    );
+}
+
+#[test]
+fn prints_the_cause_of_a_failed_main_class_initialization() {
+    let (mut vm, stdout, stderr) = vm();
+    assert_eq!(
+        vm.run_main("BadInit"),
+        Err(VmError::exception(
+            "java/lang/ExceptionInInitializerError",
+            None
+        ))
+    );
+    assert_eq!(stdout.text(), "");
+    let expected = [
+        "Exception in thread \"main\" java.lang.ExceptionInInitializerError",
+        "Caused by: java.lang.ArrayIndexOutOfBoundsException: Index 3 out of bounds for length 3",
+        "\tat BadInit.build(BadInit.java:6)",
+        "\tat BadInit.<clinit>(BadInit.java:2)",
+    ];
+    assert_eq!(
+        stderr.text(),
+        expected.map(|line| format!("{line}\n")).concat()
+    );
+}
+
+#[test]
+fn static_fields_of_superclasses_hide_those_of_their_superinterfaces() {
+    let (mut vm, stdout, _) = vm();
+    vm.run_main("StaticFields").unwrap();
+    assert_eq!(stdout.text(), "2\n");
+    let owner = vm.find_static_field("StaticFields$B", "X").unwrap();
+    assert_eq!(vm.class(owner).name, "StaticFields$Sup");
+}
+
+#[test]
+fn main_gets_its_args_after_a_collection_in_the_static_initializer() {
+    let (mut vm, stdout, _) = vm();
+    vm.run_main_with_args("GcArgs", &["hello", "world"])
+        .unwrap();
+    assert_eq!(stdout.text(), "hello\nworld\n");
+}
+
+#[test]
+fn static_call_args_survive_a_collection_in_the_static_initializer() {
+    let (mut vm, _, _) = vm();
+    let arg = vm.new_string("kept").unwrap();
+    let value = vm
+        .invoke_static(
+            "GcArgs",
+            "echo",
+            "(Ljava/lang/String;)Ljava/lang/String;",
+            vec![arg],
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(value, arg);
+    assert_eq!(vm.java_string(value).unwrap(), "kept");
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use common::vm_with;
+use rusvm::{Value, Vm, VmError};
+
+fn invoke(vm: &mut Vm, class: &str, name: &str, descriptor: &str, args: &[Value]) -> Value {
+    vm.invoke_static(class, name, descriptor, args.to_vec())
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn computes_main_sum() {
+    let mut vm = vm_with(&["Main"]);
+    let args = [Value::Int(2), Value::Int(3)];
+    assert_eq!(
+        invoke(&mut vm, "Main", "sum", "(II)I", &args),
+        Value::Int(5)
+    );
+}
+
+#[test]
+fn runs_main() {
+    let mut vm = vm_with(&["Arith"]);
+    assert_eq!(vm.run_main("Arith"), Ok(()));
+}
+
+#[test]
+fn int_arithmetic_and_branches() {
+    let mut vm = vm_with(&["Arith"]);
+    let fib = invoke(&mut vm, "Arith", "fib", "(I)I", &[Value::Int(20)]);
+    assert_eq!(fib, Value::Int(6765));
+    let gcd = invoke(
+        &mut vm,
+        "Arith",
+        "gcd",
+        "(II)I",
+        &[Value::Int(1071), Value::Int(462)],
+    );
+    assert_eq!(gcd, Value::Int(21));
+    assert_eq!(
+        invoke(&mut vm, "Arith", "overflow", "()I", &[]),
+        Value::Int(i32::MIN)
+    );
+    for (month, season) in [(1, 0), (4, 1), (8, 2), (11, 3), (12, 0), (13, -1)] {
+        let result = invoke(&mut vm, "Arith", "season", "(I)I", &[Value::Int(month)]);
+        assert_eq!(result, Value::Int(season));
+    }
+}
+
+#[test]
+fn lookupswitch_and_wide_locals() {
+    let mut vm = vm_with(&["Features"]);
+    for (key, result) in [(-100, 1), (7, 2), (100000, 3), (8, 0)] {
+        let value = invoke(&mut vm, "Features", "sparse", "(I)I", &[Value::Int(key)]);
+        assert_eq!(value, Value::Int(result));
+    }
+    assert_eq!(
+        invoke(&mut vm, "Features", "wide", "()I", &[]),
+        Value::Int(1089)
+    );
+}
+
+#[test]
+fn long_float_and_double_arithmetic() {
+    let mut vm = vm_with(&["Arith"]);
+    let factorial = invoke(&mut vm, "Arith", "factorial", "(I)J", &[Value::Int(20)]);
+    assert_eq!(factorial, Value::Long(2432902008176640000));
+    let args = [Value::Float(1.0), Value::Float(2.0), Value::Float(4.0)];
+    let average = invoke(&mut vm, "Arith", "average", "(FFF)F", &args);
+    assert_eq!(average, Value::Float(7.0 / 3.0));
+    let args = [Value::Double(3.0), Value::Double(4.0)];
+    assert_eq!(
+        invoke(&mut vm, "Arith", "norm2", "(DD)D", &args),
+        Value::Double(25.0)
+    );
+    let args = [
+        Value::Int(-77),
+        Value::Long(123456789012),
+        Value::Float(3.9),
+        Value::Double(-2.5),
+    ];
+    let mix = invoke(&mut vm, "Arith", "mix", "(IJFD)J", &args);
+    assert_eq!(mix, Value::Long(-986998694766));
+    let args = [Value::Double(f64::NAN), Value::Double(1.0)];
+    assert_eq!(
+        invoke(&mut vm, "Arith", "compare", "(DD)I", &args),
+        Value::Int(2)
+    );
+}
+
+#[test]
+fn reports_errors() {
+    let mut vm = vm_with(&["Arith"]);
+    let result = vm.invoke_static(
+        "Arith",
+        "divide",
+        "(II)I",
+        vec![Value::Int(1), Value::Int(0)],
+    );
+    assert_eq!(
+        result,
+        Err(VmError::exception(
+            "java/lang/ArithmeticException",
+            Some("/ by zero".to_string())
+        ))
+    );
+    let result = vm.invoke_static("Arith", "fib", "(I)I", vec![]);
+    assert_eq!(
+        result,
+        Err(VmError::ArgumentCount {
+            expected: 1,
+            found: 0
+        })
+    );
+    // the vm is still usable after an error
+    assert_eq!(
+        invoke(&mut vm, "Arith", "fib", "(I)I", &[Value::Int(7)]),
+        Value::Int(13)
+    );
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::vm;
+use rusvm::{Constant, JavaClassFileParser, Utf8, VmError};
+
+#[test]
+fn matches_the_jdk_output() {
+    let (mut vm, stdout, stderr) = vm();
+    vm.run_main("Lambdas").unwrap();
+    let expected = [
+        "hello from a lambda",
+        "add: 7",
+        "shift: 15",
+        "max: 9",
+        "twice: 42",
+        "bound: 101",
+        "length: 6",
+        "boxed length: 4",
+        "box: Box(b) b",
+        "supplied 10",
+        "empty: true false",
+        "wow!",
+        "ababab",
+        "compare: 1",
+        "area: 12.0",
+        "concat: 1099511627776 x true 1.5 null Box(b)",
+        "i=10, i=10, i=10",
+        "caught: from lambda",
+    ];
+    assert_eq!(
+        stdout.text(),
+        expected.map(|line| format!("{line}\n")).concat()
+    );
+    assert_eq!(stderr.text(), "");
+}
+
+#[test]
+fn lambda_classes_are_left_out_of_stack_traces() {
+    let (mut vm, _, stderr) = vm();
+    assert!(vm.run_main("LambdaTrace").is_err());
+    let expected = [
+        "Exception in thread \"main\" java.lang.ArithmeticException: / by zero",
+        "\tat LambdaTrace.lambda$main$0(LambdaTrace.java:5)",
+        "\tat LambdaTrace.main(LambdaTrace.java:6)",
+    ];
+    assert_eq!(
+        stderr.text(),
+        expected.map(|line| format!("{line}\n")).concat()
+    );
+}
+
+#[test]
+fn unknown_bootstrap_methods_throw_bootstrap_method_error() {
+    let bytes = std::fs::read("samples/LambdaTrace.class").unwrap();
+    let mut class = JavaClassFileParser::new(&bytes).parse().unwrap();
+    for constant in &mut class.constant_pool.constants {
+        if *constant == Constant::Utf8(Utf8::new("metafactory")) {
+            *constant = Constant::Utf8(Utf8::new("customFactory"));
+        }
+    }
+    let (mut vm, _, _) = vm();
+    vm.add_class(class).unwrap();
+    assert_eq!(
+        vm.run_main("LambdaTrace"),
+        Err(VmError::exception(
+            "java/lang/BootstrapMethodError",
+            Some(
+                "Bootstrap method java.lang.invoke.LambdaMetafactory.customFactory is not supported"
+                    .to_string()
+            )
+        ))
+    );
+}
@@ -0,0 +1,127 @@
+use rusvm::{Attr, Instruction, JavaClassFile, JavaClassFileParser, Query, QueryResult, QueryType};
+
+/// `public class Point { private int x; public int getX() { return x; } }` as javac
+/// would lay it out, with a line number for `getX` and a SourceFile attribute
+fn point_class() -> Vec<u8> {
+    let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 61];
+    let utf8 = |value: &[u8]| [&[1, 0, value.len() as u8][..], value].concat();
+    let pool = [
+        utf8(b"Point"),
+        vec![7, 0, 1],
+        utf8(b"java/lang/Object"),
+        vec![7, 0, 3],
+        utf8(b"x"),
+        utf8(b"I"),
+        utf8(b"getX"),
+        utf8(b"()I"),
+        utf8(b"Code"),
+        utf8(b"LineNumberTable"),
+        utf8(b"SourceFile"),
+        utf8(b"Point.java"),
+        vec![12, 0, 5, 0, 6],
+        vec![9, 0, 2, 0, 13],
+    ];
+    bytes.extend([0, pool.len() as u8 + 1]);
+    bytes.extend(pool.concat());
+    // ACC_PUBLIC | ACC_SUPER, this_class #2, super_class #4, no interfaces
+    bytes.extend([0, 0x21, 0, 2, 0, 4, 0, 0]);
+    // private int x
+    bytes.extend([0, 1, 0, 0x02, 0, 5, 0, 6, 0, 0]);
+    // public int getX() with its Code attribute
+    bytes.extend([0, 1, 0, 0x01, 0, 7, 0, 8, 0, 1, 0, 9, 0, 0, 0, 29]);
+    // max_stack 1, max_locals 1, aload_0, getfield #14, ireturn, no exception table
+    bytes.extend([0, 1, 0, 1, 0, 0, 0, 5, 0x2A, 0xB4, 0, 14, 0xAC, 0, 0]);
+    // a LineNumberTable mapping pc 0 to line 3
+    bytes.extend([0, 1, 0, 10, 0, 0, 0, 6, 0, 1, 0, 0, 0, 3]);
+    // SourceFile "Point.java"
+    bytes.extend([0, 1, 0, 11, 0, 0, 0, 2, 0, 12]);
+    bytes
+}
+
+fn parse(bytes: &[u8]) -> JavaClassFile {
+    JavaClassFileParser::new(bytes).parse().unwrap()
+}
+
+#[test]
+fn parses_the_class_declaration() {
+    let class = parse(&point_class());
+    assert_eq!(class.java_release(), Some("Java 17"));
+    assert_eq!(class.access_flags, ["ACC_PUBLIC", "ACC_SUPER"]);
+    assert_eq!(class.this_class, "Point");
+    assert_eq!(class.super_class, "java/lang/Object");
+    assert!(class.interfaces.is_empty());
+    assert_eq!(class.constant_pool.constants.len(), 14);
+}
+
+#[test]
+fn parses_fields_and_methods() {
+    let class = parse(&point_class());
+    let field = &class.fields[0];
+    assert_eq!((field.name.as_str(), field.descriptor.as_str()), ("x", "I"));
+    assert_eq!(field.access_flags, ["ACC_PRIVATE"]);
+
+    let method = &class.methods[0];
+    assert_eq!(
+        (method.name.as_str(), method.descriptor.as_str()),
+        ("getX", "()I")
+    );
+    let Attr::Code {
+        max_stack,
+        max_locals,
+        code,
+        attrs,
+        ..
+    } = &method.attrs[0]
+    else {
+        panic!("expected a Code attribute, got {:?}", method.attrs[0]);
+    };
+    assert_eq!((*max_stack, *max_locals), (1, 1));
+    assert_eq!(
+        code,
+        &[
+            (0, Instruction::Aload0),
+            (1, Instruction::Getfield(14)),
+            (4, Instruction::Ireturn),
+        ]
+    );
+    let Attr::LineNumberTable { table, .. } = &attrs[0] else {
+        panic!("expected a LineNumberTable, got {:?}", attrs[0]);
+    };
+    assert_eq!((table[0].start_pc, table[0].line_number), (0, 3));
+}
+
+#[test]
+fn parses_class_attributes() {
+    let class = parse(&point_class());
+    assert!(matches!(
+        &class.attrs[..],
+        [Attr::SourceFile { file, .. }] if file == "Point.java"
+    ));
+}
+
+#[test]
+fn resolves_field_references_in_queries() {
+    let class = parse(&point_class());
+    match Query::new(&class).query(QueryType::QDisassemble("getX".to_string())) {
+        Some(QueryResult::QDisassembly(lines)) => assert_eq!(
+            lines[1],
+            "     1: getfield #14                            // Field Point.x:I"
+        ),
+        _ => panic!("getX was not disassembled"),
+    }
+}
+
+#[test]
+fn reports_where_parsing_stopped() {
+    let mut bytes = point_class();
+    bytes.truncate(bytes.len() - 1);
+    let err = JavaClassFileParser::new(&bytes).parse().unwrap_err();
+    assert_eq!(err.offset, bytes.len() - 1);
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "offset 0x{:x} (attributes): Out of bound, needed 2 bytes but only 1 are left",
+            bytes.len() - 1
+        )
+    );
+}
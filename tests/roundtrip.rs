@@ -0,0 +1,206 @@
+mod common;
+
+use std::fs;
+
+use common::vm;
+use rusvm::writer::classwriter::{write_byte_code, WriteError};
+use rusvm::{Attr, ClassWriter, Instruction, JavaClassFile, JavaClassFileParser, Value, VmError};
+
+fn sample_classes() -> Vec<(String, Vec<u8>)> {
+    let mut classes: Vec<_> = fs::read_dir("samples")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "class"))
+        .map(|path| (path.display().to_string(), fs::read(&path).unwrap()))
+        .collect();
+    classes.sort();
+    classes
+}
+
+#[test]
+fn unmodified_classes_are_written_back_byte_for_byte() {
+    let classes = sample_classes();
+    assert!(!classes.is_empty());
+    for (path, bytes) in classes {
+        let class = JavaClassFileParser::new(&bytes).parse().unwrap();
+        let written = ClassWriter::new().write(&class).unwrap();
+        assert!(written == bytes, "{path} was not written back unchanged");
+    }
+}
+
+#[test]
+fn modified_classes_get_new_constants() {
+    let bytes = fs::read("samples/Main.class").unwrap();
+    let mut class = JavaClassFileParser::new(&bytes).parse().unwrap();
+    let pool_size = class.constant_pool.constants.len();
+    class.methods[2].name = "add".to_string();
+    class.interfaces.push("java/lang/Runnable".to_string());
+
+    let written = ClassWriter::new().write(&class).unwrap();
+    let reparsed = JavaClassFileParser::new(&written).parse().unwrap();
+    assert_eq!(reparsed.methods[2].name, "add");
+    assert_eq!(reparsed.interfaces, ["java/lang/Runnable"]);
+    // Utf8 "add", Utf8 "java/lang/Runnable" and its Class entry
+    assert_eq!(reparsed.constant_pool.constants.len(), pool_size + 3);
+}
+
+/// A class whose pool holds each of its names twice, the class using the second
+/// copies, and whose access flags have reserved bits set
+fn crafted_class() -> Vec<u8> {
+    let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52];
+    let utf8 = |value: &[u8]| [&[1, 0, value.len() as u8][..], value].concat();
+    let pool = [
+        utf8(b"Dup"),
+        vec![7, 0, 1],
+        utf8(b"java/lang/Object"),
+        vec![7, 0, 3],
+        utf8(b"Dup"),
+        vec![7, 0, 5],
+        utf8(b"x"),
+        utf8(b"I"),
+        utf8(b"x"),
+        utf8(b"Custom"),
+        utf8(b"Custom"),
+    ];
+    bytes.extend([0, pool.len() as u8 + 1]);
+    bytes.extend(pool.concat());
+    // ACC_PUBLIC | ACC_SUPER and the reserved 0x0002 and 0x0100
+    bytes.extend([0x01, 0x23]);
+    // this_class #6, super_class #4, no interfaces
+    bytes.extend([0, 6, 0, 4, 0, 0]);
+    // one field: ACC_PRIVATE and the reserved 0x0800, name #9, descriptor #8 and
+    // a Custom attribute named by #11
+    bytes.extend([
+        0, 1, 0x08, 0x02, 0, 9, 0, 8, 0, 1, 0, 11, 0, 0, 0, 2, 0xCA, 0xFE,
+    ]);
+    // no methods and no attributes
+    bytes.extend([0, 0, 0, 0]);
+    bytes
+}
+
+#[test]
+fn duplicate_constants_and_reserved_flags_are_written_back() {
+    let bytes = crafted_class();
+    let class = JavaClassFileParser::new(&bytes).parse().unwrap();
+    assert_eq!(class.this_class, "Dup");
+    assert_eq!(class.access_flags, ["ACC_PUBLIC", "ACC_SUPER"]);
+    assert_eq!(class.access_mask, 0x0123);
+    assert_eq!(class.fields[0].access_flags, ["ACC_PRIVATE"]);
+    assert_eq!(class.fields[0].name_index, 9);
+    assert_eq!(class.fields[0].attrs[0].name_index(), 11);
+
+    let written = ClassWriter::new().write(&class).unwrap();
+    assert_eq!(written, bytes);
+}
+
+#[test]
+fn renamed_members_of_classes_with_duplicates_get_the_first_entry() {
+    let bytes = crafted_class();
+    let mut class = JavaClassFileParser::new(&bytes).parse().unwrap();
+    class.fields[0].name = "Custom".to_string();
+    class.fields[0].access_flags.push("ACC_STATIC".to_string());
+
+    let written = ClassWriter::new().write(&class).unwrap();
+    let reparsed = JavaClassFileParser::new(&written).parse().unwrap();
+    assert_eq!(reparsed.fields[0].name, "Custom");
+    assert_eq!(reparsed.fields[0].name_index, 10);
+    assert_eq!(reparsed.fields[0].access_mask, 0x080A);
+    assert_eq!(
+        reparsed.constant_pool.constants.len(),
+        class.constant_pool.constants.len()
+    );
+}
+
+/// The Code attribute of the method `name` of `class`
+fn code<'a>(class: &'a mut JavaClassFile, name: &str) -> &'a mut Attr {
+    let method = class.methods.iter_mut().find(|m| m.name == name).unwrap();
+    method
+        .attrs
+        .iter_mut()
+        .find(|attr| matches!(attr, Attr::Code { .. }))
+        .unwrap()
+}
+
+#[test]
+fn edited_code_keeps_its_branches_handlers_and_line_numbers() {
+    let bytes = fs::read("samples/Exceptions.class").unwrap();
+    let mut class = JavaClassFileParser::new(&bytes).parse().unwrap();
+    for name in ["withdraw", "divide"] {
+        let Attr::Code { code, .. } = code(&mut class, name) else {
+            unreachable!()
+        };
+        // branches to pc 0 land on the nop, as the first instruction stored with it
+        code.insert(0, (0, Instruction::Nop));
+    }
+
+    let written = ClassWriter::new().write(&class).unwrap();
+    let mut reparsed = JavaClassFileParser::new(&written).parse().unwrap();
+    let Attr::Code { code: withdraw, .. } = code(&mut reparsed, "withdraw").clone() else {
+        unreachable!()
+    };
+    assert_eq!(withdraw[3], (3, Instruction::IfIcmple(17)));
+    assert_eq!(withdraw[11], (17, Instruction::Iload0));
+    let Attr::Code {
+        exception_table,
+        attrs,
+        ..
+    } = code(&mut reparsed, "divide").clone()
+    else {
+        unreachable!()
+    };
+    let handler = &exception_table[0];
+    assert_eq!(
+        (handler.start_pc, handler.end_pc, handler.handler_pc),
+        (0, 5, 32)
+    );
+    let Attr::LineNumberTable { table, .. } = &attrs[0] else {
+        panic!("expected a LineNumberTable, got {:?}", attrs[0]);
+    };
+    let starts: Vec<u16> = table.iter().map(|entry| entry.start_pc).collect();
+    assert_eq!(starts, [0, 5, 30, 32, 58]);
+
+    let (mut vm, _, _) = vm();
+    vm.add_class(reparsed).unwrap();
+    let mut call = |name, a, b| {
+        vm.invoke_static(
+            "Exceptions",
+            name,
+            "(II)I",
+            vec![Value::Int(a), Value::Int(b)],
+        )
+    };
+    assert_eq!(call("withdraw", 5, 1), Ok(Some(Value::Int(4))));
+    assert_eq!(call("divide", 6, 3), Ok(Some(Value::Int(2))));
+    assert_eq!(
+        call("divide", 6, 0),
+        Err(VmError::exception(
+            "java/lang/ArithmeticException",
+            Some("/ by zero".to_string())
+        ))
+    );
+}
+
+#[test]
+fn switches_are_realigned_when_code_moves() {
+    let code = [
+        (0, Instruction::Nop),
+        (
+            1,
+            Instruction::Lookupswitch {
+                default: 20,
+                pairs: vec![(7, 20)],
+            },
+        ),
+        (20, Instruction::Return),
+    ];
+    let written = write_byte_code(&code[1..]).unwrap();
+    // without the nop, padded by 3 bytes instead of 2, the return stays at pc 20
+    assert_eq!(&written[..8], [0xAB, 0, 0, 0, 0, 0, 0, 20]);
+    assert_eq!(written.len(), 21);
+}
+
+#[test]
+fn branches_must_target_an_instruction() {
+    let code = [(0, Instruction::Goto(2)), (3, Instruction::Return)];
+    assert_eq!(write_byte_code(&code), Err(WriteError::UnknownPc(2)));
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::vm;
+use rusvm::Value;
+
+#[test]
+fn matches_the_jdk_output() {
+    let (mut vm, stdout, _) = vm();
+    vm.run_main("Strings").unwrap();
+    let expected = [
+        "true false",
+        "true true",
+        "false true",
+        "99162322 99162322 0",
+        "héllo wörld ✓ 😀 16",
+        "233 55357 56832",
+        "3 0",
+        "hello, world true",
+        "jvm x12.5truenull",
+        "0,1,2, 6",
+        "go halt unknown",
+    ];
+    assert_eq!(
+        stdout.text(),
+        expected.map(|line| format!("{line}\n")).concat()
+    );
+}
+
+#[test]
+fn interns_strings_by_content() {
+    let (mut vm, _, _) = vm();
+    let interned = vm.interned_string("rusvm").unwrap();
+    assert_eq!(vm.interned_string("rusvm"), Ok(interned));
+    assert_ne!(vm.interned_string("jvm"), Ok(interned));
+
+    let copy = vm.new_string("rusvm").unwrap();
+    assert_ne!(copy, interned);
+    assert_eq!(vm.intern(copy), Ok(interned));
+    let other = vm.new_string("other").unwrap();
+    assert_eq!(vm.intern(other), Ok(other));
+    assert_eq!(vm.interned_string("other"), Ok(other));
+}
+
+#[test]
+fn unreachable_interned_strings_are_collected() {
+    let (mut vm, _, _) = vm();
+    let Value::Ref(interned) = vm.interned_string("temporary").unwrap() else {
+        panic!("strings are references");
+    };
+    assert_eq!(vm.collect_garbage(), 1);
+    assert!(!vm.heap().is_live(interned));
+    let string = vm.interned_string("temporary").unwrap();
+    assert_eq!(vm.java_string(string).unwrap(), "temporary");
+}
+
+#[test]
+fn literals_keep_unpaired_surrogates() {
+    let (mut vm, _, _) = vm();
+    let lone = vm
+        .invoke_static("Strings", "lone", "()Ljava/lang/String;", vec![])
+        .unwrap()
+        .unwrap();
+    assert_eq!(vm.chars(lone).unwrap(), [0xD800, 'x' as u16]);
+    assert_eq!(vm.interned_chars(vec![0xD800, 'x' as u16]), Ok(lone));
+    assert_ne!(vm.interned_string("\u{fffd}x"), Ok(lone));
+}
+
+#[test]
+fn concatenation_keeps_unpaired_surrogates() {
+    let (mut vm, _, _) = vm();
+    let concat = vm
+        .invoke_static(
+            "Strings",
+            "loneConcat",
+            "(I)Ljava/lang/String;",
+            vec![Value::Int(7)],
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(vm.chars(concat).unwrap(), [0xDC00, '7' as u16, 1, 0xD800]);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::vm;
+use rusvm::VmError;
+
+#[test]
+fn matches_the_jdk_output() {
+    let (mut vm, stdout, stderr) = vm();
+    vm.run_main("Threads").unwrap();
+    let expected = [
+        "consumed 210",
+        "alive false false",
+        "count 4000 4000",
+        "woke fast medium slow",
+        "main Thread-4 false",
+        "Thread[Thread-4,5,] false",
+        "notify: current thread is not owner",
+        "wait: current thread is not owner",
+        "wait timed out",
+        "started twice",
+        "done",
+    ];
+    assert_eq!(
+        stdout.text(),
+        expected.map(|line| format!("{line}\n")).concat()
+    );
+    // an exception escaping a thread other than main only ends that thread
+    assert_eq!(
+        stderr.text(),
+        "Exception in thread \"failing\" java.lang.IllegalStateException: boom\n\
+         \tat Threads$Failing.run(Threads.java:79)\n"
+    );
+}
+
+#[test]
+fn producer_and_consumer_interleave_the_same_on_every_run() {
+    let mut expected = vec![];
+    for square in [1, 4, 9, 16, 25] {
+        expected.push(format!("producer put {square}\n"));
+        expected.push(format!("consumer took {square}\n"));
+    }
+    expected.push("sum 55\n".to_string());
+    for _ in 0..3 {
+        let (mut vm, stdout, _) = vm();
+        vm.run_main("ProducerConsumer").unwrap();
+        assert_eq!(stdout.text(), expected.concat());
+    }
+}
+
+#[test]
+fn reports_blocking_in_a_static_initializer_as_unsupported() {
+    // a JVM prints "released" then "initialized", the main thread can't be switched out
+    // while it runs the static initializer of NestedBlocking$Late
+    let (mut vm, stdout, _) = vm();
+    assert_eq!(
+        vm.run_main("NestedBlocking"),
+        Err(VmError::BlockedInNestedCall)
+    );
+    assert_eq!(stdout.text(), "");
+}
+
+#[test]
+fn reports_threads_waiting_for_each_other() {
+    let (mut vm, stdout, _) = vm();
+    assert_eq!(vm.run_main("Deadlock"), Err(VmError::Deadlock));
+    assert_eq!(stdout.text(), "");
+}